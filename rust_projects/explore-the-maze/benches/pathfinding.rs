@@ -0,0 +1,52 @@
+// pathfinding on the full 80x43 maze - run with: cargo bench
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+use explore_the_maze::models::generators::*;
+use explore_the_maze::models::maze::*;
+use explore_the_maze::models::pathfinding::*;
+use explore_the_maze::models::util::*;
+
+// an empty maze (only the border) - the most tiles to search
+fn open_maze() -> Maze {
+    let mut maze = vec![vec![Tile::empty(); MAZE_HEIGHT as usize]; MAZE_WIDTH as usize];
+    for column in &mut maze {
+        column[0] = Tile::wall();
+        column[(MAZE_HEIGHT - 1) as usize] = Tile::wall();
+    }
+    maze[0] = vec![Tile::wall(); MAZE_HEIGHT as usize];
+    maze[(MAZE_WIDTH - 1) as usize] = vec![Tile::wall(); MAZE_HEIGHT as usize];
+    maze
+}
+
+fn pathfinding(c: &mut Criterion) {
+    let corner = (1, 1);
+    let far_corner = (MAZE_WIDTH - 2, MAZE_HEIGHT - 2);
+    let open = open_maze();
+
+    c.bench_function("a* open maze corner to corner", |b| {
+        b.iter(|| find_path(black_box(corner), black_box(far_corner), &open, &[], false))
+    });
+
+    c.bench_function("dijkstra map open maze", |b| {
+        b.iter(|| DijkstraMap::new(black_box(&[corner]), &open, &[], false))
+    });
+
+    // a generated floor - the first and the last region are usually far apart
+    for generator in [Generator::Rooms, Generator::Caves] {
+        let Layout { maze, regions } = generator.generate(&mut rng_from_seed(1));
+        let start = regions[0].center;
+        let target = regions[regions.len() - 1].center;
+
+        c.bench_function(&format!("a* {:?} first to last region", generator), |b| {
+            b.iter(|| find_path(black_box(start), black_box(target), &maze, &[], false))
+        });
+
+        c.bench_function(&format!("dijkstra map {:?}", generator), |b| {
+            b.iter(|| DijkstraMap::new(black_box(&[start]), &maze, &[], false))
+        });
+    }
+}
+
+criterion_group!(benches, pathfinding);
+criterion_main!(benches);
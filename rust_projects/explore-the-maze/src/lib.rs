@@ -0,0 +1,3 @@
+// register modules in the crate
+// the game logic lives in the library, so it can be played in a window (main.rs) or headless (tests)
+pub mod models;
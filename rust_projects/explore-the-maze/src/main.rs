@@ -1,376 +1,930 @@
+use std::io::{self, BufRead};
 use tcod::colors::*;
-use tcod::console::*;
-use tcod::map::{Map};
 
-// register modules in the crate
-mod models;
+// import modules from the library crate (lib.rs)
+use explore_the_maze::models::maze::*;
+use explore_the_maze::models::messages::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::tcod_db::*;
+use explore_the_maze::models::definitions::*;
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::experience::*;
+use explore_the_maze::models::generators::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::item::*;
+use explore_the_maze::models::keys::*;
+use explore_the_maze::models::ranged::*;
+use explore_the_maze::models::replay::*;
+use explore_the_maze::models::save::*;
+use explore_the_maze::models::render::*;
+use explore_the_maze::models::tcod_backend::*;
+use explore_the_maze::models::terminal_backend::*;
+use explore_the_maze::models::travel::*;
+
+use explore_the_maze::models::entity::PlayerAction::{DidntTakeTurn, Exit};
 
-// import modules from crate
-use crate::models::maze::*;
-use crate::models::entity::*;
-use crate::models::tcod_db::*;
+/// @title handle_player_actions
+/// @author GeorgiKostadinovPro
+/// @notice keyboard handling fn
+/// @dev custom fn to turn a key press into a game command and play the turn (engine.rs)
+fn handle_player_actions(gui: &mut Gui, keys: &KeyBindings, game: &mut Game, entities: &mut Vec<Entity>) -> PlayerAction {
+    // no event this frame => nothing to do, the game loop renders again
+    // left click on an explored tile => travel there
+    let key = match gui.backend.check_for_event() {
+        Some(Event::Key(key)) => key,
+        Some(Event::Mouse(mouse)) => {
+            let (x, y) = (mouse.x, mouse.y);
+
+            // the panel describes the tile under the mouse (nothing outside the maze)
+            gui.look_at = (x < MAZE_WIDTH && y < MAZE_HEIGHT).then_some((x, y));
+
+            let is_explored = x < MAZE_WIDTH && y < MAZE_HEIGHT && game.maze[x as usize][y as usize].is_explored;
+            if !mouse.left || !is_explored {
+                return DidntTakeTurn;
+            }
+            return walk_and_render(gui, Command::Travel(x, y), game, entities);
+        }
+        None => return DidntTakeTurn
+    };
 
-use crate::models::entity::PlayerAction::{TookTurn, DidntTakeTurn, Exit};
+    // Alt+Enter toggles the full screen whatever the key bindings say
+    // toggle screen and exit - work whether player is alive/dead
+    if key.is("enter") && key.alt {
+        gui.backend.toggle_fullscreen();
+        // this does not count as player action
+        return DidntTakeTurn;
+    }
 
-/// @title render_bar
+    // every other key does what it is bound to (keys.rs), the help screen (?) lists them
+    // for movement - the game checks that the player is alive
+    let Some(action) = key.name.as_deref().and_then(|name| keys.action(name)) else {
+        return DidntTakeTurn;
+    };
+
+    let command = match action {
+        Action::Exit => Command::Exit,
+        Action::Wait => Command::Wait,
+        Action::PickUp => Command::PickUp,
+        Action::Search => Command::Search,
+        Action::Close => Command::Close,
+        Action::Descend => Command::Descend,
+        Action::Climb => Command::Climb,
+        // shadowcasting -> symmetric -> permissive and round again
+        Action::Fov => Command::Fov(game.fov_algorithm.next()),
+        Action::Explore => {
+            return walk_and_render(gui, Command::Explore, game, entities);
+        }
+        Action::Look => {
+            look_mode(gui, game, entities);
+            return DidntTakeTurn;
+        }
+        Action::Messages => {
+            // only shows the old messages, does not take a turn
+            message_log(&game.messages, gui);
+            return DidntTakeTurn;
+        }
+        Action::Character => {
+            // only shows information, does not take a turn
+            character_sheet(game, entities, gui);
+            return DidntTakeTurn;
+        }
+        Action::Help => {
+            help_screen(keys, gui);
+            return DidntTakeTurn;
+        }
+        Action::Inventory => {
+            // show the inventory: if an item is selected, use it
+            let inventory_index = inventory_menu(
+                &game.inventory,
+                "Press the key next to an item to use it, or any other to cancel.\n",
+                gui,
+            );
+
+            let inventory_index = match inventory_index {
+                Some(inventory_index) => inventory_index,
+                None => return DidntTakeTurn
+            };
+
+            // aimed items (fireball, confusion) need a target tile first
+            match game.inventory[inventory_index].item {
+                Some(item) if needs_target(item) => {
+                    match target_tile(gui, game, entities, target_range(item)) {
+                        Some(target) => Command::UseItem(inventory_index, Some(target)),
+                        None => {
+                            game.messages.add("Cancelled", WHITE, MessageCategory::System);
+                            return DidntTakeTurn;
+                        }
+                    }
+                }
+                _ => Command::UseItem(inventory_index, None)
+            }
+        }
+        Action::Fire => {
+            // the bow shoots at a tile in range, the game checks the bow and the arrows
+            match target_tile(gui, game, entities, Some(BOW_RANGE as f32)) {
+                Some((x, y)) => Command::Fire(x, y),
+                None => {
+                    game.messages.add("Cancelled", WHITE, MessageCategory::System);
+                    return DidntTakeTurn;
+                }
+            }
+        }
+        // the rest are the 8 directions
+        direction => match direction.direction() {
+            Some((dx, dy)) => Command::Move(dx, dy),
+            None => return DidntTakeTurn
+        }
+    };
+
+    play_turn(command, game, entities)
+}
+
+/// @title animate_projectiles
 /// @author GeorgiKostadinovPro
-/// @notice render the bar in GUI panel (HP, EXP, etc)
-/// @dev custom fn to render a bar in the GUI panel under the maze to display HP, EXP, etc
-fn render_bar(
-    panel: &mut Offscreen,
-    x: i32,
-    y: i32,
-    total_width: i32,
-    name: &str,
-    value: i32,
-    maximum: i32,
-    bar_color: Color,
-    back_color: Color
-) {
-    // render a bar (HP, experience, etc). First calculate the width of the bar
-    let bar_width = (value as f32 / maximum as f32 * total_width as f32) as i32;
-
-    // render the background bar
-    panel.set_default_background(back_color);
-    panel.rect(x, y, total_width, 1, false, BackgroundFlag::Screen);
-
-    // now render the bar on top
-    // bar can change e.g. HP decreases due to monster attack
-    panel.set_default_background(bar_color);
-    if bar_width > 0 {
-        panel.rect(x, y, bar_width, 1, false, BackgroundFlag::Screen);
+/// @notice show the arrows and daggers flying
+/// @dev custom fn to draw every projectile of the last turn tile by tile over the maze (ranged.rs)
+/// only the tiles in the player's FOV are drawn - a shot from the dark is only heard of in the messages
+fn animate_projectiles(gui: &mut Gui, game: &Game, entities: &[Entity]) {
+    let delay = std::time::Duration::from_millis(PROJECTILE_DELAY);
+
+    for projectile in &game.projectiles {
+        for &(x, y) in projectile.path.iter().filter(|&&(x, y)| game.fov.is_in_fov(x, y)) {
+            gui.offscreen.clear();
+            render_game(gui, game, entities);
+
+            // over the maze already drawn, then shown again
+            gui.offscreen.put_char(x, y, projectile.glyph, projectile.color);
+            gui.root.blit(&gui.offscreen, 0, 0, 1.0);
+            gui.flush();
+            std::thread::sleep(delay);
+        }
     }
+}
+
+/// @title walk_and_render
+/// @author GeorgiKostadinovPro
+/// @notice walk the player step by step (explore, travel)
+/// @dev custom fn to walk to the destination of the command, drawing every step - any key press stops the walk
+fn walk_and_render(gui: &mut Gui, command: Command, game: &mut Game, entities: &mut Vec<Entity>) -> PlayerAction {
+    let Some(destination) = command.destination() else {
+        return play_turn(command, game, entities);
+    };
+
+    let turns = walk(destination, game, entities, |game, entities| {
+        gui.offscreen.clear();
+        render_game(gui, game, entities);
+        gui.flush();
+        !matches!(gui.backend.check_for_event(), Some(Event::Key(_)))
+    });
+
+    if turns > 0 { PlayerAction::TookTurn } else { DidntTakeTurn }
+}
+
+/// @title help_screen
+/// @author GeorgiKostadinovPro
+/// @notice show what every key does
+/// @dev custom fn to list the active key bindings (preset and the file) in a message box, any key closes it
+fn help_screen(keys: &KeyBindings, gui: &mut Gui) {
+    let text = format!("Keys\n\n{}\n", keys.help().join("\n"));
+    msgbox(&text, HELP_SCREEN_WIDTH, gui);
+}
 
-    // value and max will be shown above the bar for extra clarity
-    // a caption will also be presented to indicate if the bar is HP, EXP, etc
-    panel.set_default_foreground(WHITE);
-    panel.print_ex(
-        x + total_width / 2,
-        y,
-        BackgroundFlag::None,
-        TextAlignment::Center,
-        &format!("{}: {}/{}", name, value, maximum),
+/// @title target_tile
+/// @author GeorgiKostadinovPro
+/// @notice targeting mode - let the player pick a tile
+/// @dev custom fn to move a cursor with the mouse or the arrows and pick a tile in FOV (and range)
+/// left click / Enter picks the tile, right click / Escape cancels (None)
+fn target_tile(
+    gui: &mut Gui,
+    game: &mut Game,
+    entities: &[Entity],
+    max_range: Option<f32>
+) -> Option<(i32, i32)> {
+    game.messages.add(
+        "Left-click or press Enter on a target tile, right-click or Escape to cancel.",
+        LIGHT_CYAN,
+        MessageCategory::System,
     );
+
+    // the cursor starts on the player
+    let (mut x, mut y) = entities[PLAYER].get_pos();
+
+    while !gui.backend.window_closed() {
+        let is_valid = is_valid_target(x, y, max_range, game, entities);
+        gui.look_at = Some((x, y));
+
+        // render the screen with the cursor on top of the maze
+        gui.offscreen.clear();
+        render_game(gui, game, entities);
+        let cursor_color = if is_valid { LIGHT_GREEN } else { LIGHT_RED };
+        gui.root.set_background(x, y, cursor_color);
+        gui.flush();
+
+        match gui.backend.check_for_event() {
+            Some(Event::Mouse(mouse)) => {
+                // the cursor follows the mouse (only inside the maze)
+                if mouse.x < MAZE_WIDTH && mouse.y < MAZE_HEIGHT {
+                    x = mouse.x;
+                    y = mouse.y;
+                }
+
+                if mouse.right {
+                    return None;
+                }
+
+                if mouse.left && is_valid_target(x, y, max_range, game, entities) {
+                    return Some((x, y));
+                }
+            }
+            Some(Event::Key(key)) => match key.name.as_deref() {
+                Some("escape") => return None,
+                Some("enter" | "kpenter") if is_valid => return Some((x, y)),
+                Some("up") => y = (y - 1).max(0),
+                Some("down") => y = (y + 1).min(MAZE_HEIGHT - 1),
+                Some("left") => x = (x - 1).max(0),
+                Some("right") => x = (x + 1).min(MAZE_WIDTH - 1),
+                _ => {}
+            },
+            None => {}
+        }
+    }
+
+    None
 }
 
-/// @titgame
+/// @title look_mode
 /// @author GeorgiKostadinovPro
-/// @notice render the whole maze with its elements and entities
-/// @dev custom fn to render a custom jagged maze with its elements and entities
-pub fn render_game(tcod: &mut Tcod, game: &mut Game, entities: &[Entity], fov_recompute: bool) {
-    if fov_recompute {
-        // recompute FOV if needed (the player has moved)
-        // move fov with the player
-        let player = &entities[0];
-
-        tcod.fov
-            .compute_fov(player.x, player.y, TORCH_RADIUS, FOV_LIGHT_WALLS, FOV_ALGO);
+/// @notice look around the maze without a mouse
+/// @dev custom fn to move a cursor with the arrows and describe the tile under it in the panel
+/// Escape, Enter or v leaves the look mode, it never takes a turn
+fn look_mode(gui: &mut Gui, game: &Game, entities: &[Entity]) {
+    // the cursor starts on the player
+    let (mut x, mut y) = entities[PLAYER].get_pos();
+
+    while !gui.backend.window_closed() {
+        gui.look_at = Some((x, y));
+
+        gui.offscreen.clear();
+        render_game(gui, game, entities);
+        gui.root.set_background(x, y, LIGHT_CYAN);
+        gui.flush();
+
+        let key = gui.backend.wait_for_keypress();
+        match key.name.as_deref() {
+            Some("escape" | "enter" | "kpenter" | "v") => break,
+            Some("up") => y = (y - 1).max(0),
+            Some("down") => y = (y + 1).min(MAZE_HEIGHT - 1),
+            Some("left") => x = (x - 1).max(0),
+            Some("right") => x = (x + 1).min(MAZE_WIDTH - 1),
+            _ => {}
+        }
     }
 
-    // go through all tiles, and set their background color
-    // visit each inner vector
-    for x in 0..MAZE_WIDTH {
-        // visit each element in vector
-        for y in 0..MAZE_HEIGHT {
-            // check if location is visible
-            let is_visible = tcod.fov.is_in_fov(x, y);
-
-            // if view is blocked then this is a wall
-            let is_wall = game.maze[x as usize][y as usize].block_sight;
-
-            // if explored only then color the tile, all other tiles are not visible
-            let is_explored = &mut game.maze[x as usize][y as usize].is_explored;
-
-            // if wall or ground is visible then lighten them
-            // otherwise is not visible set dark colors
-            let color = match (is_visible, is_wall) {
-                // outside of field of view:
-                (false, true) => COLOR_DARK_WALL,
-                (false, false) => COLOR_DARK_GROUND,
-                // inside fov:
-                (true, true) => COLOR_LIGHT_WALL,
-                (true, false) => COLOR_LIGHT_GROUND
-            };
+    gui.look_at = None;
+}
 
-            if is_visible {
-                // visible tiles are explored tiles
-                *is_explored = true;
-            }
+/// @title read_seed
+/// @author GeorgiKostadinovPro
+/// @notice read the maze seed from the command line
+/// @dev custom fn to read --seed <u64> from the args, None if missing (every new game rolls a random seed)
+/// an error if the seed after it is missing or not a number
+fn read_seed(args: &[String]) -> Result<Option<u64>, String> {
+    args.iter().position(|arg| arg == "--seed").map(|i| {
+        args.get(i + 1)
+            .and_then(|seed| seed.parse().ok())
+            .ok_or_else(|| "--seed expects an unsigned number e.g. --seed 42".to_string())
+    }).transpose()
+}
 
-            // only show explored tiles (any visible tile is explored already)
-            // if tile is not explored or yet to be explored then do not color it
-            // tiles are black until explored
-            if *is_explored {
-                tcod.offscreen
-                    .set_char_background(x, y, color, BackgroundFlag::Set);
-            }
+/// @title read_generator
+/// @author GeorgiKostadinovPro
+/// @notice read the maze generator from the command line
+/// @dev custom fn to read --generator <rooms|bsp|caves|drunkard> from the args, None if missing (it changes with the depth)
+/// an error if the generator after it is missing or unknown
+fn read_generator(args: &[String]) -> Result<Option<Generator>, String> {
+    args.iter().position(|arg| arg == "--generator").map(|i| {
+        args.get(i + 1)
+            .ok_or_else(|| "--generator expects rooms, bsp, caves or drunkard".to_string())
+            .and_then(|generator| generator.parse())
+    }).transpose()
+}
+
+/// @title read_backend
+/// @author GeorgiKostadinovPro
+/// @notice read what the game is shown with from the command line
+/// @dev custom fn to read --backend <tcod|terminal> from the args, the libtcod window if missing
+/// an error if the backend after it is missing or unknown
+fn read_backend(args: &[String]) -> Result<BackendKind, String> {
+    args.iter().position(|arg| arg == "--backend").map_or(Ok(BackendKind::Tcod), |i| {
+        args.get(i + 1)
+            .ok_or_else(|| "--backend expects tcod or terminal".to_string())
+            .and_then(|backend| backend.parse())
+    })
+}
+
+/// @title read_file_arg
+/// @author GeorgiKostadinovPro
+/// @notice read a file name from the command line
+/// @dev custom fn to read the file after a flag e.g. --record <file>, None if the flag is missing
+/// an error if the file after it is missing
+fn read_file_arg(args: &[String], flag: &str) -> Result<Option<String>, String> {
+    args.iter().position(|arg| arg == flag).map(|i| {
+        args.get(i + 1)
+            .cloned()
+            .ok_or_else(|| format!("{} expects a file e.g. {} game.replay", flag, flag))
+    }).transpose()
+}
+
+/// @title read_replay_speed
+/// @author GeorgiKostadinovPro
+/// @notice read how fast a replay is played back in the window
+/// @dev custom fn to read --replay-speed <turns a second> from the args, REPLAY_SPEED if missing
+/// an error if the speed after it is missing or not a number more than 0
+fn read_replay_speed(args: &[String]) -> Result<u32, String> {
+    args.iter().position(|arg| arg == "--replay-speed").map_or(Ok(REPLAY_SPEED), |i| {
+        args.get(i + 1)
+            .and_then(|speed| speed.parse().ok())
+            .filter(|&speed| speed > 0)
+            .ok_or_else(|| "--replay-speed expects a number of turns a second e.g. --replay-speed 50".to_string())
+    })
+}
+
+/// @title run_headless
+/// @author GeorgiKostadinovPro
+/// @notice play the game without a window
+/// @dev custom fn to read one command per line from stdin (up, down, left, right, descend, exit, ...) and print the messages
+/// record - the replay file the game is recorded to (--record), written at the end
+fn run_headless(seed: u64, definitions: Definitions, generator: Option<Generator>, record: Option<&str>) {
+    let mut simulation = Simulation::with_options(seed, definitions, generator);
+    if record.is_some() {
+        start_recording(&mut simulation.game);
+    }
+
+    // print only the new messages after each command
+    let mut printed = 0;
+    let mut print_new_messages = |game: &Game| {
+        for message in game.messages.since(printed) {
+            println!("{}", message.text);
         }
-    }   
+        printed = game.messages.total();
+    };
+
+    print_new_messages(&simulation.game);
 
-    // draw all entities in the list
-    // if entity is in FOV then draw it
-    // if entity is dead then allow player to go over it
-    let mut to_draw: Vec<_> = entities.iter().collect();
+    for line in std::io::stdin().lock().lines() {
+        let line = line.expect("failed to read the commands from stdin");
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let command: Command = match line.parse() {
+            Ok(command) => command,
+            Err(err) => {
+                eprintln!("{}", err);
+                continue;
+            }
+        };
 
-    // sort so that non-blocking objects come first
-    // a player will be drawn above the dead monster
-    to_draw.sort_by(|e1, e2| { e1.is_blocking.cmp(&e2.is_blocking) });
+        let player_action = simulation.step(command);
+        print_new_messages(&simulation.game);
 
-    for entity in &to_draw {
-        if tcod.fov.is_in_fov(entity.x, entity.y) {
-            entity.draw(&mut tcod.offscreen);
+        if player_action == Exit || simulation.is_over() {
+            break;
         }
     }
 
-    // blit the contents of "offscreen" to the root console and present it
-    // blit(from, start coo, width and height of area to blit, to, start blit from coo, transparency)
-    // From now on, the offscreen console Entity will represent only the map
-    blit(&tcod.offscreen, (0, 0), (MAZE_WIDTH, MAZE_HEIGHT), &mut tcod.root, (0, 0), 1.0, 1.0);
-
-    // re-initialize the gui panel to black, call render_bar to display the player’s HP, 
-    // then show the panel on the root console
-    // prepare to render the GUI panel
-    tcod.gui_panel.set_default_background(BLACK);
-    tcod.gui_panel.clear();
-
-    // show the player's stats
-    let hp = entities[PLAYER].fighter.map_or(0, |f| f.hp);
-    let max_hp = entities[PLAYER].fighter.map_or(0, |f| f.max_hp);
-
-    render_bar(
-        &mut tcod.gui_panel,
-        1,
-        1,
-        BAR_WIDTH,
-        "HP",
-        hp,
-        max_hp,
-        LIGHT_RED,
-        DARKER_RED,
+    let (x, y) = simulation.player().get_pos();
+    let hp = simulation.player().fighter.map_or(0, |f| f.hp);
+    println!(
+        "seed: {}, turns: {}, dungeon level: {}, player at ({}, {}), hp: {}, level: {}",
+        seed, simulation.turns, simulation.game.depth, x, y, hp, simulation.player().level
     );
 
-    // print the game messages, one line at a time
-    let mut y = MSG_HEIGHT as i32;
-    for &(ref msg, color) in game.messages.messages.iter().rev() {
-        let msg_height = tcod.gui_panel.get_height_rect(MSG_X, y, MSG_WIDTH, 0, msg);
-        y -= msg_height;
+    if let Some(path) = record
+        && let Some(replay) = Replay::from_game(&simulation.game, &simulation.entities)
+        && let Err(err) = save_replay(path, &replay)
+    {
+        eprintln!("Could not save the replay: {}", err);
+    }
+}
 
-        // y < 0 => draw above the gui panel => tcod does not allow
-        // since we run out of space stop printing messages
-        if y < 0 {
-            break;
+/// @title replay_headless
+/// @author GeorgiKostadinovPro
+/// @notice play a recorded game back without a window
+/// @dev custom fn to play every command of the replay as fast as possible, print the messages and check the checksum
+/// a replay that does not end like the recorded game exits with an error (e.g. for a bug report)
+fn replay_headless(replay: &Replay, definitions: Definitions) {
+    let mut printed = 0;
+    let (game, entities) = play_back(replay, definitions, |game, _| {
+        for message in game.messages.since(printed) {
+            println!("{}", message.text);
         }
+        printed = game.messages.total();
+        true
+    });
+
+    match verify_replay(replay, &game, &entities) {
+        Ok(()) => println!("The replay of {} commands matches the recorded game.", replay.commands.len()),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
 
-        tcod.gui_panel.set_default_foreground(color);
-        tcod.gui_panel.print_rect(MSG_X, y, MSG_WIDTH, 0, msg);
+/// @title watch_replay
+/// @author GeorgiKostadinovPro
+/// @notice play a recorded game back in the window
+/// @dev custom fn to draw the replay turn by turn, speed turns a second - Escape stops it
+/// the checksum is checked at the end and shown in a message box
+fn watch_replay(gui: &mut Gui, replay: &Replay, definitions: Definitions, speed: u32) {
+    let delay = std::time::Duration::from_millis(1000 / speed as u64);
+    let mut is_stopped = false;
+
+    let (game, entities) = play_back(replay, definitions, |game, entities| {
+        gui.offscreen.clear();
+        render_game(gui, game, entities);
+        gui.flush();
+        std::thread::sleep(delay);
+
+        let is_escape = matches!(gui.backend.check_for_event(), Some(Event::Key(key)) if key.is("escape"));
+        is_stopped = is_escape || gui.backend.window_closed();
+        !is_stopped
+    });
+
+    if is_stopped {
+        return;
     }
 
-    // blit the contents of `panel` to the root and present it
-    blit(
-        &tcod.gui_panel,
-        (0, 0),
-        (SCREEN_WIDTH, PANEL_HEIGHT),
-        &mut tcod.root,
-        (0, PANEL_Y),
-        1.0,
-        1.0,
+    let result = match verify_replay(replay, &game, &entities) {
+        Ok(()) => "The replay matches the recorded game.".to_string(),
+        Err(err) => format!("{}.", err)
+    };
+    msgbox(&format!("\n{}\n", result), 50, gui);
+}
+
+/// @title menu
+/// @author GeorgiKostadinovPro
+/// @notice show a menu with options and wait for the player to choose one
+/// @dev custom fn to draw a window in the center of the root with a header and lettered options (a), (b), ...
+fn menu<T: AsRef<str>>(header: &str, options: &[T], width: i32, gui: &mut Gui) -> Option<usize> {
+    // one letter per option => no more than 26 options
+    assert!(
+        options.len() <= 26,
+        "Cannot have a menu with more than 26 options."
     );
+
+    // calculate total height for the header (after auto-wrap) and one line per option
+    let header_height = if header.is_empty() {
+        0
+    } else {
+        text_height(header, width)
+    };
+    let height = options.len() as i32 + header_height;
+
+    // create an off-screen panel that represents the menu's window
+    let mut window = Panel::new(width, height);
+
+    // print the header, with auto-wrap
+    window.print_rect(0, 0, width, header, WHITE);
+
+    // print all the options
+    for (index, option_text) in options.iter().enumerate() {
+        let menu_letter = (b'a' + index as u8) as char;
+        let text = format!("({}) {}", menu_letter, option_text.as_ref());
+        window.print(0, header_height + index as i32, &text, WHITE);
+    }
+
+    // blit the contents of "window" to the root in the center of the screen
+    let x = SCREEN_WIDTH / 2 - width / 2;
+    let y = SCREEN_HEIGHT / 2 - height / 2;
+    gui.root.blit(&window, x, y, 0.7);
+
+    // present the root to the player and wait for a key-press
+    gui.flush();
+    let key = gui.backend.wait_for_keypress();
+
+    // convert the ASCII code to an index; if it corresponds to an option, return it
+    if let Some(letter) = key.char().filter(|c| c.is_ascii_alphabetic()) {
+        let index = letter.to_ascii_lowercase() as usize - 'a' as usize;
+        if index < options.len() {
+            Some(index)
+        } else {
+            None
+        }
+    } else {
+        None
+    }
 }
 
-/// @title handle_player_actions
+/// @title inventory_menu
 /// @author GeorgiKostadinovPro
-/// @notice keyboard handling fn
-/// @dev custom fn to handle keyboard interaction
-fn handle_player_actions(tcod: &mut Tcod, game: &mut Game, entities: &mut [Entity]) -> PlayerAction {
-    use tcod::input::Key;
-    use tcod::input::KeyCode::*;
-
-    let key: Key = tcod.root.wait_for_keypress(true);
-
-    // if player is dead do not allow to move
-    let is_alive = entities[PLAYER].is_alive;
-    
-    // actions supported:
-    // enter + alt - full screen
-    // escape => close game
-    // up, down, left, right => move player
-    // toggle screen and exit - work whether player is alive/dead
-    // for movement - is_alive must be true
-    match (key, key.text(), is_alive) {
-        // get only the action without any other fields (..)
-        // without .. code will not compile because we have to specify each field
-        (Key {
-                code: Enter,
-                alt: true,
-                ..
-            },
-            _,
-            _
-        ) => {
-            // Alt+Enter: toggle fullscreen
-            let fullscreen = tcod.root.is_fullscreen();
-            tcod.root.set_fullscreen(!fullscreen);
-            // this does not count as player action
-            DidntTakeTurn
+/// @notice show the inventory
+/// @dev custom fn to show a menu with each item of the inventory as an option
+fn inventory_menu(inventory: &[Entity], header: &str, gui: &mut Gui) -> Option<usize> {
+    // show a menu with each item of the inventory as an option
+    // equipped items show where they are worn
+    let options = if inventory.is_empty() {
+        vec!["Inventory is empty.".into()]
+    } else {
+        inventory
+            .iter()
+            .map(|item| match item.equipment {
+                Some(equipment) if equipment.is_equipped => {
+                    format!("{} (on {})", item.name, equipment.slot)
+                }
+                // a stack of arrows shows how many are left
+                _ if item.item == Some(Item::Arrows) => format!("{} ({})", item.name, item.count),
+                _ => item.name.clone()
+            })
+            .collect()
+    };
+
+    let inventory_index = menu(header, &options, INVENTORY_WIDTH, gui);
+
+    // if an item was chosen, return it
+    if !inventory.is_empty() {
+        inventory_index
+    } else {
+        None
+    }
+}
+
+/// @title character_sheet
+/// @author GeorgiKostadinovPro
+/// @notice show the player's level and stats
+/// @dev custom fn to show the base stats of the player next to the effective ones (with the equipment bonuses)
+fn character_sheet(game: &Game, entities: &[Entity], gui: &mut Gui) {
+    let player = &entities[PLAYER];
+    let fighter = match player.fighter {
+        Some(fighter) => fighter,
+        None => return
+    };
+
+    // effective (base + equipment bonus)
+    let stat = |name: &str, base: i32, effective: i32| {
+        format!("{}: {} (base {} + {})", name, effective, base, effective - base)
+    };
+
+    // every hit does the power (with the equipment) and a roll of the damage dice
+    let (low, high) = fighter.damage.range();
+    let power = player.power(game);
+
+    let mut lines = vec![
+        "Character information".to_string(),
+        String::new(),
+        format!("Level: {}", player.level),
+        format!("Experience: {}", fighter.xp),
+        format!("Experience to level up: {}", level_up_xp(player.level)),
+        String::new(),
+        stat("Maximum HP", fighter.max_hp, player.max_hp(game)),
+        stat("Attack", fighter.power, player.power(game)),
+        stat("Defense", fighter.defense, player.defense(game)),
+        format!("Damage: {} + {} ({}-{})", power, fighter.damage, power + low, power + high),
+        format!("Accuracy: {}%", fighter.accuracy),
+        format!("Evasion: {}%", fighter.evasion),
+        format!("Critical hit: {}%", fighter.crit),
+        String::new(),
+        "Equipment:".to_string()
+    ];
+
+    for item in &game.inventory {
+        if let Some(equipment) = item.equipment && equipment.is_equipped {
+            lines.push(format!("  {}: {}", equipment.slot, item.name));
         }
-        (
-            Key { 
-                code: Escape, .. 
-            }, 
-            _, 
-            _
-        ) => Exit,
-        (Key 
-            { 
-                code: Up, .. 
-            },
-            _,
-            true
-        ) => {
-            Entity::move_by(game, entities, PLAYER, 0, -1); 
-            TookTurn
-        },
-        (Key 
-            { 
-                code: Down, .. 
-            },
-            _,
-            true
-        ) => {
-            Entity::move_by(game, entities, PLAYER, 0, 1);
-            TookTurn
-        },
-        (Key 
-            { 
-                code: Left, .. 
-            },
-            _,
-            true
-        ) => {
-            Entity::move_by(game, entities, PLAYER, -1, 0);
-            TookTurn
-        },
-        (Key 
-            { 
-                code: Right, .. 
-            },
-            _,
-            true
-        ) => {
-            Entity::move_by(game, entities, PLAYER, 1, 0);
-            TookTurn
-        },
-        _ => DidntTakeTurn
     }
+
+    let text = lines.join("\n");
+    msgbox(&text, CHARACTER_SCREEN_WIDTH, gui);
 }
 
-fn main() { 
-    // limit the fps to 20
-    tcod::system::set_fps(LIMIT_FPS);
-
-    // create a new window
-    // default values for not specified options
-    let root: Root = Root::initializer()
-    .font("arial10x10.png", FontLayout::Tcod)
-    .font_type(FontType::Greyscale)
-    .size(SCREEN_WIDTH, SCREEN_HEIGHT)
-    .title(GAME_TITLE)
-    .init();
-
-    // use offscreen console for transparency effects and rendring part of the main root window
-    // maze is smaller than root console, the empty space will be used for healthy bar, messages, etc
-    let offscreen = Offscreen::new(MAZE_WIDTH, MAZE_HEIGHT);    
-    
-    // init a gui panel under themaze to display messages, HP, items, etc
-    // Maze width == Screen width, Panel height = screen - maze
-    let gui_panel = Offscreen::new(MAZE_WIDTH, PANEL_HEIGHT);
-
-    // init a field of view map (tcod_db.rs for more docs)
-    let fov = Map::new(MAZE_WIDTH, MAZE_HEIGHT);
-
-    // init the root options
-    let mut tcod = Tcod { root, offscreen, gui_panel, fov };    
-
-    // init a player
-    let mut player = Entity::new(0, 0, '@', WHITE, "go4ko", true);  
-    player.is_alive = true;  
-    player.fighter = Some(
-        Fighter {
-            max_hp: 30,
-            hp: 30,
-            defense: 2,
-            power: 5,
-            on_death: DeathCallback::Player
+/// @title message_log
+/// @author GeorgiKostadinovPro
+/// @notice show every message kept in the log on the whole screen
+/// @dev custom fn to show the messages with their turn, newest at the bottom
+/// up/down and page up/down scroll, 1 2 3 toggle the combat, system and loot messages, Escape or m closes it
+fn message_log(messages: &Messages, gui: &mut Gui) {
+    // the turn column, then the text wrapped under itself
+    const TURN_WIDTH: usize = 6;
+    let text_width = SCREEN_WIDTH as usize - TURN_WIDTH - 2;
+
+    // the header, a line of filters and an empty line above the messages, a line of keys below them
+    let page = SCREEN_HEIGHT - 4;
+
+    let mut shown = MessageCategory::ALL.to_vec();
+    // lines scrolled up from the newest message
+    let mut scroll: i32 = 0;
+
+    loop {
+        // every line of the shown messages: (turn if first line of the message, text, color)
+        let mut lines: Vec<(Option<u32>, String, Color)> = vec![];
+        for message in messages.filtered(&shown) {
+            for (i, line) in wrap_text(&message.text, text_width).into_iter().enumerate() {
+                lines.push(((i == 0).then_some(message.turn), line, message.color));
+            }
         }
-    );
-    
-    // current entities
-    let mut entities = vec![player];
-
-    // init game and create a maze ref maze.rs for more docs
-    // player will be placed in the center of the first generated room
-    // monters will be placed within each generated room on random
-    let mut game = Game { 
-        maze: create_maze(&mut entities),
-        messages: Messages::new()
-    }; 
-
-    // add a welcoming message
-    game.messages.add(
-        "Welcome player! Prepare for the adventure of your life.",
-        RED,
-    );
 
-    // populate the FOV map, according to the generated maze
-    // the libtcod FOV module needs to know which tiles block sight
-    // ToDo: extract in fn()
-    for x in 0..MAZE_WIDTH {
-        for y in 0..MAZE_HEIGHT {
-            tcod.fov.set(
-                x,
-                y,
-                !game.maze[x as usize][y as usize].block_sight,
-                !game.maze[x as usize][y as usize].blocked,
-            );
+        let max_scroll = (lines.len() as i32 - page).max(0);
+        scroll = scroll.clamp(0, max_scroll);
+
+        let root = &mut gui.root;
+        root.clear();
+
+        let count = lines.iter().filter(|(turn, _, _)| turn.is_some()).count();
+        root.print(1, 0, &format!("Message log - {} of {} messages", count, messages.messages.len()), WHITE);
+
+        // the filters in their category color, dark if they are hidden
+        let mut x = 1;
+        for (i, category) in MessageCategory::ALL.iter().enumerate() {
+            let is_shown = shown.contains(category);
+            let filter = format!("[{}] {} {}", i + 1, category.name(), if is_shown { "on" } else { "off" });
+            root.print(x, 1, &filter, if is_shown { category.color() } else { DARK_GREY });
+            x += filter.len() as i32 + 3;
+        }
+
+        let end = lines.len() as i32 - scroll;
+        let start = (end - page).max(0);
+        for (row, (turn, line, color)) in lines[start as usize..end as usize].iter().enumerate() {
+            let y = 3 + row as i32;
+            if let Some(turn) = turn {
+                root.print(1, y, &format!("{:>width$}", turn, width = TURN_WIDTH - 1), DARK_GREY);
+            }
+            root.print(1 + TURN_WIDTH as i32, y, line, *color);
+        }
+
+        root.print(1, SCREEN_HEIGHT - 1, "Up/Down, PageUp/PageDown - scroll   1 2 3 - filters   Escape - close", LIGHT_GREY);
+        gui.flush();
+
+        let key = gui.backend.wait_for_keypress();
+        match key.name.as_deref() {
+            Some("escape") => break,
+            Some("up") => scroll += 1,
+            Some("down") => scroll -= 1,
+            Some("pageup") => scroll += page,
+            Some("pagedown") => scroll -= page,
+            Some("home") => scroll = max_scroll,
+            Some("end") => scroll = 0,
+            _ => match key.char() {
+                Some('m') => break,
+                Some(digit @ '1'..='3') => {
+                    let category = MessageCategory::ALL[digit as usize - '1' as usize];
+                    match shown.iter().position(|shown| *shown == category) {
+                        Some(i) => { shown.remove(i); }
+                        None => shown.push(category)
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if gui.backend.window_closed() {
+            break;
         }
     }
+}
 
-    // FOV needs to be recomputed — but only if the player moves or a tile changes
-    // force FOV "recompute" first time through the game loop
-    // using (-1, -1) to make sure FOV gets computed on the first time through the loop
-    let mut player_previous_position = (-1, -1);
+/// @title msgbox
+/// @author GeorgiKostadinovPro
+/// @notice show a message to the player
+/// @dev custom fn to show a menu without options (only a header), any key closes it
+fn msgbox(text: &str, width: i32, gui: &mut Gui) {
+    let options: &[&str] = &[];
+    menu(text, options, width, gui);
+}
 
+/// @title play_game
+/// @author GeorgiKostadinovPro
+/// @notice the game loop
+/// @dev custom fn to render, handle the player actions and autosave when the player exits (Escape)
+fn play_game(gui: &mut Gui, keys: &KeyBindings, game: &mut Game, entities: &mut Vec<Entity>) {
     // start the game loop until the window is closed
     // the loop will be executed 20 times a second (limit fps = 20)
     // golden rule for roguelikes turn-based:
     // 1. Render: clear screen => draw game on screen => flush to root
     // 2. Input: block until a key is pressed
-    // 3. Update: match key and change player's coordinates
+    // 3. Update: match key and change player's coordinates, monsters take their turn
     // 4. Repeat
-    while !tcod.root.window_closed() {
+    while !gui.backend.window_closed() {
         // clear console of elements from previous frame
-        tcod.offscreen.clear();
-
-        // recompute the fov as player moves around
-        // (-1, -1) != (0, 0) => recompute the fov based on the player location
-        // (0, 0) != (x, y) => player has moved => move the fov with him
-        let fov_recompute = player_previous_position != (entities[0].x, entities[0].y);
+        gui.offscreen.clear();
 
-        render_game(&mut tcod, &mut game, &entities, fov_recompute);
+        render_game(gui, game, entities);
 
         // flush to root so the window shows the frame
-        tcod.root.flush();
-
-        // (0, 0) on the first run then player (x, y) will change from keyboard action
-        // (x, y) on the second run then player (x, y) will change again
-        player_previous_position = (entities[0].x, entities[0].y);
+        gui.flush();
 
         // handle actions and exit game if needed
-        // entities are vec but fn accepts &mut [Entity] 
-        // deref coercion - create a mutable slice - mutate elements inside, but resize vec
-        let player_action = handle_player_actions(&mut tcod, &mut game, &mut entities);
+        // entities are passed as &mut Vec - picking up an item removes it from the entities
+        let player_action = handle_player_actions(gui, keys, game, entities);
         if player_action == PlayerAction::Exit {
+            autosave(gui, game, entities);
             break;
         }
+
+        // what was shot this turn flies before the next frame
+        animate_projectiles(gui, game, entities);
+
+        // enough xp after this turn => the player chooses what to raise before he goes on
+        level_up_menu(gui, game, entities);
+    }
+}
+
+/// @title level_up_menu
+/// @author GeorgiKostadinovPro
+/// @notice let the player choose a stat to raise on level up
+/// @dev custom fn to show the level up menu until a stat is chosen, once per level the player's xp is enough for
+fn level_up_menu(gui: &mut Gui, game: &mut Game, entities: &mut Vec<Entity>) {
+    while entities[PLAYER].is_alive && can_level_up(&entities[PLAYER]) && !gui.backend.window_closed() {
+        let Some(fighter) = entities[PLAYER].fighter else {
+            break;
+        };
+        let options = &[
+            format!("Constitution (+{} HP, from {})", LEVEL_UP_HP, fighter.max_hp),
+            format!("Strength (+{} attack, from {})", LEVEL_UP_POWER, fighter.power),
+            format!("Agility (+{} defense, from {})", LEVEL_UP_DEFENSE, fighter.defense)
+        ];
+
+        // render the maze behind the menu so the player sees the level up message
+        gui.offscreen.clear();
+        render_game(gui, game, entities);
+
+        // no cancel - a stat must be chosen
+        let header = "Level up! Choose a stat to raise:\n";
+        let stat = match menu(header, options, LEVEL_SCREEN_WIDTH, gui) {
+            Some(0) => Stat::MaxHp,
+            Some(1) => Stat::Power,
+            Some(2) => Stat::Defense,
+            _ => continue
+        };
+
+        play_turn(Command::LevelUp(stat), game, entities);
+    }
+}
+
+/// @title autosave
+/// @author GeorgiKostadinovPro
+/// @notice save the game on exit so it can be continued later
+/// @dev custom fn to save the game of a living player, a dead player has nothing to continue => remove the save
+fn autosave(gui: &mut Gui, game: &Game, entities: &[Entity]) {
+    if !entities[PLAYER].is_alive {
+        // the save may not exist yet, nothing to remove then
+        let _ = std::fs::remove_file(SAVE_FILE);
+        return;
+    }
+
+    if let Err(err) = save_game(SAVE_FILE, game, entities) {
+        msgbox(&format!("\nCould not save the game: {}\n", err), 50, gui);
+    }
+}
+
+/// @title main_menu
+/// @author GeorgiKostadinovPro
+/// @notice the first screen of the game
+/// @dev custom fn to start a new game, continue the last (autosaved) game or quit
+/// every game spawns its monsters and items from the definitions loaded at startup
+/// a new game generates its floors with the generator chosen at startup (None - by depth)
+/// the player plays with the key bindings loaded at startup
+/// record - the replay file a new game is recorded to (--record), written when the game is left
+fn main_menu(
+    gui: &mut Gui,
+    seed: Option<u64>,
+    definitions: &Definitions,
+    generator: Option<Generator>,
+    keys: &KeyBindings,
+    record: Option<&str>
+) {
+    while !gui.backend.window_closed() {
+        gui.root.clear();
+
+        // show the game's title
+        gui.root.print_center(SCREEN_WIDTH / 2, SCREEN_HEIGHT / 2 - 4, GAME_TITLE, LIGHT_YELLOW);
+
+        // show options and wait for the player's choice
+        let choices = &["Play a new game", "Continue last game", "Quit"];
+        let choice = menu("", choices, 24, gui);
+
+        match choice {
+            Some(0) => {
+                // new game - the same seed always generates the same maze with the same monsters
+                let (mut game, mut entities) = new_game(seed.unwrap_or_else(rand::random), definitions.clone(), generator);
+                if record.is_some() {
+                    start_recording(&mut game);
+                }
+                play_game(gui, keys, &mut game, &mut entities);
+
+                if let Some(path) = record
+                    && let Some(replay) = Replay::from_game(&game, &entities)
+                    && let Err(err) = save_replay(path, &replay)
+                {
+                    msgbox(&format!("\nCould not save the replay: {}\n", err), 50, gui);
+                }
+            }
+            Some(1) => {
+                // load game
+                match load_game(SAVE_FILE, definitions.clone()) {
+                    Ok((mut game, mut entities)) => {
+                        play_game(gui, keys, &mut game, &mut entities);
+                    }
+                    // no save file at all is not an error, a broken or an old one is shown with the reason
+                    Err(err)
+                        if err.downcast_ref::<io::Error>().is_some_and(|err| err.kind() == io::ErrorKind::NotFound) =>
+                    {
+                        msgbox("\nNo saved game to load.\n", 24, gui);
+                        continue;
+                    }
+                    Err(err) => {
+                        msgbox(&format!("\nCould not load the saved game: {}\n", err), 50, gui);
+                        continue;
+                    }
+                }
+            }
+            Some(2) => {
+                // quit
+                break;
+            }
+            _ => {}
+        }
+    }
+}
+
+// an invalid command line argument is reported instead of starting the game, like a broken definitions file
+fn exit_on_error<T>(arg: Result<T, String>) -> T {
+    arg.unwrap_or_else(|err| {
+        eprintln!("Invalid arguments - {}", err);
+        std::process::exit(1);
+    })
+}
+
+fn main() { 
+    let args: Vec<String> = std::env::args().collect();
+
+    // the same seed always generates the same maze with the same monsters
+    let seed = exit_on_error(read_seed(&args));
+
+    // every floor with the same algorithm, or a different one as the player goes deeper
+    let generator = exit_on_error(read_generator(&args));
+
+    // a window by default, the terminal e.g. over SSH
+    let backend = exit_on_error(read_backend(&args));
+
+    // the monsters and items are read from the definitions file (built in ones if there is no file)
+    // a broken file is reported with the entry to fix instead of starting a broken game
+    let definitions = match load_definitions(DEFINITIONS_FILE) {
+        Ok(definitions) => definitions,
+        Err(err) => {
+            eprintln!("Invalid monster and item definitions - {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    // record a new game to a replay file / play a recorded one back
+    let record = exit_on_error(read_file_arg(&args, "--record"));
+    let replay = exit_on_error(read_file_arg(&args, "--replay")).map(|path| match load_replay(&path) {
+        Ok(replay) => replay,
+        Err(err) => {
+            eprintln!("Invalid replay - {}: {}", path, err);
+            std::process::exit(1);
+        }
+    });
+    let replay_speed = exit_on_error(read_replay_speed(&args));
+
+    // no window needed, e.g. on a machine without a display
+    if args.iter().any(|arg| arg == "--headless") {
+        match replay {
+            Some(replay) => replay_headless(&replay, definitions),
+            None => run_headless(seed.unwrap_or_else(rand::random), definitions, generator, record.as_deref())
+        }
+        return;
+    }
+
+    // the keys are read from the key bindings file (the arrow keys if there is no file)
+    let keys = match load_key_bindings(KEYS_FILE) {
+        Ok(keys) => keys,
+        Err(err) => {
+            eprintln!("Invalid key bindings - {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    // the libtcod window or the terminal (--backend), both show the same screen (render.rs)
+    let backend: Box<dyn Backend> = match backend {
+        BackendKind::Tcod => Box::new(TcodBackend::new()),
+        BackendKind::Terminal => match TerminalBackend::new() {
+            Ok(terminal) => Box::new(terminal),
+            Err(err) => {
+                eprintln!("Could not start the game in the terminal - {}", err);
+                std::process::exit(1);
+            }
+        }
+    };
+    let mut gui = Gui::new(backend);
+
+    // new game (player, maze and its monsters - engine.rs) or continue the saved one (save.rs)
+    // watch a recorded game instead of playing
+    if let Some(replay) = replay {
+        watch_replay(&mut gui, &replay, definitions, replay_speed);
+        return;
     }
+
+    main_menu(&mut gui, seed, &definitions, generator, &keys, record.as_deref());
 }
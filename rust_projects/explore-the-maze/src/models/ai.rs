@@ -0,0 +1,132 @@
+use rand::Rng;
+
+use crate::models::awareness::*;
+use crate::models::effects::*;
+use crate::models::entity::*;
+use crate::models::features::*;
+use crate::models::fov::*;
+use crate::models::maze::*;
+use crate::models::pathfinding::*;
+use crate::models::ranged::*;
+use crate::models::util::*;
+
+/// @title ai_take_turn
+/// @author GeorgiKostadinovPro
+/// @notice give a monster its turn after the player took his
+/// @dev custom fn to dispatch the monster turn depending on its ai component
+/// a confused monster stumbles around whatever its ai is (effects.rs)
+/// how often a monster gets a turn is up to its speed (scheduler.rs)
+pub fn ai_take_turn(monster_id: usize, game: &mut Game, entities: &mut Vec<Entity>) {
+    if has_effect(&entities[monster_id], Effect::Confusion) {
+        step_at_random(monster_id, game, entities);
+        return;
+    }
+
+    match entities[monster_id].ai {
+        Some(Ai::Basic) => ai_basic(monster_id, game, entities),
+        None => {}
+    }
+}
+
+// a basic monster acts on what it knows about the player (awareness.rs)
+// it sees the player in its own FOV, as far as its sight - then it hunts him
+// asleep => it may wake up when it sees him, the closer he is the more likely (right next to it - always)
+// lost sight of him => it goes where it saw him last and looks around there before it gives up and wanders
+fn ai_basic(monster_id: usize, game: &mut Game, entities: &mut Vec<Entity>) {
+    let (monster_x, monster_y) = entities[monster_id].get_pos();
+    let (player_x, player_y) = entities[PLAYER].get_pos();
+
+    let sight = entities[monster_id].fighter.map_or(0, |f| f.sight);
+    let sees_player = can_see(&game.maze, (monster_x, monster_y), (player_x, player_y), sight, game.fov_algorithm);
+
+    match entities[monster_id].awareness {
+        Awareness::Asleep => {
+            let distance = entities[monster_id].distance_to(&entities[PLAYER]) as i32;
+            if sees_player && game.rng.gen_range(0, distance.max(1)) == 0 {
+                // waking up takes the turn
+                wake_up(monster_id, game, entities);
+            }
+        }
+        _ if sees_player => {
+            entities[monster_id].awareness = Awareness::Hunting { last_seen: (player_x, player_y) };
+            ai_chase(monster_id, game, entities);
+        }
+        Awareness::Hunting { last_seen } => ai_search(monster_id, last_seen, SEARCH_TURNS, game, entities),
+        Awareness::Searching { last_seen, turns } => ai_search(monster_id, last_seen, turns, game, entities),
+        Awareness::Wandering => step_at_random(monster_id, game, entities)
+    }
+}
+
+// far away => move one step towards the player, next to the player => attack
+// a monster with arrows shoots him instead of walking up to him, as long as nothing is in the way (ranged.rs)
+fn ai_chase(monster_id: usize, game: &mut Game, entities: &mut Vec<Entity>) {
+    let (monster_x, monster_y) = entities[monster_id].get_pos();
+    let (player_x, player_y) = entities[PLAYER].get_pos();
+
+    // the player can attack diagonally so the monsters can too (they still walk in 4 directions)
+    let is_adjacent = (player_x - monster_x).abs().max((player_y - monster_y).abs()) == 1;
+
+    if !is_adjacent && entities[PLAYER].is_alive && monster_shoot(monster_id, game, entities) {
+        return;
+    }
+
+    if !is_adjacent {
+        move_towards(monster_id, player_x, player_y, game, entities);
+    } else if entities[PLAYER].is_alive {
+        // close enough, attack! (if the player is still alive)
+        let (monster, player) = mut_two(entities, monster_id, PLAYER);
+        monster.attack(player, game);
+    }
+}
+
+// go to where the player was last seen (or heard), once there look around for a few turns
+// nothing there (or no way to get there) => give up and wander around
+fn ai_search(monster_id: usize, last_seen: (i32, i32), turns: u32, game: &mut Game, entities: &mut [Entity]) {
+    let position = entities[monster_id].get_pos();
+    if position != last_seen {
+        move_towards(monster_id, last_seen.0, last_seen.1, game, entities);
+        if entities[monster_id].get_pos() != position {
+            entities[monster_id].awareness = Awareness::Searching { last_seen, turns };
+            return;
+        }
+    }
+
+    entities[monster_id].awareness = if turns > 1 {
+        Awareness::Searching { last_seen, turns: turns - 1 }
+    } else {
+        Awareness::Wandering
+    };
+}
+
+// a confused or wandering monster moves in a random direction (without attacking), it stays if the way is blocked
+// the confused one until the confusion wears off, the wandering one until it sees or hears the player
+// a closed door in the way is opened instead (features.rs)
+fn step_at_random(monster_id: usize, game: &mut Game, entities: &mut [Entity]) {
+    let (dx, dy) = stumble(game);
+    let (x, y) = entities[monster_id].get_pos();
+    if open_door(monster_id, x + dx, y + dy, game, entities) {
+        return;
+    }
+    if !is_blocked(x + dx, y + dy, &game.maze, entities) {
+        entities[monster_id].set_pos(x + dx, y + dy);
+    }
+}
+
+// move the monster one step along the shortest path to (target_x, target_y) (pathfinding.rs)
+// first look for a path around walls and other monsters,
+// if the others block every path look for a path around walls only and wait for them to move
+// a closed door on the path is opened first, it is walked through in the next turn
+fn move_towards(id: usize, target_x: i32, target_y: i32, game: &mut Game, entities: &mut [Entity]) {
+    let start = entities[id].get_pos();
+    let target = (target_x, target_y);
+
+    let path = find_path(start, target, &game.maze, entities, true)
+        .or_else(|| find_path(start, target, &game.maze, entities, false));
+
+    if let Some(&(x, y)) = path.as_ref().and_then(|path| path.first())
+        && !open_door(id, x, y, game, entities)
+        && !is_blocked(x, y, &game.maze, entities)
+    {
+        entities[id].set_pos(x, y);
+    }
+}
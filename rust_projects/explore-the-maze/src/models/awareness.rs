@@ -0,0 +1,109 @@
+use std::fmt;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tcod::colors::*;
+
+use crate::models::entity::*;
+use crate::models::maze::*;
+use crate::models::messages::*;
+
+// how far (in tiles, through the walls) the sounds of a fight carry
+pub const COMBAT_NOISE: i32 = 8;
+
+// how many turns a monster looks around the place it last saw the player before it gives up
+pub const SEARCH_TURNS: u32 = 5;
+
+// the chance (in %) of a monster of a new floor being asleep, the others wander around
+pub const ASLEEP_CHANCE: u32 = 50;
+
+// what a monster knows about the player (the ai acts on it, ai.rs)
+// Asleep - does nothing until a noise wakes it, or it wakes up by itself seeing the player close by
+// Wandering - walks around at random until it sees the player
+// Hunting - saw the player on its last turn and chases him, last_seen is where
+// Searching - lost sight of the player (or heard a noise), goes to last_seen and looks around for a few turns
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Awareness {
+    Asleep,
+    Wandering,
+    Hunting { last_seen: (i32, i32) },
+    Searching { last_seen: (i32, i32), turns: u32 }
+}
+
+// shown when looking at a monster e.g. "orc (asleep)"
+impl fmt::Display for Awareness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Awareness::Asleep => write!(f, "asleep"),
+            Awareness::Wandering => write!(f, "wandering"),
+            Awareness::Hunting { .. } => write!(f, "hunting"),
+            Awareness::Searching { .. } => write!(f, "searching")
+        }
+    }
+}
+
+// a sound somewhere in the maze, heard by the monsters up to loudness tiles away
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Noise {
+    pub x: i32,
+    pub y: i32,
+    pub loudness: i32
+}
+
+/// @title roll_awareness
+/// @author GeorgiKostadinovPro
+/// @notice how a monster of a new floor starts
+/// @dev custom fn to roll if the monster sleeps (ASLEEP_CHANCE) or wanders around
+pub fn roll_awareness<R: Rng>(rng: &mut R) -> Awareness {
+    if rng.gen_range(0, 100) < ASLEEP_CHANCE {
+        Awareness::Asleep
+    } else {
+        Awareness::Wandering
+    }
+}
+
+/// @title make_noise
+/// @author GeorgiKostadinovPro
+/// @notice make a sound the monsters may hear
+/// @dev custom fn to remember the noise until the monsters get to hear it (hear_noises)
+pub fn make_noise(game: &mut Game, x: i32, y: i32, loudness: i32) {
+    game.noises.push(Noise { x, y, loudness });
+}
+
+/// @title hear_noises
+/// @author GeorgiKostadinovPro
+/// @notice let the monsters hear the noises made since they last listened
+/// @dev custom fn to send every monster close enough to a noise to search where it came from
+/// a sleeping monster wakes up, a hunting one does not care (it knows where the player is)
+pub fn hear_noises(game: &mut Game, entities: &mut [Entity]) {
+    let noises = std::mem::take(&mut game.noises);
+
+    for noise in noises {
+        for id in 0..entities.len() {
+            let monster = &entities[id];
+            let is_heard = monster.ai.is_some()
+                && monster.is_alive
+                && monster.distance(noise.x, noise.y) <= noise.loudness as f32;
+            if !is_heard || matches!(monster.awareness, Awareness::Hunting { .. }) {
+                continue;
+            }
+
+            if monster.awareness == Awareness::Asleep {
+                wake_up(id, game, entities);
+            }
+            entities[id].awareness = Awareness::Searching { last_seen: (noise.x, noise.y), turns: SEARCH_TURNS };
+        }
+    }
+}
+
+/// @title wake_up
+/// @author GeorgiKostadinovPro
+/// @notice wake a sleeping monster
+/// @dev custom fn to let the monster wander around, the player is told if he sees it
+pub fn wake_up(id: usize, game: &mut Game, entities: &mut [Entity]) {
+    let monster = &mut entities[id];
+    monster.awareness = Awareness::Wandering;
+
+    if game.fov.is_in_fov(monster.x, monster.y) {
+        game.messages.add(format!("The {} wakes up!", monster.name), LIGHT_RED, MessageCategory::Combat);
+    }
+}
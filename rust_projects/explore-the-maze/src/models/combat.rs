@@ -0,0 +1,184 @@
+use std::fmt;
+use std::str::FromStr;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::models::entity::*;
+use crate::models::maze::*;
+
+// the chance (in %) of an attack to be critical, a critical one does CRIT_MULTIPLIER times the damage (before the defense)
+pub const CRIT_CHANCE: i32 = 5;
+pub const CRIT_MULTIPLIER: i32 = 2;
+
+// the chance (in %) of an attack to hit before the target tries to dodge it
+pub const PLAYER_ACCURACY: i32 = 90;
+pub const MONSTER_ACCURACY: i32 = 80;
+
+// the chance (in %) of the player to dodge an attack that hits
+pub const PLAYER_EVASION: i32 = 10;
+
+// what kind of harm a damage does - a fighter may resist some kinds (Resistances)
+// Physical - blows, bites and arrows
+// Fire - the fireball
+// Poison - the bites of the giant spiders and the poison they leave (effects.rs)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum DamageType {
+    #[default]
+    Physical,
+    Fire,
+    Poison
+}
+
+// shown in the messages e.g. "for 3 hit points of fire damage"
+impl fmt::Display for DamageType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DamageType::Physical => write!(f, "physical"),
+            DamageType::Fire => write!(f, "fire"),
+            DamageType::Poison => write!(f, "poison")
+        }
+    }
+}
+
+// how much (in %) of every kind of damage a fighter shrugs off - 100 is immune, a negative one takes more (e.g. -50 => x1.5)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Resistances {
+    pub physical: i32,
+    pub fire: i32,
+    pub poison: i32
+}
+
+impl Resistances {
+    // the resistance to one kind of damage
+    pub fn of(self, damage_type: DamageType) -> i32 {
+        match damage_type {
+            DamageType::Physical => self.physical,
+            DamageType::Fire => self.fire,
+            DamageType::Poison => self.poison
+        }
+    }
+}
+
+// the extra damage rolled on every hit, written like in the tabletop games e.g. "2d4" - two four-sided dice
+// "0d0" rolls nothing, the damage is always the power (minus the defense)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Dice {
+    pub count: u32,
+    pub sides: u32
+}
+
+impl Dice {
+    pub fn new(count: u32, sides: u32) -> Self {
+        Dice { count, sides }
+    }
+
+    // the sum of all dice, every die from 1 to its sides
+    pub fn roll<R: Rng>(self, rng: &mut R) -> i32 {
+        if self.sides == 0 {
+            return 0;
+        }
+        (0..self.count).map(|_| rng.gen_range(1, self.sides as i32 + 1)).sum()
+    }
+
+    // the lowest and the highest roll e.g. 2d4 => (2, 8)
+    pub fn range(self) -> (i32, i32) {
+        if self.sides == 0 {
+            (0, 0)
+        } else {
+            (self.count as i32, (self.count * self.sides) as i32)
+        }
+    }
+}
+
+impl fmt::Display for Dice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}d{}", self.count, self.sides)
+    }
+}
+
+impl FromStr for Dice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid dice '{}', expected e.g. 2d4", s);
+        let (count, sides) = s.trim().split_once('d').ok_or_else(invalid)?;
+        Ok(Dice {
+            count: count.parse().map_err(|_| invalid())?,
+            sides: sides.parse().map_err(|_| invalid())?
+        })
+    }
+}
+
+// the dice are written as text in the definitions and in the save files
+impl TryFrom<String> for Dice {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Dice> for String {
+    fn from(dice: Dice) -> Self {
+        dice.to_string()
+    }
+}
+
+// how an attack went
+// Miss - the attacker missed (its accuracy)
+// Dodged - it would have hit but the target got out of the way (its evasion)
+// Hit - damage after the defense and the resistance (0 or less - no effect), resistance of the target to its type
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AttackOutcome {
+    Miss,
+    Dodged,
+    Hit { damage: i32, damage_type: DamageType, is_critical: bool, resistance: i32 }
+}
+
+/// @title resolve_attack
+/// @author GeorgiKostadinovPro
+/// @notice roll how an attack goes
+/// @dev custom fn to roll the hit (accuracy of the attacker), the dodge (evasion of the target), the critical hit and the damage dice
+/// the damage is power + dice (x CRIT_MULTIPLIER if critical) - defense, then cut (or raised) by the resistance of the target
+/// every roll is made with the game rng - the same seed fights the same
+pub fn resolve_attack(attacker: &Entity, target: &Entity, game: &mut Game) -> AttackOutcome {
+    let (Some(fighter), Some(defender)) = (attacker.fighter, target.fighter) else {
+        return AttackOutcome::Miss;
+    };
+
+    if game.rng.gen_range(0, 100) >= fighter.accuracy {
+        return AttackOutcome::Miss;
+    }
+    if game.rng.gen_range(0, 100) < defender.evasion {
+        return AttackOutcome::Dodged;
+    }
+
+    let is_critical = game.rng.gen_range(0, 100) < fighter.crit;
+    let mut damage = attacker.power(game) + fighter.damage.roll(&mut game.rng);
+    if is_critical {
+        damage *= CRIT_MULTIPLIER;
+    }
+    damage -= target.defense(game);
+
+    AttackOutcome::Hit {
+        damage: resist(damage, fighter.damage_type, target),
+        damage_type: fighter.damage_type,
+        is_critical,
+        resistance: defender.resistances.of(fighter.damage_type)
+    }
+}
+
+/// @title resist
+/// @author GeorgiKostadinovPro
+/// @notice the damage a fighter really takes
+/// @dev custom fn to cut the damage by the resistance of the target to its type (a negative resistance raises it)
+/// never less than 0 - a damage that was not going to hurt (or is fully resisted, 100 or more) does nothing
+pub fn resist(damage: i32, damage_type: DamageType, target: &Entity) -> i32 {
+    let resistance = target.fighter.map_or(0, |f| f.resistances.of(damage_type));
+    if damage <= 0 {
+        return 0;
+    }
+    (damage * (100 - resistance) / 100).max(0)
+}
@@ -0,0 +1,164 @@
+use std::collections::VecDeque;
+
+use crate::models::entity::*;
+use crate::models::maze::*;
+use crate::models::pathfinding::*;
+
+/// @title flood_fill
+/// @author GeorgiKostadinovPro
+/// @notice find every tile reachable from a tile
+/// @dev custom fn to walk (4 directions) over the tiles that are not blocked, starting from start
+pub fn flood_fill(maze: &Maze, start: (i32, i32)) -> Vec<(i32, i32)> {
+    let mut visited = vec![vec![false; MAZE_HEIGHT as usize]; MAZE_WIDTH as usize];
+    let mut reached = vec![];
+    let mut queue = VecDeque::new();
+
+    if maze[start.0 as usize][start.1 as usize].blocked {
+        return reached;
+    }
+
+    visited[start.0 as usize][start.1 as usize] = true;
+    queue.push_back(start);
+
+    while let Some((x, y)) = queue.pop_front() {
+        reached.push((x, y));
+
+        for (dx, dy) in DIRECTIONS {
+            let (nx, ny) = (x + dx, y + dy);
+            if nx < 0 || ny < 0 || nx >= MAZE_WIDTH || ny >= MAZE_HEIGHT {
+                continue;
+            }
+
+            if !visited[nx as usize][ny as usize] && !maze[nx as usize][ny as usize].blocked {
+                visited[nx as usize][ny as usize] = true;
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+
+    reached
+}
+
+/// @title open_areas
+/// @author GeorgiKostadinovPro
+/// @notice split the open tiles into the areas that are connected
+/// @dev custom fn to flood fill from every open tile not reached yet - one area per fill
+pub fn open_areas(maze: &Maze) -> Vec<Vec<(i32, i32)>> {
+    let mut reached = vec![vec![false; MAZE_HEIGHT as usize]; MAZE_WIDTH as usize];
+    let mut areas = vec![];
+
+    for x in 0..MAZE_WIDTH {
+        for y in 0..MAZE_HEIGHT {
+            if maze[x as usize][y as usize].blocked || reached[x as usize][y as usize] {
+                continue;
+            }
+
+            let area = flood_fill(maze, (x, y));
+            for &(ax, ay) in &area {
+                reached[ax as usize][ay as usize] = true;
+            }
+            areas.push(area);
+        }
+    }
+
+    areas
+}
+
+/// @title unreachable_areas
+/// @author GeorgiKostadinovPro
+/// @notice find the open areas the player cannot walk to
+/// @dev custom fn to report every open area that is not connected to start (where the player stands)
+pub fn unreachable_areas(maze: &Maze, start: (i32, i32)) -> Vec<Vec<(i32, i32)>> {
+    open_areas(maze)
+        .into_iter()
+        .filter(|area| !area.contains(&start))
+        .collect()
+}
+
+/// @title repair_connectivity
+/// @author GeorgiKostadinovPro
+/// @notice make every open tile reachable from start
+/// @dev custom fn to walk out of the area of start through the walls (breadth first), every open tile
+/// found on the way gets the walls in between dug - a tunnel to its area, which is walked on from as well
+/// returns the num of tunnels carved (0 - the maze was fine), the border is never dug
+/// a blocked start has nothing to connect to and is left as it is
+pub fn repair_connectivity(maze: &mut Maze, start: (i32, i32)) -> usize {
+    let mut visited = vec![vec![false; MAZE_HEIGHT as usize]; MAZE_WIDTH as usize];
+    let mut came_from = vec![vec![None; MAZE_HEIGHT as usize]; MAZE_WIDTH as usize];
+    let mut queue = VecDeque::new();
+    let mut tunnels = 0;
+
+    for (x, y) in flood_fill(maze, start) {
+        visited[x as usize][y as usize] = true;
+        queue.push_back((x, y));
+    }
+
+    while let Some((x, y)) = queue.pop_front() {
+        for (dx, dy) in DIRECTIONS {
+            let (nx, ny) = (x + dx, y + dy);
+            if nx < 1 || ny < 1 || nx >= MAZE_WIDTH - 1 || ny >= MAZE_HEIGHT - 1 {
+                continue;
+            }
+            if visited[nx as usize][ny as usize] {
+                continue;
+            }
+
+            if maze[nx as usize][ny as usize].blocked {
+                visited[nx as usize][ny as usize] = true;
+                came_from[nx as usize][ny as usize] = Some((x, y));
+                queue.push_back((nx, ny));
+                continue;
+            }
+
+            // an open tile not reached yet => dig the walls walked through to get here
+            let mut step = (x, y);
+            while maze[step.0 as usize][step.1 as usize].blocked {
+                maze[step.0 as usize][step.1 as usize] = Tile::empty();
+                let Some(previous) = came_from[step.0 as usize][step.1 as usize] else {
+                    break;
+                };
+                step = previous;
+            }
+            tunnels += 1;
+
+            // its whole area is reachable now, walk on from there too
+            let mut area = vec![(nx, ny)];
+            visited[nx as usize][ny as usize] = true;
+            while let Some((ax, ay)) = area.pop() {
+                queue.push_back((ax, ay));
+
+                for (dx, dy) in DIRECTIONS {
+                    let (bx, by) = (ax + dx, ay + dy);
+                    if bx < 0 || by < 0 || bx >= MAZE_WIDTH || by >= MAZE_HEIGHT {
+                        continue;
+                    }
+                    if !visited[bx as usize][by as usize] && !maze[bx as usize][by as usize].blocked {
+                        visited[bx as usize][by as usize] = true;
+                        area.push((bx, by));
+                    }
+                }
+            }
+        }
+    }
+
+    tunnels
+}
+
+/// @title overlapping_spawns
+/// @author GeorgiKostadinovPro
+/// @notice find the blocking entities standing on each other
+/// @dev custom fn to report every pair (by index) of blocking entities (monsters, player) on the same tile
+/// items and stairs do not block, they can lie under anyone
+pub fn overlapping_spawns(entities: &[Entity]) -> Vec<(usize, usize)> {
+    let mut overlapping = vec![];
+
+    for (i, first) in entities.iter().enumerate() {
+        for (j, second) in entities.iter().enumerate().skip(i + 1) {
+            if first.is_blocking && second.is_blocking && first.get_pos() == second.get_pos() {
+                overlapping.push((i, j));
+            }
+        }
+    }
+
+    overlapping
+}
@@ -0,0 +1,302 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use rand::Rng;
+use serde::Deserialize;
+use tcod::colors::Color;
+
+use crate::models::combat::*;
+use crate::models::effects::*;
+use crate::models::entity::*;
+use crate::models::fov::*;
+use crate::models::ranged::*;
+use crate::models::scheduler::*;
+
+// the definitions file read at startup, next to the game (like the font)
+// designers edit it to rebalance the monsters and items without recompiling
+pub const DEFINITIONS_FILE: &str = "data/definitions.json";
+
+// the same file built into the game - used when there is no definitions file and by the tests
+const DEFAULT_DEFINITIONS: &str = include_str!("../../data/definitions.json");
+
+// every monster and item that can be spawned in the maze
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Definitions {
+    pub monsters: Vec<MonsterDef>,
+    pub items: Vec<ItemDef>
+}
+
+// a monster template - how it looks, how it fights and how often it is spawned
+// weight - the chance of this monster relative to the others (0 - never)
+// weight_per_depth - added to the weight on every floor below min_depth (e.g. more trolls deeper down)
+// max_weight - the weight stops growing there, no cap by default (the trolls never outnumber the orcs 3 to 2)
+// min_depth - the first floor it can be spawned on
+// on_hit - a status effect its attacks put on the player e.g. { "effect": "Poison", "turns": 5, "power": 1 }
+// speed - how often it acts, 100 (the default) once for every turn of the player, 200 twice, 50 every other turn
+// sight - how many tiles away it sees the player, 10 (the default) as far as he sees
+// ranged - it shoots arrows from afar e.g. { "range": 6, "power": 3, "ammo": 5 }, melee only without it
+// accuracy, evasion, crit - the chances (in %) to hit, to dodge and to hit critically, 80, 0 and 5 by default (combat.rs)
+// damage - the dice rolled on top of the power e.g. "1d4", none by default - damage_type - Physical (the default), Fire or Poison
+// resistances - e.g. { "poison": 100, "fire": -50 }, 100 is immune and a negative one takes more damage
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MonsterDef {
+    pub name: String,
+    pub glyph: char,
+    pub color: Color,
+    pub max_hp: i32,
+    pub defense: i32,
+    pub power: i32,
+    pub xp: i32,
+    pub weight: u32,
+    #[serde(default)]
+    pub weight_per_depth: u32,
+    #[serde(default)]
+    pub max_weight: Option<u32>,
+    pub min_depth: u32,
+    #[serde(default)]
+    pub on_hit: Option<StatusEffect>,
+    #[serde(default = "normal_speed")]
+    pub speed: i32,
+    #[serde(default = "torch_radius")]
+    pub sight: i32,
+    #[serde(default)]
+    pub ranged: Option<Ranged>,
+    #[serde(default = "monster_accuracy")]
+    pub accuracy: i32,
+    #[serde(default)]
+    pub evasion: i32,
+    #[serde(default = "crit_chance")]
+    pub crit: i32,
+    #[serde(default)]
+    pub damage: Dice,
+    #[serde(default)]
+    pub damage_type: DamageType,
+    #[serde(default)]
+    pub resistances: Resistances
+}
+
+// an item template - item is what it does when used, equipment only for the items that are worn
+// count - how many come stacked together, 1 (the default) for everything but arrows
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ItemDef {
+    pub name: String,
+    pub glyph: char,
+    pub color: Color,
+    pub item: Item,
+    #[serde(default)]
+    pub equipment: Option<Equipment>,
+    pub weight: u32,
+    #[serde(default)]
+    pub weight_per_depth: u32,
+    #[serde(default)]
+    pub max_weight: Option<u32>,
+    pub min_depth: u32,
+    #[serde(default = "single")]
+    pub count: u32
+}
+
+impl Default for Definitions {
+    // the built in definitions are checked by the tests, they are always valid
+    fn default() -> Self {
+        parse_definitions(DEFAULT_DEFINITIONS).expect("the built in definitions are not valid")
+    }
+}
+
+impl MonsterDef {
+    // the chance of this monster on a floor, relative to the others
+    pub fn weight_at(&self, depth: u32) -> u32 {
+        weight_at(self.weight, self.weight_per_depth, self.max_weight, self.min_depth, depth)
+    }
+
+    // create the monster from the template, alive and hunting
+    pub fn spawn(&self, x: i32, y: i32) -> Entity {
+        let mut monster = Entity::new(x, y, self.glyph, self.color, &self.name, true);
+        monster.fighter = Some(Fighter {
+            max_hp: self.max_hp,
+            hp: self.max_hp,
+            defense: self.defense,
+            power: self.power,
+            xp: self.xp,
+            on_death: DeathCallback::Monster,
+            on_hit: self.on_hit,
+            speed: self.speed,
+            sight: self.sight,
+            ranged: self.ranged,
+            accuracy: self.accuracy,
+            evasion: self.evasion,
+            crit: self.crit,
+            damage: self.damage,
+            damage_type: self.damage_type,
+            resistances: self.resistances
+        });
+        monster.is_alive = true;
+        monster.ai = Some(Ai::Basic);
+        monster
+    }
+}
+
+impl ItemDef {
+    // the chance of this item on a floor, relative to the others
+    pub fn weight_at(&self, depth: u32) -> u32 {
+        weight_at(self.weight, self.weight_per_depth, self.max_weight, self.min_depth, depth)
+    }
+
+    // create the item from the template, lying on the floor
+    pub fn spawn(&self, x: i32, y: i32) -> Entity {
+        let mut item = Entity::new(x, y, self.glyph, self.color, &self.name, false);
+        item.item = Some(self.item);
+        item.equipment = self.equipment;
+        item.count = self.count;
+        item
+    }
+}
+
+// the speed of a monster without one in the definitions file
+fn normal_speed() -> i32 {
+    NORMAL_SPEED
+}
+
+// the sight of a monster without one in the definitions file - as far as the player sees
+fn torch_radius() -> i32 {
+    TORCH_RADIUS
+}
+
+// the accuracy of a monster without one in the definitions file
+fn monster_accuracy() -> i32 {
+    MONSTER_ACCURACY
+}
+
+// the chance of a critical hit of a monster without one in the definitions file
+fn crit_chance() -> i32 {
+    CRIT_CHANCE
+}
+
+// the count of an item without one in the definitions file
+fn single() -> u32 {
+    1
+}
+
+// nothing before min_depth, then the weight grows by weight_per_depth every floor up to max_weight
+fn weight_at(weight: u32, weight_per_depth: u32, max_weight: Option<u32>, min_depth: u32, depth: u32) -> u32 {
+    if depth < min_depth {
+        0
+    } else {
+        (weight + weight_per_depth * (depth - min_depth)).min(max_weight.unwrap_or(u32::MAX))
+    }
+}
+
+/// @title choose_weighted
+/// @author GeorgiKostadinovPro
+/// @notice pick a random definition by its weight
+/// @dev custom fn to roll a weighted random choice, None if nothing can be picked (all weights are 0)
+pub fn choose_weighted<'a, T, R: Rng>(
+    definitions: &'a [T],
+    weight: impl Fn(&T) -> u32,
+    rng: &mut R
+) -> Option<&'a T> {
+    let total: u32 = definitions.iter().map(&weight).sum();
+    if total == 0 {
+        return None;
+    }
+
+    // roll a number in the total weight and find whose share it falls in
+    let mut roll = rng.gen_range(0, total);
+    for definition in definitions {
+        let weight = weight(definition);
+        if roll < weight {
+            return Some(definition);
+        }
+        roll -= weight;
+    }
+
+    None
+}
+
+/// @title load_definitions
+/// @author GeorgiKostadinovPro
+/// @notice load the monster and item definitions from a file
+/// @dev custom fn to read and validate a definitions file, the built in definitions if there is no such file
+pub fn load_definitions<P: AsRef<Path>>(path: P) -> Result<Definitions, Box<dyn Error>> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(Definitions::default());
+    }
+
+    let json = fs::read_to_string(path)?;
+    parse_definitions(&json).map_err(|err| format!("{}: {}", path.display(), err).into())
+}
+
+// an error naming the entry if its rule is broken
+fn check(entry: &str, is_valid: bool, message: &str) -> Result<(), Box<dyn Error>> {
+    if is_valid {
+        Ok(())
+    } else {
+        Err(format!("{}: {}", entry, message).into())
+    }
+}
+
+/// @title parse_definitions
+/// @author GeorgiKostadinovPro
+/// @notice parse and validate the monster and item definitions
+/// @dev custom fn to read the definitions from json and check every entry
+/// an error names the offending entry e.g. monsters[1] "troll": max_hp must be more than 0
+pub fn parse_definitions(json: &str) -> Result<Definitions, Box<dyn Error>> {
+    // serde_json errors already point to the line and column
+    let definitions: Definitions = serde_json::from_str(json)?;
+
+    let mut names = HashSet::new();
+    for (i, monster) in definitions.monsters.iter().enumerate() {
+        let entry = format!("monsters[{}] \"{}\"", i, monster.name);
+
+        check(&entry, !monster.name.trim().is_empty(), "name must not be empty")?;
+        check(&entry, names.insert(monster.name.as_str()), "name is already defined")?;
+        check(&entry, monster.max_hp > 0, "max_hp must be more than 0")?;
+        check(&entry, monster.defense >= 0, "defense must not be negative")?;
+        check(&entry, monster.power >= 0, "power must not be negative")?;
+        check(&entry, monster.xp >= 0, "xp must not be negative")?;
+        check(&entry, monster.min_depth >= 1, "min_depth must be at least 1")?;
+        check(&entry, monster.max_weight.is_none_or(|max| max >= monster.weight), "max_weight must not be less than weight")?;
+        check(&entry, monster.speed > 0, "speed must be more than 0")?;
+        check(&entry, monster.sight > 0, "sight must be more than 0")?;
+        check(&entry, monster.on_hit.is_none_or(|status| status.turns > 0), "on_hit turns must be more than 0")?;
+        check(&entry, monster.on_hit.is_none_or(|status| status.power >= 0), "on_hit power must not be negative")?;
+        check(&entry, monster.ranged.is_none_or(|ranged| ranged.range > 0), "ranged range must be more than 0")?;
+        check(&entry, (0..=100).contains(&monster.accuracy), "accuracy must be from 0 to 100")?;
+        check(&entry, (0..=100).contains(&monster.evasion), "evasion must be from 0 to 100")?;
+        check(&entry, (0..=100).contains(&monster.crit), "crit must be from 0 to 100")?;
+        let resistances = [monster.resistances.physical, monster.resistances.fire, monster.resistances.poison];
+        check(&entry, resistances.iter().all(|resistance| *resistance <= 100), "resistances must not be more than 100")?;
+        check(&entry, monster.ranged.is_none_or(|ranged| ranged.power >= 0), "ranged power must not be negative")?;
+    }
+
+    let mut names = HashSet::new();
+    for (i, item) in definitions.items.iter().enumerate() {
+        let entry = format!("items[{}] \"{}\"", i, item.name);
+
+        // what is worn needs a slot and bonuses, what is used up must not have them
+        let is_equipment = matches!(item.item, Item::Sword | Item::Bow | Item::Shield | Item::Helmet);
+        // only arrows come in stacks
+        let is_stackable = item.item == Item::Arrows;
+
+        check(&entry, !item.name.trim().is_empty(), "name must not be empty")?;
+        check(&entry, names.insert(item.name.as_str()), "name is already defined")?;
+        check(&entry, !is_equipment || item.equipment.is_some(), "equipment is missing for a worn item")?;
+        check(&entry, is_equipment || item.equipment.is_none(), "only a sword, bow, shield or helmet can have equipment")?;
+        check(&entry, item.equipment.is_none_or(|e| !e.is_equipped), "equipment must not be equipped on the floor")?;
+        check(&entry, item.min_depth >= 1, "min_depth must be at least 1")?;
+        check(&entry, item.max_weight.is_none_or(|max| max >= item.weight), "max_weight must not be less than weight")?;
+        check(&entry, item.count >= 1, "count must be at least 1")?;
+        check(&entry, is_stackable || item.count == 1, "only arrows can have a count of more than 1")?;
+    }
+
+    // the first floor needs monsters
+    if definitions.monsters.iter().all(|monster| monster.weight_at(1) == 0) {
+        return Err("monsters: at least one monster must be spawned on depth 1 (weight > 0, min_depth 1)".into());
+    }
+
+    Ok(definitions)
+}
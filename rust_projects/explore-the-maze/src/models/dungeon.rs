@@ -0,0 +1,89 @@
+use std::mem;
+use tcod::colors::*;
+
+use crate::models::engine::*;
+use crate::models::entity::*;
+use crate::models::generators::*;
+use crate::models::maze::*;
+use crate::models::messages::*;
+
+/// @title take_stairs
+/// @author GeorgiKostadinovPro
+/// @notice take the stairs the player stands on
+/// @dev custom fn to move the player one floor down (Stairs::Down) or back up (Stairs::Up)
+/// returns false if there are no such stairs under the player
+pub fn take_stairs(stairs: Stairs, game: &mut Game, entities: &mut Vec<Entity>) -> bool {
+    let player_pos = entities[PLAYER].get_pos();
+    let on_stairs = entities
+        .iter()
+        .any(|entity| entity.get_pos() == player_pos && entity.stairs == Some(stairs));
+
+    if !on_stairs {
+        let message = match stairs {
+            Stairs::Down => "There are no stairs down here.",
+            Stairs::Up => "There are no stairs up here."
+        };
+        game.messages.add(message, WHITE, MessageCategory::System);
+        return false;
+    }
+
+    match stairs {
+        Stairs::Down => {
+            change_floor(game.depth + 1, game, entities);
+            game.messages.add(
+                format!("You descend deeper into the heart of the dungeon... (level {})", game.depth),
+                VIOLET,
+                MessageCategory::System,
+            );
+        }
+        Stairs::Up => {
+            change_floor(game.depth - 1, game, entities);
+            game.messages.add(
+                format!("You climb back up to level {}.", game.depth),
+                VIOLET,
+                MessageCategory::System,
+            );
+        }
+    }
+
+    true
+}
+
+// leave the current floor as it is and go to the floor at depth
+// a visited floor is restored with the player on the stairs leading back, a new one is generated
+fn change_floor(depth: u32, game: &mut Game, entities: &mut Vec<Entity>) {
+    // the player goes with us, everything else stays on the floor
+    let floor = Floor {
+        maze: mem::take(&mut game.maze),
+        entities: entities.drain(PLAYER + 1..).collect()
+    };
+    game.floors.insert(game.depth, floor);
+
+    // going down => arrive on the stairs up of the lower floor, and the other way around
+    let arrive_on = if depth > game.depth { Stairs::Up } else { Stairs::Down };
+    game.depth = depth;
+
+    match game.floors.remove(&depth) {
+        Some(floor) => {
+            game.maze = floor.maze;
+            entities.extend(floor.entities);
+
+            // the player left this floor from these stairs, so nobody else stands on them
+            let arrival = entities
+                .iter()
+                .find(|entity| entity.stairs == Some(arrive_on))
+                .map(|entity| entity.get_pos());
+            if let Some((x, y)) = arrival {
+                entities[PLAYER].set_pos(x, y);
+            }
+        }
+        None => {
+            // a new floor - the player is placed on its stairs up by create_maze
+            let generator = game.generator.unwrap_or_else(|| Generator::for_depth(depth));
+            game.maze = create_maze(entities, depth, generator, &game.definitions, &mut game.rng);
+        }
+    }
+
+    // a different maze => a different FOV
+    compute_fov(game, entities);
+}
@@ -0,0 +1,187 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tcod::colors::*;
+
+use crate::models::combat::*;
+use crate::models::entity::*;
+use crate::models::maze::*;
+use crate::models::messages::*;
+
+// the potion of regeneration heals a little every turn for a while
+pub const REGENERATION_TURNS: i32 = 20;
+pub const REGENERATION_AMOUNT: i32 = 1;
+
+// the potion of speed doubles the player's speed (scheduler.rs) - two actions for every monster turn
+pub const HASTE_TURNS: i32 = 10;
+
+// the 8 directions a confused entity stumbles in
+const STUMBLE_DIRECTIONS: [(i32, i32); 8] = [(0, -1), (0, 1), (-1, 0), (1, 0), (-1, -1), (1, -1), (-1, 1), (1, 1)];
+
+// what a status effect does while it lasts, ticked once at the end of every turn (tick_effects)
+// Poison - loses power hp every turn
+// Regeneration - heals power hp every turn (up to the max hp)
+// Confusion - moves in a random direction instead of where it wanted to go (monsters do not attack)
+// Haste - double speed, acts twice for every turn of the others (scheduler.rs)
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Effect {
+    Poison,
+    Regeneration,
+    Confusion,
+    Haste
+}
+
+impl Effect {
+    // shown in the GUI panel under the HP bar
+    pub fn name(self) -> &'static str {
+        match self {
+            Effect::Poison => "Poison",
+            Effect::Regeneration => "Regen",
+            Effect::Confusion => "Confused",
+            Effect::Haste => "Haste"
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Effect::Poison => LIGHT_GREEN,
+            Effect::Regeneration => LIGHT_RED,
+            Effect::Confusion => LIGHT_VIOLET,
+            Effect::Haste => LIGHT_YELLOW
+        }
+    }
+
+    // the end of the message when an entity gets / loses the effect e.g. "The orc is no longer poisoned."
+    fn adjective(self) -> &'static str {
+        match self {
+            Effect::Poison => "poisoned",
+            Effect::Regeneration => "regenerating",
+            Effect::Confusion => "confused",
+            Effect::Haste => "hasted"
+        }
+    }
+}
+
+// an effect on an entity for the turns left
+// power - the hp lost (poison) or healed (regeneration) every turn, not used by the others
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatusEffect {
+    pub effect: Effect,
+    pub turns: i32,
+    #[serde(default)]
+    pub power: i32
+}
+
+impl StatusEffect {
+    pub fn new(effect: Effect, turns: i32, power: i32) -> Self {
+        StatusEffect { effect, turns, power }
+    }
+}
+
+/// @title has_effect
+/// @author GeorgiKostadinovPro
+/// @notice check if an entity is under an effect
+/// @dev custom fn to look for the effect among the active ones of the entity
+pub fn has_effect(entity: &Entity, effect: Effect) -> bool {
+    entity.effects.iter().any(|status| status.effect == effect)
+}
+
+/// @title apply_effect
+/// @author GeorgiKostadinovPro
+/// @notice put a status effect on an entity
+/// @dev custom fn to add the effect, the same effect again does not stack - it lasts for the longer
+/// of the two durations with the stronger of the two powers
+pub fn apply_effect(entity: &mut Entity, status: StatusEffect, game: &mut Game) {
+    if !entity.is_alive || entity.fighter.is_none() || status.turns <= 0 {
+        return;
+    }
+
+    if let Some(active) = entity.effects.iter_mut().find(|active| active.effect == status.effect) {
+        active.turns = active.turns.max(status.turns);
+        active.power = active.power.max(status.power);
+        return;
+    }
+    entity.effects.push(status);
+
+    let is_player = entity.fighter.is_some_and(|f| f.on_death == DeathCallback::Player);
+    let message = if is_player {
+        format!("You are {}!", status.effect.adjective())
+    } else {
+        format!("The {} is {}!", entity.name, status.effect.adjective())
+    };
+    game.messages.add(message, status.effect.color(), MessageCategory::Combat);
+}
+
+/// @title tick_effects
+/// @author GeorgiKostadinovPro
+/// @notice let the status effects of every entity act for a turn
+/// @dev custom fn to hurt the poisoned, heal the regenerating and count down every effect, removing the expired ones
+/// called once every tick of the game clock, however many actions the entities took in it (scheduler.rs)
+/// the player gets the xp of the monsters the poison kills (only the player can poison them)
+pub fn tick_effects(game: &mut Game, entities: &mut [Entity]) {
+    let mut xp_to_gain = 0;
+
+    for (id, entity) in entities.iter_mut().enumerate() {
+        if entity.effects.is_empty() || !entity.is_alive {
+            continue;
+        }
+
+        for status in entity.effects.clone() {
+            match status.effect {
+                // poison damage - nothing for those immune to it (combat.rs)
+                Effect::Poison if resist(status.power, DamageType::Poison, entity) > 0 && entity.is_alive => {
+                    let damage = resist(status.power, DamageType::Poison, entity);
+                    if id == PLAYER {
+                        game.messages.add(
+                            format!("The poison hurts you for {} hit points.", damage),
+                            Effect::Poison.color(),
+                            MessageCategory::Combat
+                        );
+                    }
+                    if let Some(xp) = entity.take_damage(damage, game) && id != PLAYER {
+                        xp_to_gain += xp;
+                    }
+                }
+                Effect::Regeneration if entity.is_alive => {
+                    entity.heal(status.power, game);
+                }
+                _ => {}
+            }
+        }
+
+        // the poison may have killed it, the dead have no effects to count down (entity.rs)
+        if !entity.is_alive {
+            continue;
+        }
+
+        for status in entity.effects.iter_mut() {
+            status.turns -= 1;
+        }
+        let (expired, active): (Vec<StatusEffect>, Vec<StatusEffect>) =
+            std::mem::take(&mut entity.effects).into_iter().partition(|status| status.turns <= 0);
+        entity.effects = active;
+
+        // the monsters out of sight wear off their effects without a word
+        let is_seen = id == PLAYER || game.fov.is_in_fov(entity.x, entity.y);
+        for status in expired.iter().filter(|_| is_seen) {
+            let message = if id == PLAYER {
+                format!("You are no longer {}.", status.effect.adjective())
+            } else {
+                format!("The {} is no longer {}!", entity.name, status.effect.adjective())
+            };
+            game.messages.add(message, WHITE, MessageCategory::Combat);
+        }
+    }
+
+    if let Some(fighter) = entities[PLAYER].fighter.as_mut() {
+        fighter.xp += xp_to_gain;
+    }
+}
+
+/// @title stumble
+/// @author GeorgiKostadinovPro
+/// @notice the direction a confused entity really moves in
+/// @dev custom fn to replace the wanted move with a random one of the 8 directions
+pub fn stumble(game: &mut Game) -> (i32, i32) {
+    STUMBLE_DIRECTIONS[game.rng.gen_range(0, STUMBLE_DIRECTIONS.len())]
+}
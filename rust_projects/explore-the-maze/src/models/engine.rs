@@ -0,0 +1,361 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use tcod::colors::*;
+
+use crate::models::combat::*;
+use crate::models::definitions::*;
+use crate::models::dungeon::*;
+use crate::models::effects::*;
+use crate::models::entity::*;
+use crate::models::experience::*;
+use crate::models::features::*;
+use crate::models::fov::*;
+use crate::models::generators::*;
+use crate::models::item::*;
+use crate::models::maze::*;
+use crate::models::messages::*;
+use crate::models::ranged::*;
+use crate::models::scheduler::*;
+use crate::models::travel::*;
+use crate::models::util::*;
+
+use crate::models::entity::PlayerAction::{TookTurn, DidntTakeTurn};
+
+// picking an item up takes half of a normal action
+pub const PICK_UP_COST: i32 = ENERGY_THRESHOLD / 2;
+
+// everything the player can ask the game to do
+// the window turns key presses into commands (main.rs), a headless driver feeds them from a script
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    // move (or attack) by dx, dy - diagonals too
+    Move(i32, i32),
+    // skip the turn, the monsters still act
+    Wait,
+    // pick up the item the player stands on
+    PickUp,
+    // use the item at this index in the inventory, aimed at a tile if the item needs a target
+    UseItem(usize, Option<(i32, i32)>),
+    // shoot an arrow with the equipped bow at a tile (ranged.rs)
+    Fire(i32, i32),
+    // look around for hidden traps / close an open door next to the player (features.rs)
+    Search,
+    Close,
+    // take the stairs down / up the player stands on
+    Descend,
+    Climb,
+    // spend the xp on the next level, raising the chosen stat
+    LevelUp(Stat),
+    // walk over many turns - to the closest unexplored tile again and again / to an explored tile (travel.rs)
+    Explore,
+    Travel(i32, i32),
+    // switch how the fields of view are computed (fov.rs) - it does not take a turn
+    Fov(FovAlgorithm),
+    Exit
+}
+
+// commands in a script are written one per line:
+// up, down, left, right, upleft, upright, downleft, downright, move <dx> <dy> (each -1, 0 or 1), wait, pickup, use <index>, use <index> <x> <y>, fire <x> <y>, search, close, descend, climb,
+// levelup <hp|power|defense>, explore, travel <x> <y>, fov <shadowcasting|symmetric|permissive>, exit
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Command::*;
+        let words: Vec<&str> = s.split_whitespace().collect();
+        match words.as_slice() {
+            ["up"] => Ok(Move(0, -1)),
+            ["down"] => Ok(Move(0, 1)),
+            ["left"] => Ok(Move(-1, 0)),
+            ["right"] => Ok(Move(1, 0)),
+            ["upleft"] => Ok(Move(-1, -1)),
+            ["upright"] => Ok(Move(1, -1)),
+            ["downleft"] => Ok(Move(-1, 1)),
+            ["downright"] => Ok(Move(1, 1)),
+            ["move", dx, dy] => Ok(Move(parse_step(dx)?, parse_step(dy)?)),
+            ["wait"] => Ok(Wait),
+            ["pickup"] => Ok(PickUp),
+            ["use", index] => Ok(UseItem(parse_number(index)?, None)),
+            ["use", index, x, y] => Ok(UseItem(parse_number(index)?, Some((parse_number(x)?, parse_number(y)?)))),
+            ["fire", x, y] => Ok(Fire(parse_number(x)?, parse_number(y)?)),
+            ["search"] => Ok(Search),
+            ["close"] => Ok(Close),
+            ["descend"] => Ok(Descend),
+            ["climb"] => Ok(Climb),
+            ["levelup", stat] => Ok(LevelUp(stat.parse()?)),
+            ["explore"] => Ok(Explore),
+            ["travel", x, y] => Ok(Travel(parse_number(x)?, parse_number(y)?)),
+            ["fov", algorithm] => Ok(Fov(algorithm.parse()?)),
+            ["exit"] => Ok(Exit),
+            _ => Err(format!("unknown command '{}'", s.trim()))
+        }
+    }
+}
+
+// written back in the script format (a replay stores its commands like that)
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Command::*;
+        match *self {
+            Move(0, -1) => write!(f, "up"),
+            Move(0, 1) => write!(f, "down"),
+            Move(-1, 0) => write!(f, "left"),
+            Move(1, 0) => write!(f, "right"),
+            Move(-1, -1) => write!(f, "upleft"),
+            Move(1, -1) => write!(f, "upright"),
+            Move(-1, 1) => write!(f, "downleft"),
+            Move(1, 1) => write!(f, "downright"),
+            Move(dx, dy) => write!(f, "move {} {}", dx, dy),
+            Wait => write!(f, "wait"),
+            PickUp => write!(f, "pickup"),
+            UseItem(index, None) => write!(f, "use {}", index),
+            UseItem(index, Some((x, y))) => write!(f, "use {} {} {}", index, x, y),
+            Fire(x, y) => write!(f, "fire {} {}", x, y),
+            Search => write!(f, "search"),
+            Close => write!(f, "close"),
+            Descend => write!(f, "descend"),
+            Climb => write!(f, "climb"),
+            LevelUp(stat) => write!(f, "levelup {}", stat),
+            Explore => write!(f, "explore"),
+            Travel(x, y) => write!(f, "travel {} {}", x, y),
+            Fov(algorithm) => write!(f, "fov {}", algorithm),
+            Exit => write!(f, "exit")
+        }
+    }
+}
+
+impl Command {
+    // the energy the command costs the player when it takes a turn (scheduler.rs)
+    // picking an item up is quick, everything else is a full action
+    pub fn cost(self) -> i32 {
+        match self {
+            Command::PickUp => PICK_UP_COST,
+            _ => ENERGY_THRESHOLD
+        }
+    }
+
+    // where the command walks to, None if it is not a walk
+    pub fn destination(self) -> Option<Destination> {
+        match self {
+            Command::Explore => Some(Destination::Unexplored),
+            Command::Travel(x, y) => Some(Destination::Tile(x, y)),
+            _ => None
+        }
+    }
+}
+
+// parse a number from a script command
+fn parse_number<T: FromStr>(s: &str) -> Result<T, String> {
+    s.parse().map_err(|_| format!("expected a number, got '{}'", s))
+}
+
+// parse the dx or dy of a move - the player moves one tile at a time, a script cannot jump him over the walls
+fn parse_step(s: &str) -> Result<i32, String> {
+    match parse_number(s)? {
+        step @ -1..=1 => Ok(step),
+        _ => Err(format!("a move goes one tile at a time (-1, 0 or 1), got '{}'", s))
+    }
+}
+
+/// @title new_game
+/// @author GeorgiKostadinovPro
+/// @notice create a new game from a seed
+/// @dev custom fn to create the player, generate the maze with its monsters and compute the first FOV
+/// the monsters and items are spawned from the definitions (definitions.rs)
+/// generator - the algorithm of every floor, None - a different one as the player goes deeper (generators.rs)
+pub fn new_game(seed: u64, definitions: Definitions, generator: Option<Generator>) -> (Game, Vec<Entity>) {
+    // init a player
+    let mut player = Entity::new(0, 0, '@', WHITE, "go4ko", true);
+    player.is_alive = true;
+    player.fighter = Some(
+        Fighter {
+            max_hp: 30,
+            hp: 30,
+            defense: 2,
+            power: 5,
+            xp: 0,
+            on_death: DeathCallback::Player,
+            on_hit: None,
+            speed: NORMAL_SPEED,
+            sight: TORCH_RADIUS,
+            ranged: None,
+            accuracy: PLAYER_ACCURACY,
+            evasion: PLAYER_EVASION,
+            crit: CRIT_CHANCE,
+            damage: Dice::new(1, 2),
+            damage_type: DamageType::Physical,
+            resistances: Resistances::default()
+        }
+    );
+    // ready for the first action
+    player.energy = ENERGY_THRESHOLD;
+
+    // current entities
+    let mut entities = vec![player];
+
+    // the same seed always generates the same maze with the same monsters
+    let mut rng = rng_from_seed(seed);
+
+    // init game and create the first floor of the dungeon ref maze.rs for more docs
+    // player will be placed in the center of the first generated room
+    // monters will be placed within each generated room on random
+    let mut game = Game {
+        maze: create_maze(
+            &mut entities,
+            1,
+            generator.unwrap_or_else(|| Generator::for_depth(1)),
+            &definitions,
+            &mut rng
+        ),
+        messages: Messages::new(),
+        seed,
+        fov: FovMap::new(MAZE_WIDTH, MAZE_HEIGHT),
+        fov_algorithm: FOV_ALGO,
+        inventory: vec![],
+        // keep rolling with the rng that generated the maze
+        // the deeper floors are generated from it too, once the player gets there
+        rng,
+        depth: 1,
+        floors: BTreeMap::new(),
+        definitions,
+        generator,
+        turn: 1,
+        recording: None,
+        noises: vec![],
+        projectiles: vec![]
+    };
+
+    // add a welcoming message
+    game.messages.add(
+        "Welcome player! Prepare for the adventure of your life.",
+        RED,
+        MessageCategory::System,
+    );
+
+    compute_fov(&mut game, &entities);
+
+    (game, entities)
+}
+
+/// @title compute_fov
+/// @author GeorgiKostadinovPro
+/// @notice move the FOV with the player
+/// @dev custom fn to recompute the FOV from the player position and mark every visible tile as explored
+/// it is read from the maze as it is now (fov.rs) - a new floor needs nothing else
+pub fn compute_fov(game: &mut Game, entities: &[Entity]) {
+    let player = &entities[PLAYER];
+    let radius = player.fighter.map_or(TORCH_RADIUS, |f| f.sight);
+
+    game.fov = field_of_view(&game.maze, player.x, player.y, radius, game.fov_algorithm);
+
+    // visible tiles are explored tiles (fog of war)
+    for x in 0..MAZE_WIDTH {
+        for y in 0..MAZE_HEIGHT {
+            if game.fov.is_in_fov(x, y) {
+                game.maze[x as usize][y as usize].is_explored = true;
+            }
+        }
+    }
+}
+
+/// @title play_turn
+/// @author GeorgiKostadinovPro
+/// @notice play one turn of the game
+/// @dev custom fn to apply the player command and, if it took a turn, let every monster take its turn
+/// the player is told once when he has enough xp to level up, the level up itself is a LevelUp command
+/// a walk (Explore, Travel) plays all its turns at once, the window walks step by step itself (travel.rs)
+/// a recorded game remembers every command played for its replay (replay.rs)
+/// the projectiles shot this turn are kept until the next one, for the window to draw them (ranged.rs)
+pub fn play_turn(command: Command, game: &mut Game, entities: &mut Vec<Entity>) -> PlayerAction {
+    game.projectiles.clear();
+
+    if let Some(destination) = command.destination() {
+        return match walk(destination, game, entities, |_, _| true) {
+            0 => DidntTakeTurn,
+            _ => TookTurn
+        };
+    }
+
+    // a walk is recorded step by step (its moves come back through here), everything else as it is played
+    if let Some(recording) = game.recording.as_mut() {
+        recording.push(command);
+    }
+
+    let could_level_up = can_level_up(&entities[PLAYER]);
+    let player_action = player_act(command, game, entities);
+
+    // the action costs the player energy, then the monsters act (and the turns pass) until he has enough again
+    // every living monster with an ai chases the player while it is in his FOV (scheduler.rs)
+    if player_action == TookTurn {
+        entities[PLAYER].energy -= command.cost();
+
+        // the player has moved => move the FOV with him before the monsters look for him
+        compute_fov(game, entities);
+
+        advance_time(game, entities);
+    }
+
+    if !could_level_up && can_level_up(&entities[PLAYER]) && entities[PLAYER].is_alive {
+        game.messages.add("You feel more experienced. Time to level up!", YELLOW, MessageCategory::System);
+    }
+
+    player_action
+}
+
+// apply the player command
+// exit works whether player is alive/dead, for movement - is_alive must be true
+fn player_act(command: Command, game: &mut Game, entities: &mut Vec<Entity>) -> PlayerAction {
+    match (command, entities[PLAYER].is_alive) {
+        (Command::Exit, _) => PlayerAction::Exit,
+        (Command::Fov(algorithm), _) => {
+            game.fov_algorithm = algorithm;
+            compute_fov(game, entities);
+            game.messages.add(format!("You now see with {} field of view.", algorithm), WHITE, MessageCategory::System);
+            DidntTakeTurn
+        },
+        (Command::Move(dx, dy), true) => {
+            // a confused player does not go where he wants to
+            let (dx, dy) = if has_effect(&entities[PLAYER], Effect::Confusion) { stumble(game) } else { (dx, dy) };
+            Entity::move_by(game, entities, PLAYER, dx, dy);
+            TookTurn
+        },
+        (Command::Wait, true) => TookTurn,
+        (Command::PickUp, true) => {
+            // pick up an item under the player
+            let player_pos = entities[PLAYER].get_pos();
+            let item_id = entities
+                .iter()
+                .position(|entity| entity.get_pos() == player_pos && entity.item.is_some());
+
+            match item_id {
+                Some(item_id) if pick_item_up(item_id, game, entities) => TookTurn,
+                _ => DidntTakeTurn
+            }
+        },
+        (Command::UseItem(inventory_id, target), true) if inventory_id < game.inventory.len() => {
+            match use_item(inventory_id, target, game, entities) {
+                UseResult::UsedUp | UseResult::UsedAndKept => TookTurn,
+                UseResult::Cancelled => DidntTakeTurn
+            }
+        },
+        (Command::Fire(x, y), true) => fire_bow((x, y), game, entities),
+        (Command::Search, true) => search(game, entities),
+        (Command::Close, true) => close_door(game, entities),
+        // the monsters of the new floor do not get a free turn on the arriving player
+        (Command::Descend, true) => {
+            take_stairs(Stairs::Down, game, entities);
+            DidntTakeTurn
+        },
+        (Command::Climb, true) => {
+            take_stairs(Stairs::Up, game, entities);
+            DidntTakeTurn
+        },
+        // leveling up is free, the monsters wait for the player to choose
+        (Command::LevelUp(stat), true) => {
+            level_up(stat, game, entities);
+            DidntTakeTurn
+        },
+        _ => DidntTakeTurn
+    }
+}
@@ -1,7 +1,15 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
 use tcod::colors::*;
-use tcod::console::{Console, BackgroundFlag};
 
-use crate::models::maze::{Game};
+use crate::models::awareness::*;
+use crate::models::combat::*;
+use crate::models::effects::*;
+use crate::models::features::*;
+use crate::models::maze::{Game, PLAYER};
+use crate::models::messages::*;
+use crate::models::ranged::*;
+use crate::models::render::Panel;
 use crate::models::util::*;
 
 // deriving PartialEq lets us use == and != to compare the enums together
@@ -12,8 +20,91 @@ pub enum PlayerAction {
     Exit
 }
 
-// a callback to call into when entity (fighter) dies
+// monster behaviour - only entities with an ai take a turn after the player
+// Basic - chase the player while it sees him (its sight, fov.rs) and attack when next to him
+// a confused monster stumbles around whatever its ai is (the Confusion effect, effects.rs)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Ai {
+    Basic
+}
+
+// items that can be picked up and used from the inventory
+// Heal - a healing potion, restores hp up to max hp
+// Lightning, Fireball, Confuse - spell scrolls (item.rs for more docs)
+// Regenerate, Haste - potions of regeneration and speed, they put a status effect on the player (effects.rs)
+// Sword, Bow, Shield, Helmet - equipment, using it equips / unequips it (equipment.rs for more docs)
+// Arrows - shot with an equipped bow, a stack of them takes one slot (ranged.rs)
+// Dagger - thrown at a target tile, it lands on the floor where it stops (ranged.rs)
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Item {
+    Heal,
+    Lightning,
+    Fireball,
+    Confuse,
+    Regenerate,
+    Haste,
+    Sword,
+    Shield,
+    Helmet,
+    Bow,
+    Arrows,
+    Dagger
+}
+
+// the places on the player's body an equipment can be worn at - one item per slot
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Slot {
+    RightHand,
+    LeftHand,
+    Head
+}
+
+// shown in the messages and the inventory e.g. "equipped on right hand"
+impl fmt::Display for Slot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Slot::RightHand => write!(f, "right hand"),
+            Slot::LeftHand => write!(f, "left hand"),
+            Slot::Head => write!(f, "head")
+        }
+    }
+}
+
+// an item that can be equipped - it stays in the inventory, marked as equipped
+// while equipped its bonuses are added to the player's stats (Entity::power, defense, max_hp)
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Equipment {
+    pub slot: Slot,
+    // not needed in the definitions file (definitions.rs), nothing is equipped on the floor
+    #[serde(default)]
+    pub is_equipped: bool,
+    pub power_bonus: i32,
+    pub defense_bonus: i32,
+    pub max_hp_bonus: i32
+}
+
+// the result of using an item
+// UsedUp - the item is consumed and removed from the inventory
+// UsedAndKept - the item was used but stays in the inventory (e.g. equipment)
+// Cancelled - the item could not be used (e.g. full hp) and stays in the inventory
 #[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UseResult {
+    UsedUp,
+    UsedAndKept,
+    Cancelled
+}
+
+// stairs between two floors of the dungeon
+// Down - leads one floor deeper, placed in the last room of every floor
+// Up - leads back to the floor above, placed where the player arrives on a floor (not on the first one)
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Stairs {
+    Down,
+    Up
+}
+
+// a callback to call into when entity (fighter) dies
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum DeathCallback {
     Player,
     Monster
@@ -24,7 +115,7 @@ pub enum DeathCallback {
 impl DeathCallback {
     fn player_death(player: &mut Entity, game: &mut Game) {
         // the game ended
-        game.messages.add("You died!", RED);
+        game.messages.add("You died!", RED, MessageCategory::Combat);
 
         // for added effect, transform the player into a corpse!
         player.char = '%';
@@ -35,17 +126,29 @@ impl DeathCallback {
     fn monster_death(monster: &mut Entity, game: &mut Game) {
         // transform it into a corpse
         // it doesn't block, can't be attacked and doesn't move
-        game.messages.add(format!("{} is dead!", monster.name), ORANGE);
+        // its xp goes to whoever killed it (take_damage returns it)
+        game.messages.add(
+            format!(
+                "{} is dead! You gain {} experience points.",
+                monster.name,
+                monster.fighter.map_or(0, |f| f.xp)
+            ),
+            ORANGE,
+            MessageCategory::Combat,
+        );
         monster.char = '%';
         monster.color = DARK_RED;
         monster.is_blocking = false;
         monster.is_alive = false;
         monster.fighter = None;
+        monster.ai = None;
         monster.name = format!("remains of {}", monster.name);
     }
 
     fn callback(self, entity: &mut Entity, game: &mut Game) {
         use DeathCallback::*;
+        // the poison or the haste do not outlive the one they were on
+        entity.effects.clear();
         let callback: fn(&mut Entity, &mut Game) = match self {
             Player => DeathCallback::player_death,
             Monster => DeathCallback::monster_death
@@ -55,20 +158,46 @@ impl DeathCallback {
 }
 
 // combat-related properties and methods (monster, player, etc)
+// xp - for a monster the experience it is worth when killed, for the player the experience gained so far
 // on_death - if player died - end game, if monster - then add a corpse
-#[derive(Clone, Copy, Debug, PartialEq)]
+// on_hit - a status effect its attacks put on the target when they do damage (e.g. a spider's poison)
+// speed - the energy gained every tick of the game clock, NORMAL_SPEED acts once a tick (scheduler.rs)
+// sight - how far it sees (fov.rs), the player as far as his torch lights
+// ranged - a monster that shoots arrows at the player from afar (ranged.rs), the player shoots with a bow instead
+// accuracy, evasion, crit - the chances (in %) to hit, to dodge a hit and to hit critically (combat.rs)
+// damage - the dice rolled on top of the power on every hit, damage_type - what kind of harm the hits do
+// resistances - how much of every kind of damage it shrugs off
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Fighter {
     pub max_hp: i32,
     pub hp: i32,
     pub defense: i32,
     pub power: i32,
-    pub on_death: DeathCallback
+    pub xp: i32,
+    pub on_death: DeathCallback,
+    pub on_hit: Option<StatusEffect>,
+    pub speed: i32,
+    pub sight: i32,
+    pub ranged: Option<Ranged>,
+    pub accuracy: i32,
+    pub evasion: i32,
+    pub crit: i32,
+    pub damage: Dice,
+    pub damage_type: DamageType,
+    pub resistances: Resistances
 }
 
 /// This is a generic object: the player, a monster, an item, the stairs...
 /// It's always represented by a character on screen.
 // Entity may not be a fighter -> Option -> init passing None
-#[derive(Debug)]
+// always_visible - drawn on explored tiles even outside the FOV (e.g. the stairs)
+// level - the experience level of the player (experience.rs), grows as he kills monsters
+// effects - the status effects on the entity with the turns they last (effects.rs)
+// energy - spent on actions and regained with the speed of its fighter, it acts only with enough (scheduler.rs)
+// awareness - what a monster knows about the player (awareness.rs), a new one wanders until it sees him
+// count - how many of an item are stacked in it (arrows), 1 for everything else
+// Serialize, Deserialize - entities are saved to disk with the game (save.rs)
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Entity {
     pub x: i32,
     pub y: i32,
@@ -77,7 +206,17 @@ pub struct Entity {
     pub name: String,  
     pub is_blocking: bool,  
     pub is_alive: bool,  
-    pub fighter: Option<Fighter>
+    pub always_visible: bool,
+    pub level: i32,
+    pub fighter: Option<Fighter>,
+    pub ai: Option<Ai>,
+    pub item: Option<Item>,
+    pub equipment: Option<Equipment>,
+    pub stairs: Option<Stairs>,
+    pub effects: Vec<StatusEffect>,
+    pub energy: i32,
+    pub awareness: Awareness,
+    pub count: u32
 }
 
 impl Entity {
@@ -91,7 +230,17 @@ impl Entity {
             name: name.to_string(), 
             is_blocking, 
             is_alive: false, 
-            fighter: None 
+            always_visible: false,
+            level: 1,
+            fighter: None,
+            ai: None,
+            item: None,
+            equipment: None,
+            stairs: None,
+            effects: vec![],
+            energy: 0,
+            awareness: Awareness::Wandering,
+            count: 1
         }
     }
 
@@ -106,9 +255,20 @@ impl Entity {
         self.y = y;
     }
 
+    // distance to another entity
+    pub fn distance_to(&self, other: &Entity) -> f32 {
+        self.distance(other.x, other.y)
+    }
+
+    // distance to a tile
+    pub fn distance(&self, x: i32, y: i32) -> f32 {
+        (((x - self.x).pow(2) + (y - self.y).pow(2)) as f32).sqrt()
+    }
+
     // player takes damage from monster
-    // monster takes damane from player
-    fn take_damage(&mut self, damage: i32, game: &mut Game) {
+    // monster takes damane from player (or from a spell)
+    // returns the xp of the entity if the damage killed it, so the killer can be rewarded
+    pub fn take_damage(&mut self, damage: i32, game: &mut Game) -> Option<i32> {
         // apply damage if possible
         // only if the entity is fighter, can it take damage
         // take as mut because we update the hp
@@ -121,40 +281,126 @@ impl Entity {
             }
 
             if fighter.hp <= 0 {
+                let fighter = *fighter;
                 fighter.on_death.callback(self, game);
+                return Some(fighter.xp);
+            }
+        }
+
+        None
+    }
+
+    // heal by the given amount, without going over the maximum (with the equipment bonus)
+    pub fn heal(&mut self, amount: i32, game: &Game) {
+        let max_hp = self.max_hp(game);
+        if let Some(fighter) = self.fighter.as_mut() {
+            fighter.hp += amount;
+            if fighter.hp > max_hp {
+                fighter.hp = max_hp;
             }
         }
     }
 
+    // the player carries the inventory (in Game), so only he gets bonuses from equipment
+    // monsters fight with their base stats
+    pub fn get_all_equipped(&self, game: &Game) -> Vec<Equipment> {
+        let is_player = self.fighter.is_some_and(|f| f.on_death == DeathCallback::Player);
+        if !is_player {
+            return vec![];
+        }
+
+        game.inventory
+            .iter()
+            .filter_map(|item| item.equipment)
+            .filter(|equipment| equipment.is_equipped)
+            .collect()
+    }
+
+    // effective stats = the base stats of the fighter + the bonuses of everything equipped
+    pub fn power(&self, game: &Game) -> i32 {
+        let base_power = self.fighter.map_or(0, |f| f.power);
+        let bonus: i32 = self.get_all_equipped(game).iter().map(|e| e.power_bonus).sum();
+        base_power + bonus
+    }
+
+    pub fn defense(&self, game: &Game) -> i32 {
+        let base_defense = self.fighter.map_or(0, |f| f.defense);
+        let bonus: i32 = self.get_all_equipped(game).iter().map(|e| e.defense_bonus).sum();
+        base_defense + bonus
+    }
+
+    pub fn max_hp(&self, game: &Game) -> i32 {
+        let base_max_hp = self.fighter.map_or(0, |f| f.max_hp);
+        let bonus: i32 = self.get_all_equipped(game).iter().map(|e| e.max_hp_bonus).sum();
+        base_max_hp + bonus
+    }
+
     // plater attacks monter
     // monster attacks player
-    fn attack(&mut self, target: &mut Entity, game: &mut Game) {
-        // a simple formula for attack damage
-        let damage = self.fighter.map_or(0, |f| f.power) - target.fighter.map_or(0, |f| f.defense);
-        if damage > 0 {
-            // make the target take some damage
-            game.messages.add(
-                format!(
-                    "{} attacks {} for {} hit points.",
-                    self.name, target.name, damage
-                ),
-                WHITE
-            );
+    // the attack may miss, be dodged or hit (critically) - rolled by the combat resolver (combat.rs)
+    pub fn attack(&mut self, target: &mut Entity, game: &mut Game) {
+        // the monsters around hear the fight (awareness.rs)
+        make_noise(game, target.x, target.y, COMBAT_NOISE);
 
-            target.take_damage(damage, game);
-        } else {
+        let (damage, damage_type, is_critical, resistance) = match resolve_attack(self, target, game) {
+            AttackOutcome::Miss => {
+                game.messages.add(format!("{} attacks {} but misses.", self.name, target.name), WHITE, MessageCategory::Combat);
+                return;
+            }
+            AttackOutcome::Dodged => {
+                game.messages.add(
+                    format!("{} attacks {} but {} dodges the blow.", self.name, target.name, target.name),
+                    WHITE,
+                    MessageCategory::Combat
+                );
+                return;
+            }
+            AttackOutcome::Hit { damage, damage_type, is_critical, resistance } => (damage, damage_type, is_critical, resistance)
+        };
+
+        if damage <= 0 {
             game.messages.add(
                 format!(
                     "{} attacks {} but it has no effect!",
                     self.name, target.name
                 ),
-                WHITE
+                WHITE,
+                MessageCategory::Combat
             );
+            return;
+        }
+
+        // make the target take some damage e.g. "orc attacks go4ko with a critical hit for 8 hit points of poison damage!"
+        let kind = match damage_type {
+            DamageType::Physical => String::new(),
+            damage_type => format!(" of {} damage", damage_type)
+        };
+        let (text, color) = if is_critical {
+            (format!("{} attacks {} with a critical hit for {} hit points{}!", self.name, target.name, damage, kind), YELLOW)
+        } else {
+            (format!("{} attacks {} for {} hit points{}.", self.name, target.name, damage, kind), WHITE)
+        };
+        game.messages.add(text, color, MessageCategory::Combat);
+
+        if resistance > 0 {
+            game.messages.add(format!("{} resists {} damage.", target.name, damage_type), WHITE, MessageCategory::Combat);
+        } else if resistance < 0 {
+            game.messages.add(format!("{} is vulnerable to {} damage!", target.name, damage_type), WHITE, MessageCategory::Combat);
+        }
+
+        if let Some(xp) = target.take_damage(damage, game) {
+            // yield experience to the attacker
+            if let Some(fighter) = self.fighter.as_mut() {
+                fighter.xp += xp;
+            }
+        } else if let Some(on_hit) = self.fighter.and_then(|f| f.on_hit) {
+            // the wound carries the attacker's poison (or whatever it hits with)
+            apply_effect(target, on_hit, game);
         }
     }
 
     // move by the given amount
-    // if wall return
+    // if wall return, a closed door is opened and a cracked wall is hit instead
     // self cannot be used because player is &mut, but entities is &, player is in the entitites
     // To guarantee memory safety and no data races, Rust’s references (& and &mut) have a few rules
     // One of them is that when you have a mutable borrow (player), you can’t have any other mutable or immutable borrows into the same data
@@ -164,6 +410,13 @@ impl Entity {
         // check that his next position is not a wall
         let (x, y) = entities[idx].get_pos();
 
+        // a closed door is opened and a cracked wall is hit by the player instead of stepping on them (features.rs)
+        if open_door(idx, x + dx, y + dy, game, entities)
+            || (idx == PLAYER && hit_cracked_wall(x + dx, y + dy, game, entities))
+        {
+            return;
+        }
+
         // check if tile is wall
         if game.maze[(x + dx) as usize][(y + dy) as usize].blocked {
             return;
@@ -184,24 +437,24 @@ impl Entity {
         // tricking Rust (safely) into treating them like different arrays
         if let Some((target_idx, _)) = maybe_target {
             // player attacks monster
+            // the monster does not retaliate here, it attacks back in its own turn (ai.rs)
             let (player, monster) = mut_two(entities, idx, target_idx);
             player.attack(monster, game);
 
-            // monster retaliates
-            if monster.is_alive {
-                monster.attack(player, game);
-            }
-
             return;
         }
         
         entities[idx].set_pos(x + dx, y + dy);
+
+        // only the player springs the traps
+        if idx == PLAYER {
+            spring_trap(game, entities);
+        }
     }
 
-    // set the color and then draw the character that represents this object at its position
-    // dyn highlights that Console is a trait and not a concrete type (such as a struct or enum)
-    pub fn draw(&self, console: &mut dyn Console) {
-        console.set_default_foreground(self.color);
-        console.put_char(self.x, self.y, self.char, BackgroundFlag::None);
+    // draw the character that represents this object at its position, in its color
+    // on a panel of the screen whatever the backend it is shown with (render.rs)
+    pub fn draw(&self, panel: &mut Panel) {
+        panel.put_char(self.x, self.y, self.char, self.color);
     }
 }
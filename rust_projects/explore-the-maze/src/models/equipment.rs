@@ -0,0 +1,64 @@
+use tcod::colors::*;
+
+use crate::models::entity::*;
+use crate::models::maze::*;
+use crate::models::messages::*;
+
+/// @title toggle_equipment
+/// @author GeorgiKostadinovPro
+/// @notice equip or unequip an item from the inventory
+/// @dev custom fn to equip the item at inventory_id if it is not equipped yet, otherwise unequip it
+/// whatever is equipped on the same slot is unequipped first
+pub fn toggle_equipment(inventory_id: usize, game: &mut Game, entities: &mut [Entity]) {
+    let equipment = match game.inventory[inventory_id].equipment {
+        Some(equipment) => equipment,
+        None => return
+    };
+
+    if equipment.is_equipped {
+        unequip(inventory_id, game, entities);
+    } else {
+        // one item per slot - take off the old one first
+        if let Some(current) = get_equipped_in_slot(equipment.slot, &game.inventory) {
+            unequip(current, game, entities);
+        }
+        equip(inventory_id, game);
+    }
+}
+
+/// @title get_equipped_in_slot
+/// @author GeorgiKostadinovPro
+/// @notice find what the player wears on a slot
+/// @dev custom fn to get the inventory index of the item equipped on the slot, None if the slot is free
+pub fn get_equipped_in_slot(slot: Slot, inventory: &[Entity]) -> Option<usize> {
+    inventory.iter().position(|item| {
+        item.equipment
+            .is_some_and(|equipment| equipment.is_equipped && equipment.slot == slot)
+    })
+}
+
+// mark the item as equipped, its bonuses count from now on
+fn equip(inventory_id: usize, game: &mut Game) {
+    let item = &mut game.inventory[inventory_id];
+    if let Some(equipment) = item.equipment.as_mut() {
+        equipment.is_equipped = true;
+        let message = format!("Equipped {} on {}.", item.name, equipment.slot);
+        game.messages.add(message, LIGHT_GREEN, MessageCategory::Loot);
+    }
+}
+
+// mark the item as not equipped
+// taking off a max hp bonus may leave the player with more hp than his new max => cut it down
+fn unequip(inventory_id: usize, game: &mut Game, entities: &mut [Entity]) {
+    let item = &mut game.inventory[inventory_id];
+    if let Some(equipment) = item.equipment.as_mut() {
+        equipment.is_equipped = false;
+        let message = format!("Unequipped {} from {}.", item.name, equipment.slot);
+        game.messages.add(message, LIGHT_YELLOW, MessageCategory::Loot);
+    }
+
+    let max_hp = entities[PLAYER].max_hp(game);
+    if let Some(fighter) = entities[PLAYER].fighter.as_mut() {
+        fighter.hp = fighter.hp.min(max_hp);
+    }
+}
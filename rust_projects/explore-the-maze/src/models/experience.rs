@@ -0,0 +1,103 @@
+use std::fmt;
+use std::str::FromStr;
+use tcod::colors::*;
+
+use crate::models::entity::*;
+use crate::models::maze::*;
+use crate::models::messages::*;
+
+// xp needed to reach level 2, every next level needs LEVEL_UP_FACTOR more
+pub const LEVEL_UP_BASE: i32 = 200;
+pub const LEVEL_UP_FACTOR: i32 = 150;
+
+// how much the chosen stat grows on level up
+pub const LEVEL_UP_HP: i32 = 20;
+pub const LEVEL_UP_POWER: i32 = 1;
+pub const LEVEL_UP_DEFENSE: i32 = 1;
+
+// the stats the player can choose to raise when he levels up
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stat {
+    MaxHp,
+    Power,
+    Defense
+}
+
+// stats in a script: hp, power, defense
+impl FromStr for Stat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hp" => Ok(Stat::MaxHp),
+            "power" => Ok(Stat::Power),
+            "defense" => Ok(Stat::Defense),
+            _ => Err(format!("unknown stat '{}', expected hp, power or defense", s))
+        }
+    }
+}
+
+impl fmt::Display for Stat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Stat::MaxHp => write!(f, "hp"),
+            Stat::Power => write!(f, "power"),
+            Stat::Defense => write!(f, "defense")
+        }
+    }
+}
+
+/// @title level_up_xp
+/// @author GeorgiKostadinovPro
+/// @notice how much xp the next level needs
+/// @dev custom fn to get the xp threshold to level up from the given level - it grows with every level
+pub fn level_up_xp(level: i32) -> i32 {
+    LEVEL_UP_BASE + level * LEVEL_UP_FACTOR
+}
+
+/// @title can_level_up
+/// @author GeorgiKostadinovPro
+/// @notice check if the player has enough xp for the next level
+/// @dev custom fn to compare the player's xp with the threshold of his level
+pub fn can_level_up(player: &Entity) -> bool {
+    player.fighter.is_some_and(|f| f.xp >= level_up_xp(player.level))
+}
+
+/// @title level_up
+/// @author GeorgiKostadinovPro
+/// @notice level the player up and raise the chosen stat
+/// @dev custom fn to spend the xp of the threshold on a new level, returns false if there is not enough xp
+/// more hp raises both the max and the current hp
+pub fn level_up(stat: Stat, game: &mut Game, entities: &mut [Entity]) -> bool {
+    if !can_level_up(&entities[PLAYER]) {
+        return false;
+    }
+
+    let player = &mut entities[PLAYER];
+    let xp_needed = level_up_xp(player.level);
+    player.level += 1;
+
+    if let Some(fighter) = player.fighter.as_mut() {
+        // the leftover xp counts towards the next level
+        fighter.xp -= xp_needed;
+
+        match stat {
+            Stat::MaxHp => {
+                fighter.max_hp += LEVEL_UP_HP;
+                fighter.hp += LEVEL_UP_HP;
+            }
+            Stat::Power => fighter.power += LEVEL_UP_POWER,
+            Stat::Defense => fighter.defense += LEVEL_UP_DEFENSE
+        }
+    }
+
+    game.messages.add(
+        format!(
+            "Your battle skills grow stronger! You reached level {}!",
+            player.level
+        ),
+        YELLOW,
+        MessageCategory::System,
+    );
+    true
+}
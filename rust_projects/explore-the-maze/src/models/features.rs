@@ -0,0 +1,258 @@
+use std::fmt;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tcod::colors::*;
+
+use crate::models::awareness::*;
+use crate::models::combat::*;
+use crate::models::engine::*;
+use crate::models::entity::*;
+use crate::models::maze::*;
+use crate::models::messages::*;
+
+use crate::models::entity::PlayerAction::{TookTurn, DidntTakeTurn};
+
+// the spikes of a spike trap hurt like a blow (physical damage)
+pub const SPIKE_DAMAGE: i32 = 6;
+
+// how far (in tiles, through the walls) an alarm trap is heard - much further than a fight
+pub const ALARM_NOISE: i32 = 30;
+
+// searching looks this far around the player, every hidden trap there is found with this chance (in %)
+pub const SEARCH_RADIUS: i32 = 2;
+pub const SEARCH_CHANCE: i32 = 50;
+
+// how many hit points of blows a cracked wall takes before it crumbles
+pub const CRACKED_WALL_HP: i32 = 10;
+
+// the kinds of traps
+// Spike - spikes come out of the floor
+// Teleport - takes the player to a random place of the floor
+// Alarm - rings a loud bell, the monsters far around hear it (awareness.rs)
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Trap {
+    Spike,
+    Teleport,
+    Alarm
+}
+
+// shown in the messages and when looking at the tile e.g. "spike trap"
+impl fmt::Display for Trap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Trap::Spike => write!(f, "spike trap"),
+            Trap::Teleport => write!(f, "teleport trap"),
+            Trap::Alarm => write!(f, "alarm trap")
+        }
+    }
+}
+
+// what else there is on a tile of the maze besides the floor or the wall
+// Door - a closed one blocks the sight (not the way - walking into it opens it), an open one blocks nothing
+// Trap - hidden until the player finds it by searching or steps on it, it springs only on him stepping on it unaware
+// CrackedWall - a wall the player can break through, it crumbles to floor when its hp are gone
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Feature {
+    Door { is_open: bool },
+    Trap { trap: Trap, is_found: bool },
+    CrackedWall { hp: i32 }
+}
+
+impl Feature {
+    // how the feature is drawn over its tile, None - nothing to draw (a hidden trap)
+    pub fn glyph(self) -> Option<(char, Color)> {
+        match self {
+            Feature::Door { is_open: false } => Some(('+', DARK_ORANGE)),
+            Feature::Door { is_open: true } => Some(('\'', DARK_ORANGE)),
+            Feature::Trap { is_found: false, .. } => None,
+            Feature::Trap { trap: Trap::Spike, .. } => Some(('^', LIGHT_RED)),
+            Feature::Trap { trap: Trap::Teleport, .. } => Some(('^', LIGHT_VIOLET)),
+            Feature::Trap { trap: Trap::Alarm, .. } => Some(('^', YELLOW)),
+            Feature::CrackedWall { .. } => Some(('%', GREY))
+        }
+    }
+
+    // what the player knows the tile is, None - it looks like a plain floor or wall
+    pub fn name(self) -> Option<&'static str> {
+        match self {
+            Feature::Door { is_open: false } => Some("closed door"),
+            Feature::Door { is_open: true } => Some("open door"),
+            Feature::Trap { is_found: false, .. } => None,
+            Feature::Trap { trap: Trap::Spike, .. } => Some("spike trap"),
+            Feature::Trap { trap: Trap::Teleport, .. } => Some("teleport trap"),
+            Feature::Trap { trap: Trap::Alarm, .. } => Some("alarm trap"),
+            Feature::CrackedWall { .. } => Some("cracked wall")
+        }
+    }
+}
+
+/// @title open_door
+/// @author GeorgiKostadinovPro
+/// @notice open a closed door someone walks into
+/// @dev custom fn to open the closed door on (x, y) - it no longer blocks the sight, so the FOV is computed again
+/// opening it takes the turn, the one who opened it steps through in the next one
+/// returns false if there is no closed door there
+pub fn open_door(id: usize, x: i32, y: i32, game: &mut Game, entities: &[Entity]) -> bool {
+    let tile = &mut game.maze[x as usize][y as usize];
+    if tile.feature != Some(Feature::Door { is_open: false }) {
+        return false;
+    }
+
+    tile.block_sight = false;
+    tile.feature = Some(Feature::Door { is_open: true });
+
+    if id == PLAYER {
+        game.messages.add("You open the door.", WHITE, MessageCategory::System);
+    } else if game.fov.is_in_fov(entities[id].x, entities[id].y) {
+        game.messages.add(format!("The {} opens a door.", entities[id].name), WHITE, MessageCategory::System);
+    }
+
+    compute_fov(game, entities);
+    true
+}
+
+/// @title close_door
+/// @author GeorgiKostadinovPro
+/// @notice close an open door next to the player
+/// @dev custom fn to close the first open door around the player with nothing on it (a monster, an item)
+/// it blocks the sight again, so the FOV is computed again
+pub fn close_door(game: &mut Game, entities: &[Entity]) -> PlayerAction {
+    let (player_x, player_y) = entities[PLAYER].get_pos();
+
+    let door = neighbours(player_x, player_y).find(|&(x, y)| {
+        game.maze[x as usize][y as usize].feature == Some(Feature::Door { is_open: true })
+            && !entities.iter().any(|entity| entity.get_pos() == (x, y))
+    });
+
+    let Some((x, y)) = door else {
+        game.messages.add("There is no open door next to you to close.", WHITE, MessageCategory::System);
+        return DidntTakeTurn;
+    };
+
+    let tile = &mut game.maze[x as usize][y as usize];
+    tile.block_sight = true;
+    tile.feature = Some(Feature::Door { is_open: false });
+    game.messages.add("You close the door.", WHITE, MessageCategory::System);
+
+    compute_fov(game, entities);
+    TookTurn
+}
+
+/// @title hit_cracked_wall
+/// @author GeorgiKostadinovPro
+/// @notice break through a cracked wall
+/// @dev custom fn to take the power of the player off the hp of the cracked wall on (x, y) he walks into,
+/// with no hp left it crumbles to floor - loud enough for the monsters around to hear (awareness.rs)
+/// only the player breaks through - the monsters walk around the cracked walls (ai.rs)
+/// returns false if there is no cracked wall there
+pub fn hit_cracked_wall(x: i32, y: i32, game: &mut Game, entities: &[Entity]) -> bool {
+    let Some(Feature::CrackedWall { hp }) = game.maze[x as usize][y as usize].feature else {
+        return false;
+    };
+
+    let hp = hp - entities[PLAYER].power(game);
+    if hp > 0 {
+        game.maze[x as usize][y as usize].feature = Some(Feature::CrackedWall { hp });
+        game.messages.add("You hit the cracked wall.", WHITE, MessageCategory::System);
+        return true;
+    }
+
+    game.maze[x as usize][y as usize] = Tile { is_explored: true, ..Tile::empty() };
+    game.messages.add("The cracked wall crumbles to rubble!", LIGHT_GREY, MessageCategory::System);
+    make_noise(game, x, y, COMBAT_NOISE);
+
+    compute_fov(game, entities);
+    true
+}
+
+/// @title search
+/// @author GeorgiKostadinovPro
+/// @notice look around for hidden traps
+/// @dev custom fn to find every hidden trap within SEARCH_RADIUS of the player, each one with SEARCH_CHANCE
+/// it takes the turn whether anything is found or not - the player may have to search a few times
+pub fn search(game: &mut Game, entities: &[Entity]) -> PlayerAction {
+    let (player_x, player_y) = entities[PLAYER].get_pos();
+    let mut found = vec![];
+
+    for x in (player_x - SEARCH_RADIUS)..=(player_x + SEARCH_RADIUS) {
+        for y in (player_y - SEARCH_RADIUS)..=(player_y + SEARCH_RADIUS) {
+            if x < 0 || y < 0 || x >= MAZE_WIDTH || y >= MAZE_HEIGHT {
+                continue;
+            }
+
+            if let Some(Feature::Trap { trap, is_found: false }) = game.maze[x as usize][y as usize].feature
+                && game.rng.gen_range(0, 100) < SEARCH_CHANCE
+            {
+                game.maze[x as usize][y as usize].feature = Some(Feature::Trap { trap, is_found: true });
+                found.push(trap);
+            }
+        }
+    }
+
+    if found.is_empty() {
+        game.messages.add("You search around but find nothing.", WHITE, MessageCategory::System);
+    }
+    for trap in found {
+        game.messages.add(format!("You find a {}!", trap), YELLOW, MessageCategory::System);
+    }
+
+    TookTurn
+}
+
+/// @title spring_trap
+/// @author GeorgiKostadinovPro
+/// @notice spring the trap the player stepped on
+/// @dev custom fn to spring the hidden trap under the player - it is found after that, a found trap is stepped over carefully
+/// spike - SPIKE_DAMAGE physical damage, teleport - to a random free floor tile, alarm - ALARM_NOISE around the trap
+/// the monsters do not spring traps, they know their floor
+pub fn spring_trap(game: &mut Game, entities: &mut [Entity]) {
+    let (x, y) = entities[PLAYER].get_pos();
+    let Some(Feature::Trap { trap, is_found: false }) = game.maze[x as usize][y as usize].feature else {
+        return;
+    };
+    game.maze[x as usize][y as usize].feature = Some(Feature::Trap { trap, is_found: true });
+
+    match trap {
+        Trap::Spike => {
+            let damage = resist(SPIKE_DAMAGE, DamageType::Physical, &entities[PLAYER]);
+            game.messages.add(
+                format!("You step on a spike trap! The spikes pierce you for {} hit points.", damage),
+                LIGHT_RED,
+                MessageCategory::Combat
+            );
+            entities[PLAYER].take_damage(damage, game);
+        }
+        Trap::Teleport => {
+            game.messages.add("You step on a teleport trap and the world spins around you!", LIGHT_VIOLET, MessageCategory::System);
+            // nowhere to land - the player stays where he is
+            if let Some((x, y)) = random_free_tile(game, entities) {
+                entities[PLAYER].set_pos(x, y);
+            }
+        }
+        Trap::Alarm => {
+            game.messages.add("You step on an alarm trap and a loud bell rings out!", YELLOW, MessageCategory::System);
+            make_noise(game, x, y, ALARM_NOISE);
+        }
+    }
+}
+
+// the 8 tiles around (x, y)
+fn neighbours(x: i32, y: i32) -> impl Iterator<Item = (i32, i32)> {
+    (-1..=1)
+        .flat_map(move |dx| (-1..=1).map(move |dy| (x + dx, y + dy)))
+        .filter(move |&neighbour| neighbour != (x, y))
+}
+
+// a random floor tile with nothing blocking on it and no feature (a teleport does not land on another trap)
+// None if there is no such tile
+fn random_free_tile(game: &mut Game, entities: &[Entity]) -> Option<(i32, i32)> {
+    let free: Vec<(i32, i32)> = (0..MAZE_WIDTH)
+        .flat_map(|x| (0..MAZE_HEIGHT).map(move |y| (x, y)))
+        .filter(|&(x, y)| game.maze[x as usize][y as usize].feature.is_none() && !is_blocked(x, y, &game.maze, entities))
+        .collect();
+
+    if free.is_empty() {
+        return None;
+    }
+    Some(free[game.rng.gen_range(0, free.len())])
+}
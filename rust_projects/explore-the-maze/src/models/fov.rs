@@ -0,0 +1,338 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+use crate::models::maze::*;
+
+// how far the player sees (the radius of his torch), also the sight of a monster without one in the definitions
+pub const TORCH_RADIUS: i32 = 10;
+
+// the algorithm a new game starts with - it can be switched while playing (the fov command)
+pub const FOV_ALGO: FovAlgorithm = FovAlgorithm::Symmetric;
+
+// the 8 octants of recursive shadowcasting - the multipliers turning an octant into maze coordinates
+const OCTANTS: [(i32, i32, i32, i32); 8] = [
+    (1, 0, 0, 1), (0, 1, 1, 0), (0, -1, 1, 0), (-1, 0, 0, 1),
+    (-1, 0, 0, -1), (0, -1, -1, 0), (0, 1, -1, 0), (1, 0, 0, -1)
+];
+
+// the points of a tile a permissive line of sight may start or end at - its center and a little inside its corners
+const TILE_POINTS: [(f64, f64); 5] = [(0.5, 0.5), (0.05, 0.05), (0.95, 0.05), (0.05, 0.95), (0.95, 0.95)];
+
+// how the visible tiles are found, every one from Tile::block_sight (walls in sight are lit)
+// Shadowcasting - recursive shadowcasting, octant by octant, the walls cast shadows behind them
+// Symmetric - symmetric shadowcasting, a floor tile is seen from another only if it sees it back
+// (a monster sees the player exactly when he sees it)
+// Permissive - a tile is seen if any line from the eye to any part of it is clear, the most tiles of the three
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FovAlgorithm {
+    Shadowcasting,
+    Symmetric,
+    Permissive
+}
+
+impl FovAlgorithm {
+    // the next algorithm, to switch between them with a key
+    pub fn next(self) -> Self {
+        use FovAlgorithm::*;
+        match self {
+            Shadowcasting => Symmetric,
+            Symmetric => Permissive,
+            Permissive => Shadowcasting
+        }
+    }
+}
+
+// the name used by the fov command and in the messages e.g. "fov permissive"
+impl fmt::Display for FovAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FovAlgorithm::Shadowcasting => write!(f, "shadowcasting"),
+            FovAlgorithm::Symmetric => write!(f, "symmetric"),
+            FovAlgorithm::Permissive => write!(f, "permissive")
+        }
+    }
+}
+
+impl FromStr for FovAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "shadowcasting" => Ok(FovAlgorithm::Shadowcasting),
+            "symmetric" => Ok(FovAlgorithm::Symmetric),
+            "permissive" => Ok(FovAlgorithm::Permissive),
+            _ => Err(format!("unknown fov algorithm '{}', expected shadowcasting, symmetric or permissive", s))
+        }
+    }
+}
+
+// the tiles seen from one spot of the maze - computed by field_of_view
+// nothing is seen outside the maze
+#[derive(Clone, Debug, PartialEq)]
+pub struct FovMap {
+    width: i32,
+    height: i32,
+    visible: Vec<bool>
+}
+
+impl FovMap {
+    // constructor - nothing is seen yet
+    pub fn new(width: i32, height: i32) -> Self {
+        FovMap { width, height, visible: vec![false; (width * height) as usize] }
+    }
+
+    pub fn is_in_fov(&self, x: i32, y: i32) -> bool {
+        self.contains(x, y) && self.visible[(x * self.height + y) as usize]
+    }
+
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && x < self.width && y < self.height
+    }
+
+    // mark a tile as seen, if it is in the maze and within the radius from the eye
+    fn reveal(&mut self, (x, y): (i32, i32), (eye_x, eye_y): (i32, i32), radius: i32) {
+        let is_in_radius = (x - eye_x).pow(2) + (y - eye_y).pow(2) <= radius * radius;
+        if self.contains(x, y) && is_in_radius {
+            self.visible[(x * self.height + y) as usize] = true;
+        }
+    }
+}
+
+/// @title field_of_view
+/// @author GeorgiKostadinovPro
+/// @notice find every tile seen from a spot of the maze
+/// @dev custom fn to compute the FOV from (x, y) up to radius tiles away with the algorithm
+/// the sight is blocked by the tiles that block_sight (and by the edge of the maze), the eye always sees its own tile
+pub fn field_of_view(maze: &Maze, x: i32, y: i32, radius: i32, algorithm: FovAlgorithm) -> FovMap {
+    let mut fov = FovMap::new(maze.len() as i32, maze.first().map_or(0, |column| column.len() as i32));
+    fov.reveal((x, y), (x, y), radius);
+
+    match algorithm {
+        FovAlgorithm::Shadowcasting => {
+            for octant in OCTANTS {
+                cast_light(maze, &mut fov, (x, y), radius, 1, 1.0, 0.0, octant);
+            }
+        }
+        FovAlgorithm::Symmetric => symmetric_shadowcasting(maze, &mut fov, (x, y), radius),
+        FovAlgorithm::Permissive => permissive(maze, &mut fov, (x, y), radius)
+    }
+
+    fov
+}
+
+/// @title can_see
+/// @author GeorgiKostadinovPro
+/// @notice check if one spot of the maze is seen from another
+/// @dev custom fn to compute the FOV of the eye (e.g. a monster with its own sight radius) and look for the target in it
+pub fn can_see(maze: &Maze, eye: (i32, i32), target: (i32, i32), radius: i32, algorithm: FovAlgorithm) -> bool {
+    // too far, no need to look
+    if (target.0 - eye.0).pow(2) + (target.1 - eye.1).pow(2) > radius * radius {
+        return false;
+    }
+    field_of_view(maze, eye.0, eye.1, radius, algorithm).is_in_fov(target.0, target.1)
+}
+
+// outside the maze is as good as a wall
+fn blocks_sight(maze: &Maze, x: i32, y: i32) -> bool {
+    x < 0 || y < 0
+        || maze.get(x as usize).and_then(|column| column.get(y as usize)).is_none_or(|tile| tile.block_sight)
+}
+
+// recursive shadowcasting of one octant, row by row from the eye
+// the slopes start..end are the part of the row not in shadow yet, a wall narrows it and casts the rest as a new scan
+#[allow(clippy::too_many_arguments)]
+fn cast_light(
+    maze: &Maze,
+    fov: &mut FovMap,
+    eye: (i32, i32),
+    radius: i32,
+    row: i32,
+    mut start: f64,
+    end: f64,
+    (xx, xy, yx, yy): (i32, i32, i32, i32)
+) {
+    if start < end {
+        return;
+    }
+
+    let mut new_start = 0.0;
+    for distance in row..=radius {
+        let dy = -distance;
+        let mut is_blocked = false;
+
+        for dx in -distance..=0 {
+            let (x, y) = (eye.0 + dx * xx + dy * xy, eye.1 + dx * yx + dy * yy);
+            let left_slope = (dx as f64 - 0.5) / (dy as f64 + 0.5);
+            let right_slope = (dx as f64 + 0.5) / (dy as f64 - 0.5);
+
+            if start < right_slope {
+                continue;
+            }
+            if end > left_slope {
+                break;
+            }
+
+            fov.reveal((x, y), eye, radius);
+
+            if is_blocked {
+                // still in the shadow of a wall
+                if blocks_sight(maze, x, y) {
+                    new_start = right_slope;
+                } else {
+                    is_blocked = false;
+                    start = new_start;
+                }
+            } else if blocks_sight(maze, x, y) && distance < radius {
+                // a wall starts, scan what is left of the octant behind it
+                is_blocked = true;
+                cast_light(maze, fov, eye, radius, distance + 1, start, left_slope, (xx, xy, yx, yy));
+                new_start = right_slope;
+            }
+        }
+
+        if is_blocked {
+            break;
+        }
+    }
+}
+
+// a slope of symmetric shadowcasting as a fraction - exact, so the symmetry does not depend on rounding
+#[derive(Clone, Copy)]
+struct Slope {
+    num: i32,
+    den: i32
+}
+
+// a row of a quadrant being scanned - depth tiles from the eye, between two slopes
+#[derive(Clone, Copy)]
+struct Row {
+    depth: i32,
+    start: Slope,
+    end: Slope
+}
+
+impl Row {
+    // the first and the last column of the row, the tiles exactly half way are included
+    fn columns(&self) -> (i32, i32) {
+        let first = (2 * self.depth * self.start.num + self.start.den).div_euclid(2 * self.start.den);
+        let last = -((self.end.den - 2 * self.depth * self.end.num).div_euclid(2 * self.end.den));
+        (first, last)
+    }
+
+    // a floor tile is seen only if its center is within the slopes - then it sees the eye too
+    fn is_symmetric(&self, column: i32) -> bool {
+        column * self.start.den >= self.depth * self.start.num && column * self.end.den <= self.depth * self.end.num
+    }
+
+    fn next(&self) -> Row {
+        Row { depth: self.depth + 1, ..*self }
+    }
+}
+
+// symmetric shadowcasting (Albert Ford) in the 4 quadrants around the eye
+// the walls are lit when any part of them is seen, the floor tiles only when seen symmetrically
+fn symmetric_shadowcasting(maze: &Maze, fov: &mut FovMap, eye: (i32, i32), radius: i32) {
+    for quadrant in 0..4 {
+        // a tile of the quadrant (depth, column) in maze coordinates
+        let transform = |depth: i32, column: i32| match quadrant {
+            0 => (eye.0 + column, eye.1 - depth),
+            1 => (eye.0 + depth, eye.1 + column),
+            2 => (eye.0 + column, eye.1 + depth),
+            _ => (eye.0 - depth, eye.1 + column)
+        };
+
+        let mut rows = vec![Row { depth: 1, start: Slope { num: -1, den: 1 }, end: Slope { num: 1, den: 1 } }];
+        while let Some(mut row) = rows.pop() {
+            if row.depth > radius {
+                continue;
+            }
+
+            let mut was_wall = None;
+            let (first, last) = row.columns();
+            for column in first..=last {
+                let (x, y) = transform(row.depth, column);
+                let is_wall = blocks_sight(maze, x, y);
+
+                if is_wall || row.is_symmetric(column) {
+                    fov.reveal((x, y), eye, radius);
+                }
+
+                // the slope of the left edge of the tile
+                let slope = Slope { num: 2 * column - 1, den: 2 * row.depth };
+                if was_wall == Some(true) && !is_wall {
+                    row.start = slope;
+                }
+                if was_wall == Some(false) && is_wall {
+                    rows.push(Row { end: slope, ..row.next() });
+                }
+                was_wall = Some(is_wall);
+            }
+
+            if was_wall == Some(false) {
+                rows.push(row.next());
+            }
+        }
+    }
+}
+
+// a tile is seen if any of its points can be reached by a line from any point of the eye tile
+fn permissive(maze: &Maze, fov: &mut FovMap, eye: (i32, i32), radius: i32) {
+    for x in eye.0 - radius..=eye.0 + radius {
+        for y in eye.1 - radius..=eye.1 + radius {
+            let is_seen = TILE_POINTS.iter().any(|&(ex, ey)| {
+                let from = (eye.0 as f64 + ex, eye.1 as f64 + ey);
+                TILE_POINTS
+                    .iter()
+                    .any(|&(tx, ty)| is_line_clear(maze, eye, (x, y), from, (x as f64 + tx, y as f64 + ty)))
+            });
+            if is_seen {
+                fov.reveal((x, y), eye, radius);
+            }
+        }
+    }
+}
+
+// walk the line from a point of the eye tile to a point of the target tile, tile by tile (every tile it crosses)
+// clear if no tile between the two blocks sight - the target itself may be a wall
+fn is_line_clear(
+    maze: &Maze,
+    eye: (i32, i32),
+    target: (i32, i32),
+    (from_x, from_y): (f64, f64),
+    (to_x, to_y): (f64, f64)
+) -> bool {
+    let (dx, dy) = (to_x - from_x, to_y - from_y);
+    let (step_x, step_y) = (dx.signum() as i32, dy.signum() as i32);
+
+    // how far along the line the next column / row starts, and the length of a tile in each direction
+    let to_edge = |from: f64, d: f64| if d > 0.0 { from.floor() + 1.0 - from } else { from - from.floor() };
+    let (mut next_x, delta_x) = if dx == 0.0 { (f64::INFINITY, 0.0) } else { (to_edge(from_x, dx) / dx.abs(), 1.0 / dx.abs()) };
+    let (mut next_y, delta_y) = if dy == 0.0 { (f64::INFINITY, 0.0) } else { (to_edge(from_y, dy) / dy.abs(), 1.0 / dy.abs()) };
+
+    let (mut x, mut y) = eye;
+    while (x, y) != target {
+        if (x, y) != eye && blocks_sight(maze, x, y) {
+            return false;
+        }
+
+        // the line ends in this tile without reaching the target
+        if next_x.min(next_y) > 1.0 {
+            return false;
+        }
+
+        // exactly through a corner - on to the diagonal tile, squeezing between the two next to it
+        let (crosses_x, crosses_y) = (next_x <= next_y, next_y <= next_x);
+        if crosses_x {
+            x += step_x;
+            next_x += delta_x;
+        }
+        if crosses_y {
+            y += step_y;
+            next_y += delta_y;
+        }
+    }
+
+    true
+}
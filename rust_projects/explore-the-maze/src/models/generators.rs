@@ -0,0 +1,540 @@
+use std::cmp;
+use std::str::FromStr;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::models::connectivity::*;
+use crate::models::features::*;
+use crate::models::maze::*;
+
+// max num of room + max/min size of rooms
+const MAX_ROOMS: i32 = 30;
+pub const ROOM_MIN_SIZE: i32 = 5;
+pub const ROOM_MAX_SIZE: i32 = 10;
+
+// a BSP leaf is not split any further once it is smaller than two of these
+const BSP_MIN_LEAF_SIZE: i32 = ROOM_MAX_SIZE + 1;
+
+// caves: the chance of a wall in the first random fill and how many times it is smoothed
+const CAVE_WALL_CHANCE: f32 = 0.45;
+const CAVE_SMOOTHING_STEPS: i32 = 5;
+
+// drunkard's walk: dig until this part of the maze is open, each walker takes this many steps
+const DRUNKARD_OPEN_PART: f32 = 0.4;
+const DRUNKARD_STEPS: i32 = 200;
+
+// caves and tunnels have no rooms - they are cut into squares of this size to spawn in
+// a square with less open tiles than CAVE_REGION_MIN_TILES is too cramped to be a region
+const CAVE_REGION_SIZE: i32 = 12;
+const CAVE_REGION_MIN_TILES: usize = 12;
+
+// rooms and tunnels: the chance (in %) of a door in a room entrance, of a hidden trap on a tile of a tunnel
+// and of a thin wall between a tunnel and another open tile to be cracked
+const DOOR_CHANCE: u32 = 50;
+const TRAP_CHANCE: u32 = 3;
+const CRACKED_WALL_CHANCE: u32 = 10;
+
+// a trap of a tunnel is one of these at random
+const TRAPS: [Trap; 3] = [Trap::Spike, Trap::Teleport, Trap::Alarm];
+
+// the generators are picked by depth (unless one is chosen at startup) - they repeat in this order
+const GENERATORS_BY_DEPTH: [Generator; 4] = [
+    Generator::Rooms,
+    Generator::Bsp,
+    Generator::Caves,
+    Generator::DrunkardsWalk
+];
+
+// the algorithms a floor of the dungeon can be generated with
+// Rooms - random non-intersecting rooms joined by L-shaped tunnels (the classic one)
+// Bsp - the maze split in two again and again (binary space partitioning), a room in every part
+// Caves - random noise smoothed by a cellular automaton into open caves
+// DrunkardsWalk - tunnels dug by walkers stumbling around in random directions
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Generator {
+    Rooms,
+    Bsp,
+    Caves,
+    DrunkardsWalk
+}
+
+// generator names on the command line: rooms, bsp, caves, drunkard
+impl FromStr for Generator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rooms" => Ok(Generator::Rooms),
+            "bsp" => Ok(Generator::Bsp),
+            "caves" => Ok(Generator::Caves),
+            "drunkard" => Ok(Generator::DrunkardsWalk),
+            _ => Err(format!("unknown generator '{}', expected rooms, bsp, caves or drunkard", s))
+        }
+    }
+}
+
+// a part of the maze monsters and items are spawned in (a room, a part of a cave)
+// center - where the player starts (first region) or the stairs down go (last region)
+// tiles - every walkable tile of the region
+#[derive(Clone, Debug, PartialEq)]
+pub struct Region {
+    pub center: (i32, i32),
+    pub tiles: Vec<(i32, i32)>
+}
+
+// what every generator produces - the maze and where to spawn in it
+// regions are never empty, the player starts in the first one
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layout {
+    pub maze: Maze,
+    pub regions: Vec<Region>
+}
+
+impl Generator {
+    // the generator of a floor when none was chosen at startup
+    pub fn for_depth(depth: u32) -> Generator {
+        GENERATORS_BY_DEPTH[(depth.max(1) - 1) as usize % GENERATORS_BY_DEPTH.len()]
+    }
+
+    // generate the layout of a floor, every random roll comes from rng
+    pub fn generate<R: Rng>(self, rng: &mut R) -> Layout {
+        use Generator::*;
+        match self {
+            Rooms => generate_rooms(rng),
+            Bsp => generate_bsp(rng),
+            Caves => generate_caves(rng),
+            DrunkardsWalk => generate_drunkards_walk(rng)
+        }
+    }
+}
+
+// Room struct for a maze room
+// Use Clone & Copy traits to not pass Room as a reference each time
+#[derive(Clone, Copy, Debug)]
+struct Room {
+    x1: i32,
+    y1: i32,
+    x2: i32,
+    y2: i32,
+}
+
+impl Room {
+    pub fn new(x: i32, y: i32, w: i32, h: i32) -> Self {
+        Room {
+            x1: x,
+            y1: y,
+            x2: x + w,
+            y2: y + h,
+        }
+    }
+
+    // find the xenter of each room
+    // e.g top left (10, 10), bottom right (20, 20) => center ((x1 + x2) / 2, (y1 + y2) / 2) = (15, 15)
+    pub fn center(&self) -> (i32, i32) {
+        let center_x = (self.x1 + self.x2) / 2;
+        let center_y = (self.y1 + self.y2) / 2;
+        (center_x, center_y)
+    }
+
+    // check if two rooms intersect
+    // ensure no rooms go over each other
+    pub fn intersects_with(&self, other: &Room) -> bool {
+        // returns true if this intersects with another one
+        (self.x1 <= other.x2)
+            && (self.x2 >= other.x1)
+            && (self.y1 <= other.y2)
+            && (self.y2 >= other.y1)
+    }
+
+    // check if the tile is inside the room (not on its walls)
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x > self.x1 && x < self.x2 && y > self.y1 && y < self.y2
+    }
+
+    // check if the tile is on a wall of the room (not on a corner)
+    // returns the way the wall goes - true if it is horizontal (the top or the bottom one)
+    fn wall_at(&self, x: i32, y: i32) -> Option<bool> {
+        if (y == self.y1 || y == self.y2) && x > self.x1 && x < self.x2 {
+            Some(true)
+        } else if (x == self.x1 || x == self.x2) && y > self.y1 && y < self.y2 {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    // the room as a spawn region - its inner (empty) tiles
+    fn region(&self) -> Region {
+        let mut tiles = vec![];
+        for x in (self.x1 + 1)..self.x2 {
+            for y in (self.y1 + 1)..self.y2 {
+                tiles.push((x, y));
+            }
+        }
+
+        Region { center: self.center(), tiles }
+    }
+}
+
+/// @title create_room
+/// @author GeorgiKostadinovPro
+/// @notice create a custom room in maze
+/// @dev custom fn to create an empty custom room within maze ((x, y), (x + dx, y + dy))
+fn create_room(maze: &mut Maze, room: Room) {
+    // go through the tiles in the room and make them passable
+    // from x + 1 and y1 + 1 so that only inside the room is empty, not the walls
+    // A..B means A is inclused up to B (exclusive)
+    for x in (room.x1 + 1)..room.x2 {
+        for y in (room.y1 + 1)..room.y2 {
+            maze[x as usize][y as usize] = Tile::empty();
+        }
+    }
+}
+
+/// @title create_tunnel
+/// @author GeorgiKostadinovPro
+/// @notice create a custom tunnel in maze
+/// @dev custom fn to create an empty custom tunnel within maze
+fn create_tunnel(maze: &mut Maze, x1: i32, x2: i32, y1: i32, y2: i32, is_horizontal: bool) {
+    // the tunner can be horizontal or vertical - isHorizontal
+    // isHorizontal - loop in maze only on rows, not cols
+    // in rust jagged array x - cols, y - rows. In C# x - rows, y - cols
+    // x1 and x2 are the start and end, y1 is the height, y2 = 0 not needed
+    // !isHorizontal - tunnel is vertical loop only through cols, not rows
+    // y1 and y2 are the start and end, x1 is the width, x2 = 0 not needed
+    // min & max ensure that we always start with the smaller number (1, 5) is the same as (5, 1)
+    // otherwise the for loop will not produce result
+    if is_horizontal {
+        for x in cmp::min(x1, x2)..cmp::max(x1, x2) {
+            maze[x as usize][y1 as usize] = Tile::empty();
+        }
+    } else {
+        for y in cmp::min(y1, y2)..cmp::max(y1, y2) {
+            maze[x1 as usize][y as usize] = Tile::empty();
+        }
+    }
+}
+
+// join two room centers with an L-shaped tunnel
+fn connect_rooms<R: Rng>(maze: &mut Maze, (prev_x, prev_y): (i32, i32), (x, y): (i32, i32), rng: &mut R) {
+    // toss a coin (random bool value -- either true or false)
+    if rng.r#gen() {
+        // first move horizontally, then vertically
+        create_tunnel(maze, prev_x, x, prev_y, 0, true);
+        create_tunnel(maze, x, 0, prev_y, y, false);
+    } else {
+        // first move vertically, then horizontally
+        create_tunnel(maze, prev_x, 0, prev_y, y, false);
+        create_tunnel(maze, prev_x, x, y, 0, true);
+    }
+}
+
+/// @title place_features
+/// @author GeorgiKostadinovPro
+/// @notice put doors, traps and cracked walls in the rooms and tunnels
+/// @dev custom fn to go through what create_room and create_tunnel carved out of the maze:
+/// an entrance - a tunnel going through the wall of a room (with walls on both sides) - may get a closed door
+/// a tile of a tunnel (outside of every room) may get a hidden trap
+/// a thin wall between a tunnel and another open tile may be cracked - breaking it makes a shortcut
+/// doors and traps do not block the way and a cracked wall was a wall anyway, so the maze stays connected
+fn place_features<R: Rng>(maze: &mut Maze, rooms: &[Room], rng: &mut R) {
+    let is_open = |maze: &Maze, x: i32, y: i32| !maze[x as usize][y as usize].blocked;
+    let is_tunnel = |maze: &Maze, x: i32, y: i32| {
+        is_open(maze, x, y) && rooms.iter().all(|room| !room.contains(x, y) && room.wall_at(x, y).is_none())
+    };
+
+    // the border stays as it is, every tile checked has 4 neighbours
+    for x in 1..MAZE_WIDTH - 1 {
+        for y in 1..MAZE_HEIGHT - 1 {
+            let (left, right) = (is_open(maze, x - 1, y), is_open(maze, x + 1, y));
+            let (up, down) = (is_open(maze, x, y - 1), is_open(maze, x, y + 1));
+
+            if !is_open(maze, x, y) {
+                let is_thin = (left && right && !up && !down) || (up && down && !left && !right);
+                let by_tunnel = is_tunnel(maze, x - 1, y) || is_tunnel(maze, x + 1, y)
+                    || is_tunnel(maze, x, y - 1) || is_tunnel(maze, x, y + 1);
+                if is_thin && by_tunnel && rng.gen_range(0, 100) < CRACKED_WALL_CHANCE {
+                    maze[x as usize][y as usize] = Tile::cracked_wall();
+                }
+                continue;
+            }
+
+            if rooms.iter().any(|room| room.contains(x, y)) {
+                continue;
+            }
+
+            match rooms.iter().find_map(|room| room.wall_at(x, y)) {
+                // the walls of the room go on at both sides of the entrance
+                Some(true) if !left && !right && rng.gen_range(0, 100) < DOOR_CHANCE => {
+                    maze[x as usize][y as usize] = Tile::door();
+                }
+                Some(false) if !up && !down && rng.gen_range(0, 100) < DOOR_CHANCE => {
+                    maze[x as usize][y as usize] = Tile::door();
+                }
+                Some(_) => {}
+                None if rng.gen_range(0, 100) < TRAP_CHANCE => {
+                    maze[x as usize][y as usize] = Tile::trap(TRAPS[rng.gen_range(0, TRAPS.len())]);
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+// fill maze with wall tiles, then when creating rooms, tunnels, etc wall => empty
+fn walls() -> Maze {
+    vec![vec![Tile::wall(); MAZE_HEIGHT as usize]; MAZE_WIDTH as usize]
+}
+
+/// @title generate_rooms
+/// @author GeorgiKostadinovPro
+/// @notice the classic maze - rooms and tunnels
+/// @dev custom fn to place up to MAX_ROOMS random rooms that do not intersect
+/// and connect every room to the previous one with a tunnel, then put doors, traps and cracked walls in them
+fn generate_rooms<R: Rng>(rng: &mut R) -> Layout {
+    let mut maze = walls();
+
+    // after populating the vec => loop it and call create_room()
+    let mut rooms: Vec<Room> = vec![];
+
+    // generate rooms and tunnels on random
+    for _ in 0..MAX_ROOMS {
+        // random width and height
+        let w = rng.gen_range(ROOM_MIN_SIZE, ROOM_MAX_SIZE + 1);
+        let h = rng.gen_range(ROOM_MIN_SIZE, ROOM_MAX_SIZE + 1);
+        // random position without going out of the boundaries of the map
+        // maze is 80x45 ensure room start (x1, y1) (x1 + w, y2 + h) <= borders
+        let x = rng.gen_range(0, MAZE_WIDTH - w);
+        let y = rng.gen_range(0, MAZE_HEIGHT - h);
+
+        // init a room
+        let room = Room::new(x, y, w, h);
+
+        // loop the other rooms and see if any intersect with this one
+        // if ture any() will abort and return true
+        let failed = rooms
+            .iter()
+            .any(|other_room| room.intersects_with(other_room));
+
+        if failed {
+            continue;
+        }
+
+        // insert the room in the maze with empty tiles
+        create_room(&mut maze, room);
+
+        // all rooms after the first:
+        // connect it to the previous room (the curr last room) with a tunnel
+        if let Some(prev_room) = rooms.last() {
+            connect_rooms(&mut maze, prev_room.center(), room.center(), rng);
+        }
+
+        // add room and use it to check the next ones
+        rooms.push(room);
+    }
+
+    place_features(&mut maze, &rooms, rng);
+
+    let regions = rooms.iter().map(|room| room.region()).collect();
+    Layout { maze, regions }
+}
+
+/// @title generate_bsp
+/// @author GeorgiKostadinovPro
+/// @notice rooms in a binary space partition
+/// @dev custom fn to split the maze in two again and again, put a room in every leaf
+/// and connect the two halves of every split with a tunnel - the rooms never overlap
+/// the doors, traps and cracked walls are placed like in generate_rooms
+fn generate_bsp<R: Rng>(rng: &mut R) -> Layout {
+    let mut maze = walls();
+    let mut rooms = vec![];
+
+    // the last column and row stay walls, like in generate_rooms
+    split_bsp(&mut maze, (0, 0, MAZE_WIDTH - 1, MAZE_HEIGHT - 1), &mut rooms, rng);
+    place_features(&mut maze, &rooms, rng);
+
+    let regions = rooms.iter().map(|room| room.region()).collect();
+    Layout { maze, regions }
+}
+
+// split the leaf (x, y, w, h) or put a room in it if it is too small to split
+fn split_bsp<R: Rng>(maze: &mut Maze, (x, y, w, h): (i32, i32, i32, i32), rooms: &mut Vec<Room>, rng: &mut R) {
+    let can_split_vertically = w >= 2 * BSP_MIN_LEAF_SIZE;
+    let can_split_horizontally = h >= 2 * BSP_MIN_LEAF_SIZE;
+
+    // a leaf - a random room inside it
+    if !can_split_vertically && !can_split_horizontally {
+        let room_w = rng.gen_range(ROOM_MIN_SIZE, cmp::min(ROOM_MAX_SIZE, w) + 1);
+        let room_h = rng.gen_range(ROOM_MIN_SIZE, cmp::min(ROOM_MAX_SIZE, h) + 1);
+        let room_x = x + rng.gen_range(0, w - room_w + 1);
+        let room_y = y + rng.gen_range(0, h - room_h + 1);
+
+        let room = Room::new(room_x, room_y, room_w, room_h);
+        create_room(maze, room);
+        rooms.push(room);
+        return;
+    }
+
+    // split along the longer side if both are possible
+    let vertically = match (can_split_vertically, can_split_horizontally) {
+        (true, true) => w > h || (w == h && rng.r#gen()),
+        (vertically, _) => vertically
+    };
+
+    let (first_half, second_half) = if vertically {
+        let split = rng.gen_range(BSP_MIN_LEAF_SIZE, w - BSP_MIN_LEAF_SIZE + 1);
+        ((x, y, split, h), (x + split, y, w - split, h))
+    } else {
+        let split = rng.gen_range(BSP_MIN_LEAF_SIZE, h - BSP_MIN_LEAF_SIZE + 1);
+        ((x, y, w, split), (x, y + split, w, h - split))
+    };
+
+    split_bsp(maze, first_half, rooms, rng);
+    let second_rooms = rooms.len();
+    split_bsp(maze, second_half, rooms, rng);
+
+    // connect the last room of the first half to the first room of the second half
+    // both halves are connected inside already, so the whole part is connected after this
+    let from = rooms[second_rooms - 1].center();
+    let to = rooms[second_rooms].center();
+    connect_rooms(maze, from, to, rng);
+}
+
+/// @title generate_caves
+/// @author GeorgiKostadinovPro
+/// @notice open caves from a cellular automaton
+/// @dev custom fn to fill the maze with random walls, smooth them with the 4-5 rule
+/// (a tile is a wall if 5 of its 9 tiles are walls) and keep only the biggest cave so all of it is reachable
+fn generate_caves<R: Rng>(rng: &mut R) -> Layout {
+    loop {
+        let mut maze = walls();
+
+        // random noise inside the border
+        for x in 1..MAZE_WIDTH - 1 {
+            for y in 1..MAZE_HEIGHT - 1 {
+                if rng.r#gen::<f32>() >= CAVE_WALL_CHANCE {
+                    maze[x as usize][y as usize] = Tile::empty();
+                }
+            }
+        }
+
+        for _ in 0..CAVE_SMOOTHING_STEPS {
+            maze = smooth_caves(&maze);
+        }
+
+        // the caves not connected to the biggest one are filled in
+        let biggest = open_areas(&maze).into_iter().max_by_key(|area| area.len());
+        let biggest = match biggest {
+            Some(biggest) => biggest,
+            None => continue
+        };
+
+        let mut caves = walls();
+        for &(x, y) in &biggest {
+            caves[x as usize][y as usize] = Tile::empty();
+        }
+
+        // a too small cave is rolled again
+        let regions = grid_regions(&caves);
+        if regions.len() >= 2 {
+            return Layout { maze: caves, regions };
+        }
+    }
+}
+
+// one step of the cellular automaton, the border stays walls
+fn smooth_caves(maze: &Maze) -> Maze {
+    let mut smoothed = walls();
+
+    for x in 1..MAZE_WIDTH - 1 {
+        for y in 1..MAZE_HEIGHT - 1 {
+            // the tile itself and its 8 neighbours
+            let mut walls = 0;
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    if maze[(x + dx) as usize][(y + dy) as usize].blocked {
+                        walls += 1;
+                    }
+                }
+            }
+
+            if walls < 5 {
+                smoothed[x as usize][y as usize] = Tile::empty();
+            }
+        }
+    }
+
+    smoothed
+}
+
+/// @title generate_drunkards_walk
+/// @author GeorgiKostadinovPro
+/// @notice winding tunnels dug by drunk walkers
+/// @dev custom fn to let walkers stumble around from the open tiles, digging every tile they step on
+/// until DRUNKARD_OPEN_PART of the maze is open - every walker starts on a dug tile so all of it is connected
+fn generate_drunkards_walk<R: Rng>(rng: &mut R) -> Layout {
+    let mut maze = walls();
+
+    // the first walker starts in the middle
+    let mut open = vec![(MAZE_WIDTH / 2, MAZE_HEIGHT / 2)];
+    maze[(MAZE_WIDTH / 2) as usize][(MAZE_HEIGHT / 2) as usize] = Tile::empty();
+
+    let inside = ((MAZE_WIDTH - 2) * (MAZE_HEIGHT - 2)) as f32;
+    while (open.len() as f32) < inside * DRUNKARD_OPEN_PART {
+        // every next walker starts from a random open tile
+        let (mut x, mut y) = open[rng.gen_range(0, open.len())];
+
+        for _ in 0..DRUNKARD_STEPS {
+            let (dx, dy) = [(0, -1), (0, 1), (-1, 0), (1, 0)][rng.gen_range(0, 4)];
+
+            // never dig the border
+            if x + dx < 1 || y + dy < 1 || x + dx > MAZE_WIDTH - 2 || y + dy > MAZE_HEIGHT - 2 {
+                continue;
+            }
+
+            x += dx;
+            y += dy;
+            if maze[x as usize][y as usize].blocked {
+                maze[x as usize][y as usize] = Tile::empty();
+                open.push((x, y));
+            }
+        }
+    }
+
+    let regions = grid_regions(&maze);
+    Layout { maze, regions }
+}
+
+// cut a maze without rooms into squares, every square with enough open tiles is a region
+// its center is the open tile closest to the middle of its open tiles
+fn grid_regions(maze: &Maze) -> Vec<Region> {
+    let mut regions = vec![];
+
+    for square_x in (0..MAZE_WIDTH).step_by(CAVE_REGION_SIZE as usize) {
+        for square_y in (0..MAZE_HEIGHT).step_by(CAVE_REGION_SIZE as usize) {
+            let mut tiles = vec![];
+            for x in square_x..cmp::min(square_x + CAVE_REGION_SIZE, MAZE_WIDTH) {
+                for y in square_y..cmp::min(square_y + CAVE_REGION_SIZE, MAZE_HEIGHT) {
+                    if !maze[x as usize][y as usize].blocked {
+                        tiles.push((x, y));
+                    }
+                }
+            }
+
+            if tiles.len() < CAVE_REGION_MIN_TILES {
+                continue;
+            }
+
+            let middle_x = tiles.iter().map(|&(x, _)| x).sum::<i32>() / tiles.len() as i32;
+            let middle_y = tiles.iter().map(|&(_, y)| y).sum::<i32>() / tiles.len() as i32;
+            let center = *tiles
+                .iter()
+                .min_by_key(|&&(x, y)| (x - middle_x).pow(2) + (y - middle_y).pow(2))
+                .unwrap_or(&tiles[0]);
+
+            regions.push(Region { center, tiles });
+        }
+    }
+
+    regions
+}
@@ -0,0 +1,72 @@
+use crate::models::definitions::*;
+use crate::models::engine::*;
+use crate::models::generators::*;
+use crate::models::entity::*;
+use crate::models::maze::*;
+use crate::models::travel::*;
+
+// drive the game without a window (no Root, no Offscreen)
+// feed scripted commands, advance turns and inspect game and entities afterwards
+pub struct Simulation {
+    pub game: Game,
+    pub entities: Vec<Entity>,
+    // number of turns the player took
+    pub turns: u32
+}
+
+impl Simulation {
+    // constructor - the same seed always starts the same game
+    // with the built in monster and item definitions and the generators changing with the depth
+    pub fn new(seed: u64) -> Self {
+        Simulation::with_options(seed, Definitions::default(), None)
+    }
+
+    // the same, with the monster and item definitions from a file (definitions.rs)
+    // and a generator for every floor (generators.rs)
+    pub fn with_options(seed: u64, definitions: Definitions, generator: Option<Generator>) -> Self {
+        let (game, entities) = new_game(seed, definitions, generator);
+        Simulation { game, entities, turns: 0 }
+    }
+
+    pub fn player(&self) -> &Entity {
+        &self.entities[PLAYER]
+    }
+
+    // the game is over once the player is dead
+    pub fn is_over(&self) -> bool {
+        !self.entities[PLAYER].is_alive
+    }
+
+    // play a single command (the monsters act too if the player took a turn)
+    // a walk (explore, travel) counts every step it took as a turn
+    pub fn step(&mut self, command: Command) -> PlayerAction {
+        if let Some(destination) = command.destination() {
+            let turns = walk(destination, &mut self.game, &mut self.entities, |_, _| true);
+            self.turns += turns;
+            return if turns > 0 { PlayerAction::TookTurn } else { PlayerAction::DidntTakeTurn };
+        }
+
+        let player_action = play_turn(command, &mut self.game, &mut self.entities);
+        if player_action == PlayerAction::TookTurn {
+            self.turns += 1;
+        }
+        player_action
+    }
+
+    // play the commands one by one until the script ends, the player exits or dies
+    // returns how many commands were played
+    pub fn run<I: IntoIterator<Item = Command>>(&mut self, commands: I) -> usize {
+        let mut played = 0;
+        for command in commands {
+            if self.is_over() {
+                break;
+            }
+
+            played += 1;
+            if self.step(command) == PlayerAction::Exit {
+                break;
+            }
+        }
+        played
+    }
+}
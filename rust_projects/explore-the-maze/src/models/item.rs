@@ -0,0 +1,328 @@
+use tcod::colors::*;
+
+use crate::models::combat::*;
+use crate::models::effects::*;
+use crate::models::entity::*;
+use crate::models::equipment::*;
+use crate::models::maze::*;
+use crate::models::messages::*;
+use crate::models::ranged::*;
+
+// the player can carry up to 26 items (one per letter in the inventory menu)
+pub const INVENTORY_SIZE: usize = 26;
+
+// how much a healing potion heals
+pub const HEAL_AMOUNT: i32 = 4;
+
+// lightning strikes the closest monster in FOV within its range
+pub const LIGHTNING_DAMAGE: i32 = 40;
+pub const LIGHTNING_RANGE: i32 = 5;
+
+// fireball burns everything (the player too) within its radius around the target tile
+pub const FIREBALL_RADIUS: i32 = 3;
+pub const FIREBALL_DAMAGE: i32 = 12;
+
+// confusion makes the target monster stumble around for a number of turns
+pub const CONFUSE_RANGE: i32 = 8;
+pub const CONFUSE_NUM_TURNS: i32 = 10;
+
+/// @title needs_target
+/// @author GeorgiKostadinovPro
+/// @notice check if an item must be aimed
+/// @dev custom fn to tell the window (or a script) that a target tile must be picked before the item is used
+pub fn needs_target(item: Item) -> bool {
+    use Item::*;
+    match item {
+        Fireball | Confuse | Dagger => true,
+        Heal | Lightning | Regenerate | Haste | Sword | Shield | Helmet | Bow | Arrows => false
+    }
+}
+
+/// @title target_range
+/// @author GeorgiKostadinovPro
+/// @notice how far from the player an item can be aimed
+/// @dev custom fn to get the max range of an aimed item, None - any tile in the FOV
+pub fn target_range(item: Item) -> Option<f32> {
+    match item {
+        Item::Confuse => Some(CONFUSE_RANGE as f32),
+        Item::Dagger => Some(DAGGER_RANGE as f32),
+        _ => None
+    }
+}
+
+/// @title is_valid_target
+/// @author GeorgiKostadinovPro
+/// @notice check if a tile can be aimed at
+/// @dev custom fn to check the tile is inside the maze, in the player's FOV and within the max range
+pub fn is_valid_target(x: i32, y: i32, max_range: Option<f32>, game: &Game, entities: &[Entity]) -> bool {
+    let in_maze = x >= 0 && y >= 0 && x < MAZE_WIDTH && y < MAZE_HEIGHT;
+    let in_range = max_range.is_none_or(|range| entities[PLAYER].distance(x, y) <= range);
+
+    in_maze && in_range && game.fov.is_in_fov(x, y)
+}
+
+/// @title pick_item_up
+/// @author GeorgiKostadinovPro
+/// @notice pick an item up from the maze
+/// @dev custom fn to move an item from the entities to the player's inventory if there is room for it
+/// arrows go onto the stack already in the inventory (even a full one), they do not take another slot
+/// returns true if the item was picked up
+pub fn pick_item_up(item_id: usize, game: &mut Game, entities: &mut Vec<Entity>) -> bool {
+    let stack = game.inventory.iter().position(|item| {
+        item.item == Some(Item::Arrows) && item.item == entities[item_id].item
+    });
+
+    if let Some(stack) = stack {
+        let item = entities.swap_remove(item_id);
+        game.inventory[stack].count += item.count;
+        game.messages.add(
+            format!("You picked up {} ({})!", item.name, game.inventory[stack].count),
+            GREEN,
+            MessageCategory::Loot
+        );
+        true
+    } else if game.inventory.len() >= INVENTORY_SIZE {
+        game.messages.add(
+            format!(
+                "Your inventory is full, cannot pick up {}.",
+                entities[item_id].name
+            ),
+            RED,
+            MessageCategory::Loot,
+        );
+        false
+    } else {
+        // swap_remove is O(1) - the last entity takes the place of the picked item
+        // the player is always the first entity so he never moves
+        let item = entities.swap_remove(item_id);
+        game.messages.add(format!("You picked up a {}!", item.name), GREEN, MessageCategory::Loot);
+        game.inventory.push(item);
+        true
+    }
+}
+
+/// @title use_item
+/// @author GeorgiKostadinovPro
+/// @notice use an item from the inventory
+/// @dev custom fn to call the item's use function and remove the item from the inventory if it was used up
+/// target is the tile picked by the player for the items that need one (needs_target)
+/// equipment is never used up, using it equips or unequips it
+/// a thrown dagger leaves the inventory and lands in the maze (ranged.rs), arrows are only shot with a bow
+pub fn use_item(
+    inventory_id: usize,
+    target: Option<(i32, i32)>,
+    game: &mut Game,
+    entities: &mut Vec<Entity>
+) -> UseResult {
+    use Item::*;
+
+    // just call the "use_function" if it is defined
+    if let Some(item) = game.inventory[inventory_id].item {
+        let on_use = match item {
+            Heal => cast_heal,
+            Lightning => cast_lightning,
+            Fireball => cast_fireball,
+            Confuse => cast_confuse,
+            Regenerate => drink_regeneration,
+            Haste => drink_haste,
+            Sword | Bow | Shield | Helmet => {
+                toggle_equipment(inventory_id, game, entities);
+                return UseResult::UsedAndKept;
+            }
+            Dagger => return throw_dagger(inventory_id, target, game, entities),
+            Arrows => {
+                game.messages.add("Arrows are shot with an equipped bow.", WHITE, MessageCategory::Loot);
+                return UseResult::Cancelled;
+            }
+        };
+
+        let result = on_use(target, game, entities);
+        if result == UseResult::UsedUp {
+            // destroy after use, unless it was cancelled for some reason
+            game.inventory.remove(inventory_id);
+        }
+        result
+    } else {
+        game.messages.add(
+            format!("The {} cannot be used.", game.inventory[inventory_id].name),
+            WHITE,
+            MessageCategory::Loot,
+        );
+        UseResult::Cancelled
+    }
+}
+
+// heal the player, a potion is wasted if the player is at full health => cancel
+fn cast_heal(_target: Option<(i32, i32)>, game: &mut Game, entities: &mut [Entity]) -> UseResult {
+    if let Some(fighter) = entities[PLAYER].fighter {
+        if fighter.hp >= entities[PLAYER].max_hp(game) {
+            game.messages.add("You are already at full health.", RED, MessageCategory::Loot);
+            return UseResult::Cancelled;
+        }
+
+        game.messages.add("Your wounds start to feel better!", LIGHT_VIOLET, MessageCategory::Loot);
+        entities[PLAYER].heal(HEAL_AMOUNT, game);
+        return UseResult::UsedUp;
+    }
+
+    UseResult::Cancelled
+}
+
+// the player gets the xp of the monsters killed by his spells
+fn gain_xp(xp: i32, entities: &mut [Entity]) {
+    if let Some(fighter) = entities[PLAYER].fighter.as_mut() {
+        fighter.xp += xp;
+    }
+}
+
+// find the closest monster in the player's FOV, up to a maximum range
+fn closest_monster(max_range: i32, game: &Game, entities: &[Entity]) -> Option<usize> {
+    let mut closest_enemy = None;
+    // start with (slightly more than) maximum range
+    let mut closest_dist = (max_range + 1) as f32;
+
+    for (id, entity) in entities.iter().enumerate() {
+        if id != PLAYER
+            && entity.fighter.is_some()
+            && entity.ai.is_some()
+            && game.fov.is_in_fov(entity.x, entity.y)
+        {
+            // calculate distance between this entity and the player
+            let dist = entities[PLAYER].distance_to(entity);
+            if dist < closest_dist {
+                // it's closer, so remember it
+                closest_enemy = Some(id);
+                closest_dist = dist;
+            }
+        }
+    }
+
+    closest_enemy
+}
+
+// strike the closest monster in FOV, nothing to strike => cancel and keep the scroll
+fn cast_lightning(_target: Option<(i32, i32)>, game: &mut Game, entities: &mut [Entity]) -> UseResult {
+    // find closest enemy (inside a maximum range) and damage it
+    let monster_id = closest_monster(LIGHTNING_RANGE, game, entities);
+
+    if let Some(monster_id) = monster_id {
+        // zap it!
+        game.messages.add(
+            format!(
+                "A lightning bolt strikes the {} with a loud thunder! The damage is {} hit points.",
+                entities[monster_id].name, LIGHTNING_DAMAGE
+            ),
+            LIGHT_BLUE,
+            MessageCategory::Combat,
+        );
+        if let Some(xp) = entities[monster_id].take_damage(LIGHTNING_DAMAGE, game) {
+            gain_xp(xp, entities);
+        }
+        UseResult::UsedUp
+    } else {
+        // no enemy found within maximum range
+        game.messages.add("No enemy is close enough to strike.", RED, MessageCategory::Combat);
+        UseResult::Cancelled
+    }
+}
+
+// burn every fighter within the radius around the target tile
+fn cast_fireball(target: Option<(i32, i32)>, game: &mut Game, entities: &mut [Entity]) -> UseResult {
+    // the target must be a tile the player can see
+    let (x, y) = match target {
+        Some((x, y)) if is_valid_target(x, y, None, game, entities) => (x, y),
+        _ => {
+            game.messages.add("Cancelled", WHITE, MessageCategory::System);
+            return UseResult::Cancelled;
+        }
+    };
+
+    game.messages.add(
+        format!(
+            "The fireball explodes, burning everything within {} tiles!",
+            FIREBALL_RADIUS
+        ),
+        ORANGE,
+        MessageCategory::Combat,
+    );
+
+    // the xp of the burned monsters goes to the player after the explosion (he may be burned too)
+    // it is fire damage - less for those who resist fire (nothing for the immune), more for those who are vulnerable to it (combat.rs)
+    let mut xp_to_gain = 0;
+    for (id, entity) in entities.iter_mut().enumerate() {
+        if entity.distance(x, y) <= FIREBALL_RADIUS as f32 && entity.fighter.is_some() {
+            let damage = resist(FIREBALL_DAMAGE, DamageType::Fire, entity);
+            if damage == 0 {
+                game.messages.add(
+                    format!("The {} resists {} damage.", entity.name, DamageType::Fire),
+                    ORANGE,
+                    MessageCategory::Combat
+                );
+                continue;
+            }
+            game.messages.add(
+                format!(
+                    "The {} gets burned for {} hit points.",
+                    entity.name, damage
+                ),
+                ORANGE,
+                MessageCategory::Combat,
+            );
+            if let Some(xp) = entity.take_damage(damage, game) && id != PLAYER {
+                xp_to_gain += xp;
+            }
+        }
+    }
+    gain_xp(xp_to_gain, entities);
+
+    UseResult::UsedUp
+}
+
+// confuse the monster on the target tile, it stumbles around instead of acting
+fn cast_confuse(target: Option<(i32, i32)>, game: &mut Game, entities: &mut [Entity]) -> UseResult {
+    let max_range = target_range(Item::Confuse);
+
+    // the target must be a monster the player can see within range
+    let monster_id = target
+        .filter(|&(x, y)| is_valid_target(x, y, max_range, game, entities))
+        .and_then(|(x, y)| {
+            entities.iter().position(|entity| {
+                entity.get_pos() == (x, y) && entity.fighter.is_some() && entity.ai.is_some()
+            })
+        });
+
+    if let Some(monster_id) = monster_id {
+        game.messages.add(
+            format!(
+                "The eyes of {} look vacant, as he starts to stumble around!",
+                entities[monster_id].name
+            ),
+            LIGHT_GREEN,
+            MessageCategory::Combat,
+        );
+
+        // it stumbles around instead of following its ai until the confusion wears off
+        let confusion = StatusEffect::new(Effect::Confusion, CONFUSE_NUM_TURNS, 0);
+        apply_effect(&mut entities[monster_id], confusion, game);
+        UseResult::UsedUp
+    } else {
+        // no enemy found on the target tile
+        game.messages.add("No enemy to confuse there.", RED, MessageCategory::Combat);
+        UseResult::Cancelled
+    }
+}
+
+// heal a little every turn for a while, drinking it again only makes it last longer
+fn drink_regeneration(_target: Option<(i32, i32)>, game: &mut Game, entities: &mut [Entity]) -> UseResult {
+    game.messages.add("You feel your body mending itself.", LIGHT_VIOLET, MessageCategory::Loot);
+    let regeneration = StatusEffect::new(Effect::Regeneration, REGENERATION_TURNS, REGENERATION_AMOUNT);
+    apply_effect(&mut entities[PLAYER], regeneration, game);
+    UseResult::UsedUp
+}
+
+// act twice for every turn of the monsters for a while
+fn drink_haste(_target: Option<(i32, i32)>, game: &mut Game, entities: &mut [Entity]) -> UseResult {
+    game.messages.add("The world around you slows down.", LIGHT_VIOLET, MessageCategory::Loot);
+    apply_effect(&mut entities[PLAYER], StatusEffect::new(Effect::Haste, HASTE_TURNS, 0), game);
+    UseResult::UsedUp
+}
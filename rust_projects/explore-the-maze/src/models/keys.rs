@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use serde::Deserialize;
+
+// the key bindings file read at startup, next to the game (like the definitions)
+pub const KEYS_FILE: &str = "data/keys.json";
+
+// the names of the keys that are not a single character - every backend names its key presses so (tcod_backend.rs, terminal_backend.rs)
+// a printable key is named by its character e.g. "h", ">", "?"
+pub const SPECIAL_KEYS: &[&str] = &[
+    "up", "down", "left", "right",
+    "home", "end", "pageup", "pagedown", "insert", "delete",
+    "enter", "escape", "space", "tab", "backspace",
+    "kp0", "kp1", "kp2", "kp3", "kp4", "kp5", "kp6", "kp7", "kp8", "kp9", "kpenter"
+];
+
+// everything a key can be bound to
+// the moves go in 8 directions, the rest are the player's commands and screens of the window
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+    Wait,
+    PickUp,
+    Inventory,
+    Fire,
+    Search,
+    Close,
+    Descend,
+    Climb,
+    Explore,
+    Look,
+    Character,
+    Messages,
+    Help,
+    Fov,
+    Exit
+}
+
+impl Action {
+    // the move by dx, dy of a direction, None for the other actions
+    pub fn direction(self) -> Option<(i32, i32)> {
+        use Action::*;
+        match self {
+            Up => Some((0, -1)),
+            Down => Some((0, 1)),
+            Left => Some((-1, 0)),
+            Right => Some((1, 0)),
+            UpLeft => Some((-1, -1)),
+            UpRight => Some((1, -1)),
+            DownLeft => Some((-1, 1)),
+            DownRight => Some((1, 1)),
+            _ => None
+        }
+    }
+
+    // what the action does, for the help screen
+    pub fn description(self) -> &'static str {
+        use Action::*;
+        match self {
+            Up => "move / attack up",
+            Down => "move / attack down",
+            Left => "move / attack left",
+            Right => "move / attack right",
+            UpLeft => "move / attack up-left",
+            UpRight => "move / attack up-right",
+            DownLeft => "move / attack down-left",
+            DownRight => "move / attack down-right",
+            Wait => "wait a turn",
+            PickUp => "pick up an item",
+            Inventory => "use an item from the inventory",
+            Fire => "shoot the bow",
+            Search => "search for hidden traps",
+            Close => "close a door",
+            Descend => "take the stairs down",
+            Climb => "take the stairs up",
+            Explore => "explore",
+            Look => "look around",
+            Character => "character sheet",
+            Messages => "message log",
+            Help => "this help",
+            Fov => "switch the field of view algorithm",
+            Exit => "save and exit"
+        }
+    }
+}
+
+// the built in sets of movement keys
+// Arrows - the arrow keys, 4 directions
+// Numpad - the numeric keypad, 8 directions and 5 to wait
+// Vi - hjkl and yubn for the diagonals (like vi and the classic roguelikes)
+// the arrow keys and the other commands are bound in every preset
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    Arrows,
+    Numpad,
+    Vi
+}
+
+// the key bindings file
+// preset - the movement keys to start from
+// bindings - key name => action, added to the preset (a key bound by the preset is bound again)
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeysFile {
+    preset: Preset,
+    #[serde(default)]
+    bindings: BTreeMap<String, Action>
+}
+
+// which key does what - key name => action (e.g. "k" => Up)
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyBindings {
+    bindings: BTreeMap<String, Action>
+}
+
+impl Default for KeyBindings {
+    // the arrow keys
+    fn default() -> Self {
+        KeyBindings::preset(Preset::Arrows)
+    }
+}
+
+impl KeyBindings {
+    // the bindings of a preset
+    pub fn preset(preset: Preset) -> Self {
+        use Action::*;
+
+        let common = [
+            ("up", Up), ("down", Down), ("left", Left), ("right", Right),
+            (".", Wait), ("g", PickUp), ("i", Inventory), ("t", Fire), ("s", Search), ("d", Close), (">", Descend), ("<", Climb),
+            ("x", Explore), ("v", Look), ("c", Character), ("m", Messages), ("?", Help), ("f", Fov), ("escape", Exit)
+        ];
+        let movement: &[(&str, Action)] = match preset {
+            Preset::Arrows => &[],
+            Preset::Numpad => &[
+                ("kp8", Up), ("kp2", Down), ("kp4", Left), ("kp6", Right),
+                ("kp7", UpLeft), ("kp9", UpRight), ("kp1", DownLeft), ("kp3", DownRight), ("kp5", Wait)
+            ],
+            Preset::Vi => &[
+                ("k", Up), ("j", Down), ("h", Left), ("l", Right),
+                ("y", UpLeft), ("u", UpRight), ("b", DownLeft), ("n", DownRight)
+            ]
+        };
+
+        let bindings = common
+            .iter()
+            .chain(movement)
+            .map(|&(key, action)| (key.to_string(), action))
+            .collect();
+        KeyBindings { bindings }
+    }
+
+    // the action bound to the key, None if the key does nothing
+    pub fn action(&self, key: &str) -> Option<Action> {
+        self.bindings.get(key).copied()
+    }
+
+    // bind a key to an action (a key does one thing - its old action is unbound)
+    pub fn bind(&mut self, key: &str, action: Action) {
+        self.bindings.insert(key.to_string(), action);
+    }
+
+    // every action with the keys bound to it, in the order of the actions
+    pub fn by_action(&self) -> Vec<(Action, Vec<&str>)> {
+        let mut by_action: BTreeMap<Action, Vec<&str>> = BTreeMap::new();
+        for (key, action) in &self.bindings {
+            by_action.entry(*action).or_default().push(key);
+        }
+        by_action.into_iter().collect()
+    }
+
+    // the lines of the help screen - every bound action with its keys e.g. "k, up          move / attack up"
+    pub fn help(&self) -> Vec<String> {
+        self.by_action()
+            .into_iter()
+            .map(|(action, keys)| format!("{:<14} {}", keys.join(", "), action.description()))
+            .collect()
+    }
+}
+
+/// @title is_key_name
+/// @author GeorgiKostadinovPro
+/// @notice check that a key can be bound
+/// @dev custom fn to accept a special key name (SPECIAL_KEYS) or a single printable character
+pub fn is_key_name(key: &str) -> bool {
+    let mut chars = key.chars();
+    let is_char = matches!((chars.next(), chars.next()), (Some(c), None) if !c.is_whitespace() && !c.is_control());
+    is_char || SPECIAL_KEYS.contains(&key)
+}
+
+/// @title load_key_bindings
+/// @author GeorgiKostadinovPro
+/// @notice load the key bindings from a file
+/// @dev custom fn to read and validate a key bindings file, the arrow keys preset if there is no such file
+pub fn load_key_bindings<P: AsRef<Path>>(path: P) -> Result<KeyBindings, Box<dyn Error>> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(KeyBindings::default());
+    }
+
+    let json = fs::read_to_string(path)?;
+    parse_key_bindings(&json).map_err(|err| format!("{}: {}", path.display(), err).into())
+}
+
+/// @title parse_key_bindings
+/// @author GeorgiKostadinovPro
+/// @notice parse and validate the key bindings
+/// @dev custom fn to read the preset and the extra bindings from json and check every key
+/// an error names the offending key e.g. bindings "kp10": unknown key
+pub fn parse_key_bindings(json: &str) -> Result<KeyBindings, Box<dyn Error>> {
+    // serde_json errors already point to the line and column (and name an unknown action)
+    let file: KeysFile = serde_json::from_str(json)?;
+
+    let mut key_bindings = KeyBindings::preset(file.preset);
+    for (key, action) in file.bindings {
+        if !is_key_name(&key) {
+            return Err(format!("bindings \"{}\": unknown key, expected a single character or one of {}", key, SPECIAL_KEYS.join(", ")).into());
+        }
+        key_bindings.bind(&key, action);
+    }
+
+    // the player must always be able to leave the game
+    if !key_bindings.bindings.values().any(|action| *action == Action::Exit) {
+        return Err("bindings: no key is bound to exit".into());
+    }
+
+    Ok(key_bindings)
+}
@@ -0,0 +1,76 @@
+use crate::models::entity::*;
+use crate::models::maze::*;
+
+/// @title names_under
+/// @author GeorgiKostadinovPro
+/// @notice the names of what the player sees on a tile
+/// @dev custom fn to list the entities on the tile - only if it is in the player's FOV
+/// (or they are always visible on an explored tile, like the stairs), the blocking ones first as they are drawn on top
+/// the monsters are shown with their awareness (awareness.rs)
+pub fn names_under(x: i32, y: i32, game: &Game, entities: &[Entity]) -> Vec<String> {
+    if !is_in_maze(x, y) {
+        return vec![];
+    }
+
+    let is_visible = game.fov.is_in_fov(x, y);
+    let is_explored = game.maze[x as usize][y as usize].is_explored;
+
+    let mut seen: Vec<&Entity> = entities
+        .iter()
+        .filter(|entity| entity.get_pos() == (x, y))
+        .filter(|entity| is_visible || (entity.always_visible && is_explored))
+        .collect();
+    seen.sort_by_key(|entity| !entity.is_blocking);
+
+    // a living monster with what it knows about the player e.g. "orc (asleep)"
+    seen.into_iter()
+        .map(|entity| match entity.ai {
+            Some(_) if entity.is_alive => format!("{} ({})", entity.name, entity.awareness),
+            _ => entity.name.clone()
+        })
+        .collect()
+}
+
+/// @title tile_name
+/// @author GeorgiKostadinovPro
+/// @notice what kind of tile it is
+/// @dev custom fn to name the tile - wall or floor, unexplored if the player has never seen it
+/// a door, a found trap or a cracked wall is named after what it is (features.rs)
+pub fn tile_name(x: i32, y: i32, game: &Game) -> &'static str {
+    if !is_in_maze(x, y) || !game.maze[x as usize][y as usize].is_explored {
+        return "unexplored";
+    }
+
+    let tile = game.maze[x as usize][y as usize];
+    if let Some(name) = tile.feature.and_then(|feature| feature.name()) {
+        name
+    } else if tile.blocked {
+        "wall"
+    } else {
+        "floor"
+    }
+}
+
+/// @title describe_tile
+/// @author GeorgiKostadinovPro
+/// @notice describe a tile for the panel (mouse look, look mode)
+/// @dev custom fn to join the names under the tile with its kind e.g. "orc, healing potion (floor)"
+/// None outside of the maze
+pub fn describe_tile(x: i32, y: i32, game: &Game, entities: &[Entity]) -> Option<String> {
+    if !is_in_maze(x, y) {
+        return None;
+    }
+
+    let names = names_under(x, y, game, entities);
+    let tile = tile_name(x, y, game);
+
+    if names.is_empty() {
+        Some(tile.to_string())
+    } else {
+        Some(format!("{} ({})", names.join(", "), tile))
+    }
+}
+
+fn is_in_maze(x: i32, y: i32) -> bool {
+    x >= 0 && y >= 0 && x < MAZE_WIDTH && y < MAZE_HEIGHT
+}
@@ -1,8 +1,18 @@
-use std::cmp;
+use std::collections::BTreeMap;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use tcod::colors::*;
+use crate::models::awareness::*;
+use crate::models::connectivity::*;
+use crate::models::definitions::*;
+use crate::models::engine::Command;
 use crate::models::entity::*;
-use crate::models::tcod_db::{Messages};
+use crate::models::features::*;
+use crate::models::fov::*;
+use crate::models::generators::*;
+use crate::models::messages::*;
+use crate::models::ranged::*;
+use crate::models::util::GameRng;
 
 // size of the maze
 pub const MAZE_WIDTH: i32 = 80;
@@ -15,14 +25,13 @@ pub const COLOR_LIGHT_WALL: Color = Color { r: 130, g: 110, b: 50 };
 pub const COLOR_DARK_GROUND: Color = Color {r: 50, g: 50, b: 150 };
 pub const COLOR_LIGHT_GROUND: Color = Color { r: 200, g: 180, b: 50 };
 
-// max num of room + max/min size of rooms
-const MAX_ROOMS: i32 = 30;
-pub const ROOM_MIN_SIZE: i32 = 5;
-pub const ROOM_MAX_SIZE: i32 = 10;
-
-// max num of monsters in each room
+// max num of monsters in each room on the first floor
+// deeper floors get more of them (max_monsters_in_room)
 const MAX_MONSTERS_IN_ROOM: i32 = 3;
 
+// max num of items in each room
+const MAX_ITEMS_IN_ROOM: i32 = 2;
+
 // player index in entities vector
 // player will always be the first Entity
 pub const PLAYER: usize = 0;
@@ -32,22 +41,61 @@ pub type Maze = Vec<Vec<Tile>>;
 
 // the main game Entity
 // maze is the map to be explored - a jagged array
+// seed is the one used to generate the maze, shown in the GUI panel to reproduce the level
+// fov is the field of view of the player - game state, not rendering, so it works without a window too
+// fov_algorithm - how the fov of the player and of the monsters is computed (fov.rs), switched with the fov command
+// inventory - the items picked up by the player (removed from the entities)
+// rng - every random roll during the game (e.g. a confused monster's steps) so a seed replays the same
+// depth - the floor of the dungeon the player is on, starting from 1
+// floors - every visited floor the player is not on right now, by depth (the player can climb back up)
+// definitions - the monsters and items the floors are generated with (definitions.rs), not saved with the game
+// generator - the algorithm every new floor is generated with, None - it changes with the depth (generators.rs)
+// turn - the turn being played, counting from 1 (the messages are stamped with it)
+// recording - every command played so far when the game is recorded for a replay, None if not (replay.rs)
+// noises - made since the monsters last listened (awareness.rs), not saved - they are heard within the turn
+// projectiles - shot during the last turn (ranged.rs), the window animates them, not saved
 pub struct Game {
     pub maze: Maze,
-    pub messages: Messages
+    pub messages: Messages,
+    pub seed: u64,
+    pub fov: FovMap,
+    pub fov_algorithm: FovAlgorithm,
+    pub inventory: Vec<Entity>,
+    pub rng: GameRng,
+    pub depth: u32,
+    pub floors: BTreeMap<u32, Floor>,
+    pub definitions: Definitions,
+    pub generator: Option<Generator>,
+    pub turn: u32,
+    pub recording: Option<Vec<Command>>,
+    pub noises: Vec<Noise>,
+    pub projectiles: Vec<Projectile>
+}
+
+// a floor of the dungeon the player has left
+// kept as it was - explored tiles, monsters, items and remains - until the player comes back
+// entities - everything on the floor except the player (he takes his place in the current entities)
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Floor {
+    pub maze: Maze,
+    pub entities: Vec<Entity>
 }
 
 // A tile of the maze and its properties
 // clone & Copy - copy values as arguments instead of borrow
 // Debug - print tile content
-#[derive(Clone, Copy, Debug)]
+// PartialEq - compare mazes generated from the same seed
+// Serialize, Deserialize - the maze is saved to disk with the game, fog of war included
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Tile {
     // wall
     pub blocked: bool,
     // wall + fov is blocked
     pub block_sight: bool,
     // fog of war tracker
-    pub is_explored: bool
+    pub is_explored: bool,
+    // a door, a trap or a cracked wall on the tile (features.rs), None - a plain floor or wall
+    pub feature: Option<Feature>
 }
 
 impl Tile {
@@ -55,7 +103,8 @@ impl Tile {
         Tile {
             blocked: false,
             block_sight: false,
-            is_explored: false
+            is_explored: false,
+            feature: None
         }
     }
 
@@ -63,204 +112,191 @@ impl Tile {
         Tile {
             blocked: true,
             block_sight: true,
-            is_explored: false
+            is_explored: false,
+            feature: None
         }
     }
-}
 
-// Room struct for a maze room
-// Use Clone & Copy traits to not pass Room as a reference each time
-#[derive(Clone, Copy, Debug)]
-struct Room {
-    x1: i32,
-    y1: i32,
-    x2: i32,
-    y2: i32,
-}
-
-impl Room {
-    pub fn new(x: i32, y: i32, w: i32, h: i32) -> Self {
-        Room {
-            x1: x,
-            y1: y,
-            x2: x + w,
-            y2: y + h,
+    // a closed door - it is walked through once opened, so only the sight is blocked
+    pub fn door() -> Self {
+        Tile {
+            block_sight: true,
+            feature: Some(Feature::Door { is_open: false }),
+            ..Tile::empty()
         }
     }
 
-    // find the xenter of each room 
-    // e.g top left (10, 10), bottom right (20, 20) => center ((x1 + x2) / 2, (y1 + y2) / 2) = (15, 15)
-    pub fn center(&self) -> (i32, i32) {
-        let center_x = (self.x1 + self.x2) / 2;
-        let center_y = (self.y1 + self.y2) / 2;
-        (center_x, center_y)
+    // a floor with a hidden trap
+    pub fn trap(trap: Trap) -> Self {
+        Tile {
+            feature: Some(Feature::Trap { trap, is_found: false }),
+            ..Tile::empty()
+        }
     }
 
-    // check if two rooms intersect
-    // ensure no rooms go over each other
-    pub fn intersects_with(&self, other: &Room) -> bool {
-        // returns true if this intersects with another one
-        (self.x1 <= other.x2)
-            && (self.x2 >= other.x1)
-            && (self.y1 <= other.y2)
-            && (self.y2 >= other.y1)
+    // a wall that crumbles after enough blows
+    pub fn cracked_wall() -> Self {
+        Tile {
+            feature: Some(Feature::CrackedWall { hp: CRACKED_WALL_HP }),
+            ..Tile::wall()
+        }
     }
 }
 
-/// @title create_room
+/// @title is_blocked
 /// @author GeorgiKostadinovPro
-/// @notice create a custom room in maze
-/// @dev custom fn to create an empty custom room within maze ((x, y), (x + dx, y + dy))
-fn create_room(maze: &mut Maze, room: Room) {
-    // go through the tiles in the room and make them passable
-    // from x + 1 and y1 + 1 so that only inside the room is empty, not the walls
-    // A..B means A is inclused up to B (exclusive)
-    for x in (room.x1 + 1)..room.x2 {
-        for y in (room.y1 + 1)..room.y2 {
-            maze[x as usize][y as usize] = Tile::empty();
-        }
+/// @notice check if a tile can be stepped on
+/// @dev custom fn to check if a tile is a wall or is taken by a blocking entity (monster, player)
+pub fn is_blocked(x: i32, y: i32, maze: &Maze, entities: &[Entity]) -> bool {
+    // first test the maze tile
+    if maze[x as usize][y as usize].blocked {
+        return true;
     }
+
+    // now check for any blocking entities on the tile
+    entities
+        .iter()
+        .any(|entity| entity.is_blocking && entity.get_pos() == (x, y))
 }
 
-/// @title create_room
+/// @title max_monsters_in_room
 /// @author GeorgiKostadinovPro
-/// @notice create a custom tunnel in maze
-/// @dev custom fn to create an empty custom tunnel within maze
-fn create_tunnel(maze: &mut Maze, x1: i32, x2: i32, y1: i32, y2: i32, is_horizontal: bool) {
-    // the tunner can be horizontal or vertical - isHorizontal
-    // isHorizontal - loop in maze only on rows, not cols
-    // in rust jagged array x - cols, y - rows. In C# x - rows, y - cols
-    // x1 and x2 are the start and end, y1 is the height, y2 = 0 not needed
-    // !isHorizontal - tunnel is vertical loop only through cols, not rows
-    // y1 and y2 are the start and end, x1 is the width, x2 = 0 not needed
-    // min & max ensure that we always start with the smaller number (1, 5) is the same as (5, 1)
-    // otherwise the for loop will not produce result
-    if is_horizontal {
-        for x in cmp::min(x1, x2)..cmp::max(x1, x2) {
-            maze[x as usize][y1 as usize] = Tile::empty();
-        }
-    } else {
-        for y in cmp::min(y1, y2)..cmp::max(y1, y2) {
-            maze[x1 as usize][y as usize] = Tile::empty();
-        }
-    }
+/// @notice how crowded the rooms are on a floor
+/// @dev custom fn to scale the max num of monsters in a room with the depth - one more every second floor
+pub fn max_monsters_in_room(depth: u32) -> i32 {
+    MAX_MONSTERS_IN_ROOM + (depth.max(1) as i32 - 1) / 2
 }
 
 /// @title create_monsters
 /// @author GeorgiKostadinovPro
 /// @notice create monsters in maze on random
-/// @dev custom fn to create monsters within maze on random
-fn create_monsters(room: Room, entities: &mut Vec<Entity>) {
+/// @dev custom fn to create monsters within maze on random (rng passed from create_maze)
+/// the monster kinds are rolled by their weight on this depth (definitions.rs), deeper floors have more monsters
+/// a monster never spawns on the player or on another monster
+fn create_monsters<R: Rng>(
+    region: &Region,
+    depth: u32,
+    definitions: &Definitions,
+    maze: &Maze,
+    entities: &mut Vec<Entity>,
+    rng: &mut R
+) {
     // choose random number of monsters
-    let monsters_count = rand::thread_rng().gen_range(0, MAX_MONSTERS_IN_ROOM + 1);
+    let monsters_count = rng.gen_range(0, max_monsters_in_room(depth) + 1);
 
     for _ in 0..monsters_count {
         // choose random spot for curr monster
-        // (x1 + 1, y1 + 1) => x2, y2 (exclusive)
-        // monster is placed only within room
-        let x = rand::thread_rng().gen_range(room.x1 + 1, room.x2);
-        let y = rand::thread_rng().gen_range(room.y1 + 1, room.y2);
-
-        // 80% chance of getting an orc
-        // 20% - trolls
-        let mut monster = if rand::random::<f32>() < 0.8 {  
-            // create an orc
-            let mut orc = Entity::new(x, y, 'o', DESATURATED_GREEN, "orc", true);
-            orc.fighter = Some(Fighter {
-                max_hp: 10,
-                hp: 10,
-                defense: 0,
-                power: 5,
-                on_death: DeathCallback::Monster
-            });
-            orc
-        } else {
-            let mut troll = Entity::new(x, y, 'T', DARKER_GREEN, "troll", true);
-            troll.fighter = Some(Fighter {
-                max_hp: 15,
-                hp: 15,
-                defense: 0,
-                power: 10,
-                on_death: DeathCallback::Monster
-            });
-            troll
-        };
-
-        monster.is_alive = true;
-        entities.push(monster);
+        // monster is placed only within the region (a room, a part of a cave)
+        let (x, y) = region.tiles[rng.gen_range(0, region.tiles.len())];
+
+        // the tile is taken (by the player or a monster rolled before) => one monster less
+        if is_blocked(x, y, maze, entities) {
+            continue;
+        }
+
+        // e.g. on the first floor 80% chance of getting an orc, 20% - trolls (more trolls on deeper floors)
+        let monster = choose_weighted(&definitions.monsters, |monster| monster.weight_at(depth), rng);
+
+        if let Some(monster) = monster {
+            entities.push(monster.spawn(x, y));
+        }
     }
 }
 
-/// @title create_maze
+/// @title create_items
 /// @author GeorgiKostadinovPro
-/// @notice create a custom jagged maze
-/// @dev custom fn to create a custom jagged maze (80 inner vectors with 45 Tiles each)
-pub fn create_maze(entities: &mut Vec<Entity>) -> Maze {
-    // fill maze with wall tiles, then when creating rooms, tunnels, etc wall => empty
-    let mut maze = vec![vec![Tile::wall(); MAZE_HEIGHT as usize]; MAZE_WIDTH as usize];
-
-    // after populating the vec => loop it and call create_room()
-    let mut rooms = vec![];
-
-    // generate rooms and tunnels on random
-    for _ in 0..MAX_ROOMS {
-        // random width and height
-        let w = rand::thread_rng().gen_range(ROOM_MIN_SIZE, ROOM_MAX_SIZE + 1);
-        let h = rand::thread_rng().gen_range(ROOM_MIN_SIZE, ROOM_MAX_SIZE + 1);
-        // random position without going out of the boundaries of the map
-        // maze is 80x45 ensure room start (x1, y1) (x1 + w, y2 + h) <= borders
-        let x = rand::thread_rng().gen_range(0, MAZE_WIDTH - w);
-        let y = rand::thread_rng().gen_range(0, MAZE_HEIGHT - h);
-
-        // init a room
-        let room = Room::new(x, y, w, h);
-
-        // loop the other rooms and see if any intersect with this one
-        // if ture any() will abort and return true
-        let failed = rooms
-            .iter()
-            .any(|other_room| room.intersects_with(other_room));
-
-        if failed {
+/// @notice create items in maze on random
+/// @dev custom fn to create items within a room on random, only on tiles not taken by a monster
+/// the item kinds are rolled by their weight on this depth (definitions.rs)
+fn create_items<R: Rng>(
+    region: &Region,
+    depth: u32,
+    definitions: &Definitions,
+    maze: &Maze,
+    entities: &mut Vec<Entity>,
+    rng: &mut R
+) {
+    // choose random number of items
+    let items_count = rng.gen_range(0, MAX_ITEMS_IN_ROOM + 1);
+
+    for _ in 0..items_count {
+        // choose random spot for this item
+        let (x, y) = region.tiles[rng.gen_range(0, region.tiles.len())];
+
+        // only place it if the tile is not blocked
+        if is_blocked(x, y, maze, entities) {
             continue;
         }
 
-        // insert the room in the maze with empty tiles
-        create_room(&mut maze, room);
-
-        // create monsters
-        create_monsters(room, entities);
-
-        // get the center of the room to place the player
-        let (center_x, center_y) = room.center();
-
-        // if this room is the first put the player inside
-        // for every other room try to connect it via a tunnel to the previous one
-        if rooms.is_empty() {
-            // this is the first room, where the player starts at
-            entities[PLAYER].set_pos(center_x, center_y);
-        } else {
-            // all rooms after the first:
-            // connect it to the previous room with a tunnel
-
-            // center coordinates of the previous room (the curr last room)
-            let (prev_center_x, prev_center_y) = rooms[rooms.len() - 1].center();
-
-            // toss a coin (random bool value -- either true or false)
-            if rand::random() {
-                // first move horizontally, then vertically
-                create_tunnel(&mut maze, prev_center_x, center_x, prev_center_y, 0, true);
-                create_tunnel(&mut maze, center_x, 0, prev_center_y, center_y, false);
-            } else {
-                // first move vertically, then horizontally
-                create_tunnel(&mut maze, prev_center_x, 0, prev_center_y, center_y, false);
-                create_tunnel(&mut maze, prev_center_x, center_x, center_y, 0, true);
-            }
+        // e.g. 55% chance of getting a healing potion, 10% - a scroll, 5% - a sword
+        // non-blocking - the player walks over it and picks it up
+        let item = choose_weighted(&definitions.items, |item| item.weight_at(depth), rng);
+
+        if let Some(item) = item {
+            entities.push(item.spawn(x, y));
         }
+    }
+}
 
-        // add room and use it to check the next ones
-        rooms.push(room);
-    } 
+/// @title create_maze
+/// @author GeorgiKostadinovPro
+/// @notice create a custom jagged maze
+/// @dev custom fn to create a custom jagged maze (80 inner vectors with 45 Tiles each)
+/// every random roll comes from rng, so the same seed yields the same maze and monsters
+/// depth is the floor of the dungeon - it places up stairs under the player below the first floor
+/// and makes the monsters harder to deal with
+/// generator is the algorithm that lays out the floor (generators.rs), definitions are the monsters and items to spawn
+pub fn create_maze<R: Rng>(
+    entities: &mut Vec<Entity>,
+    depth: u32,
+    generator: Generator,
+    definitions: &Definitions,
+    rng: &mut R
+) -> Maze {
+    let Layout { mut maze, regions } = generator.generate(rng);
+
+    // the first region is where the player starts at
+    // he is placed before the monsters so none of them spawns on him
+    let (player_x, player_y) = regions[0].center;
+    entities[PLAYER].set_pos(player_x, player_y);
+
+    // a generator may leave a part of the maze cut off (e.g. two tunnels missing each other at a corner)
+    // tunnels are carved to it, so every open tile - and everything spawned on it - can be walked to
+    repair_connectivity(&mut maze, (player_x, player_y));
+
+    // create monsters and items in every region (a room, a part of a cave)
+    for region in &regions {
+        create_monsters(region, depth, definitions, &maze, entities, rng);
+        create_items(region, depth, definitions, &maze, entities, rng);
+    }
+
+    // some monsters sleep, the others wander around - rolled after the spawning so it draws from the rng as before
+    for monster in entities.iter_mut().filter(|entity| entity.ai.is_some()) {
+        monster.awareness = roll_awareness(rng);
+    }
+
+    // the stairs down are in the center of the last region, as far as the generation goes from the player
+    let (last_x, last_y) = regions[regions.len() - 1].center;
+    entities.push(create_stairs(last_x, last_y, Stairs::Down));
+
+    // the player arrives on the stairs up from the floor above (the first floor has no way up)
+    if depth > 1 {
+        entities.push(create_stairs(player_x, player_y, Stairs::Up));
+    }
 
     maze
 }
+
+// the stairs are not blocking - the player stands on them to take them
+// always visible - once seen they are drawn on the explored maze so the player can find his way back
+fn create_stairs(x: i32, y: i32, stairs: Stairs) -> Entity {
+    let (char, name) = match stairs {
+        Stairs::Down => ('>', "stairs down"),
+        Stairs::Up => ('<', "stairs up")
+    };
+
+    let mut entity = Entity::new(x, y, char, WHITE, name, false);
+    entity.always_visible = true;
+    entity.stairs = Some(stairs);
+    entity
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+use tcod::colors::*;
+
+// how many messages the log keeps - the oldest ones are dropped first, so long games do not grow it forever
+pub const MAX_MESSAGES: usize = 500;
+
+// what a message is about, the message log can show only some of them
+// Combat - attacks, kills, spells hitting the monsters
+// System - the dungeon, levels, hints and cancelled actions
+// Loot - items found, picked up, used and equipped
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MessageCategory {
+    Combat,
+    System,
+    Loot
+}
+
+impl MessageCategory {
+    // in the order the filters are listed (and toggled with 1, 2, 3) in the message log
+    pub const ALL: [MessageCategory; 3] = [
+        MessageCategory::Combat,
+        MessageCategory::System,
+        MessageCategory::Loot
+    ];
+
+    // the color the filter of the category is shown in
+    pub fn color(self) -> Color {
+        match self {
+            MessageCategory::Combat => LIGHT_RED,
+            MessageCategory::System => LIGHT_GREY,
+            MessageCategory::Loot => LIGHT_GREEN
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MessageCategory::Combat => "combat",
+            MessageCategory::System => "system",
+            MessageCategory::Loot => "loot"
+        }
+    }
+}
+
+// a message of the log - the text in its color, the turn it was added on and what it is about
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Message {
+    pub text: String,
+    pub color: Color,
+    pub turn: u32,
+    pub category: MessageCategory
+}
+
+// the message log
+// turn - the turn new messages are stamped with, the game keeps it up to date (engine.rs)
+// dropped - how many of the oldest messages were dropped to keep the log under MAX_MESSAGES
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Messages {
+    pub messages: Vec<Message>,
+    pub turn: u32,
+    pub dropped: usize
+}
+
+impl Default for Messages {
+    fn default() -> Self {
+        Messages::new()
+    }
+}
+
+impl Messages {
+    pub fn new() -> Self {
+        Self { messages: vec![], turn: 1, dropped: 0 }
+    }
+
+    // add the new message with the text, the color and the category
+    // We can pass both & and String as they both implement the Into trait for String
+    // anything that can be converted to String can be passed as T e.g. &str
+    pub fn add<T: Into<String>>(&mut self, message: T, color: Color, category: MessageCategory) {
+        self.messages.push(Message {
+            text: message.into(),
+            color,
+            turn: self.turn,
+            category
+        });
+
+        if self.messages.len() > MAX_MESSAGES {
+            let excess = self.messages.len() - MAX_MESSAGES;
+            self.messages.drain(..excess);
+            self.dropped += excess;
+        }
+    }
+
+    // every message added so far, the dropped ones too
+    pub fn total(&self) -> usize {
+        self.dropped + self.messages.len()
+    }
+
+    // the messages added after the log had total messages (e.g. to print only the new ones)
+    pub fn since(&self, total: usize) -> &[Message] {
+        let start = total.saturating_sub(self.dropped).min(self.messages.len());
+        &self.messages[start..]
+    }
+
+    // the messages of the given categories, the oldest first
+    pub fn filtered(&self, categories: &[MessageCategory]) -> Vec<&Message> {
+        self.messages
+            .iter()
+            .filter(|message| categories.contains(&message.category))
+            .collect()
+    }
+}
+
+/// @title wrap_text
+/// @author GeorgiKostadinovPro
+/// @notice break a text into lines that fit a width
+/// @dev custom fn to wrap a text on spaces, a word longer than the width is cut
+pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+
+        // a single word too long for a line
+        while line.chars().count() > width {
+            let rest = line.split_off(line.char_indices().nth(width).map_or(line.len(), |(i, _)| i));
+            lines.push(std::mem::replace(&mut line, rest));
+        }
+    }
+
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
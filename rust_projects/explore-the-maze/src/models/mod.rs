@@ -2,3 +2,30 @@ pub mod util;
 pub mod maze;
 pub mod entity;
 pub mod tcod_db;
+pub mod ai;
+pub mod engine;
+pub mod headless;
+pub mod save;
+pub mod item;
+pub mod dungeon;
+pub mod experience;
+pub mod equipment;
+pub mod definitions;
+pub mod generators;
+pub mod connectivity;
+pub mod pathfinding;
+pub mod travel;
+pub mod messages;
+pub mod look;
+pub mod keys;
+pub mod effects;
+pub mod scheduler;
+pub mod replay;
+pub mod fov;
+pub mod awareness;
+pub mod ranged;
+pub mod combat;
+pub mod features;
+pub mod render;
+pub mod tcod_backend;
+pub mod terminal_backend;
@@ -0,0 +1,198 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+use crate::models::entity::*;
+use crate::models::maze::*;
+
+// the 4 directions the monsters, explore and travel walk in (only the player steps diagonally, with his keys)
+pub const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+
+// inside the maze
+fn in_maze(x: i32, y: i32) -> bool {
+    x >= 0 && y >= 0 && x < MAZE_WIDTH && y < MAZE_HEIGHT
+}
+
+// can the tile be walked on
+// avoid_entities - a tile taken by a blocking entity (monster, player) counts as a wall
+fn is_walkable(x: i32, y: i32, maze: &Maze, entities: &[Entity], avoid_entities: bool) -> bool {
+    if avoid_entities {
+        !is_blocked(x, y, maze, entities)
+    } else {
+        !maze[x as usize][y as usize].blocked
+    }
+}
+
+/// @title find_path
+/// @author GeorgiKostadinovPro
+/// @notice find the shortest path between two tiles
+/// @dev custom fn to search the maze with A* - the tiles closest to the target (manhattan distance) are tried first
+/// returns the tiles to step on in order, without start and with target (None if the target cannot be reached)
+/// the target itself is always allowed - it is usually someone standing there (the player, a monster to attack)
+/// avoid_entities - walk around the blocking entities too, not only around the walls
+pub fn find_path(
+    start: (i32, i32),
+    target: (i32, i32),
+    maze: &Maze,
+    entities: &[Entity],
+    avoid_entities: bool
+) -> Option<Vec<(i32, i32)>> {
+    if !in_maze(start.0, start.1) || !in_maze(target.0, target.1) {
+        return None;
+    }
+    if start == target {
+        return Some(vec![]);
+    }
+
+    // the distance of a tile from the target if there was nothing in the way - never more than the real one
+    let heuristic = |(x, y): (i32, i32)| (x - target.0).abs() + (y - target.1).abs();
+
+    // cost[x][y] - steps from start to the tile so far, came_from[x][y] - the previous tile of that path
+    let mut cost = vec![vec![i32::MAX; MAZE_HEIGHT as usize]; MAZE_WIDTH as usize];
+    let mut came_from = vec![vec![None; MAZE_HEIGHT as usize]; MAZE_WIDTH as usize];
+
+    // the open tiles, the lowest (steps + heuristic) first - ties go to the tile closer to the target
+    let mut open = BinaryHeap::new();
+
+    cost[start.0 as usize][start.1 as usize] = 0;
+    open.push(Reverse((heuristic(start), heuristic(start), start)));
+
+    while let Some(Reverse((_, _, (x, y)))) = open.pop() {
+        if (x, y) == target {
+            // walk the path back to the start
+            let mut path = vec![target];
+            let mut curr = target;
+            while let Some(prev) = came_from[curr.0 as usize][curr.1 as usize] {
+                if prev == start {
+                    break;
+                }
+                path.push(prev);
+                curr = prev;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let steps = cost[x as usize][y as usize] + 1;
+
+        for (dx, dy) in DIRECTIONS {
+            let next = (x + dx, y + dy);
+            if !in_maze(next.0, next.1) {
+                continue;
+            }
+
+            if next != target && !is_walkable(next.0, next.1, maze, entities, avoid_entities) {
+                continue;
+            }
+
+            // only a shorter way to the tile is worth another look
+            if steps < cost[next.0 as usize][next.1 as usize] {
+                cost[next.0 as usize][next.1 as usize] = steps;
+                came_from[next.0 as usize][next.1 as usize] = Some((x, y));
+                open.push(Reverse((steps + heuristic(next), heuristic(next), next)));
+            }
+        }
+    }
+
+    None
+}
+
+// the distance (in steps) from every tile of the maze to the closest goal
+// one search answers "which way to the goal" for everybody - approaching, fleeing, exploring
+// distances[x][y] - None if no goal can be reached from the tile
+#[derive(Clone, Debug, PartialEq)]
+pub struct DijkstraMap {
+    distances: Vec<Vec<Option<i32>>>
+}
+
+impl DijkstraMap {
+    // spread out from all the goals at once (every step costs the same, so breadth-first is enough)
+    // the goals themselves are always allowed, avoid_entities - the blocking entities count as walls
+    pub fn new(goals: &[(i32, i32)], maze: &Maze, entities: &[Entity], avoid_entities: bool) -> Self {
+        let mut distances = vec![vec![None; MAZE_HEIGHT as usize]; MAZE_WIDTH as usize];
+        let mut queue = VecDeque::new();
+
+        for &(x, y) in goals {
+            if in_maze(x, y) && distances[x as usize][y as usize].is_none() {
+                distances[x as usize][y as usize] = Some(0);
+                queue.push_back((x, y));
+            }
+        }
+
+        while let Some((x, y)) = queue.pop_front() {
+            let distance = distances[x as usize][y as usize].unwrap_or(0) + 1;
+
+            for (dx, dy) in DIRECTIONS {
+                let (next_x, next_y) = (x + dx, y + dy);
+                if !in_maze(next_x, next_y) || distances[next_x as usize][next_y as usize].is_some() {
+                    continue;
+                }
+
+                if is_walkable(next_x, next_y, maze, entities, avoid_entities) {
+                    distances[next_x as usize][next_y as usize] = Some(distance);
+                    queue.push_back((next_x, next_y));
+                }
+            }
+        }
+
+        DijkstraMap { distances }
+    }
+
+    // steps from the tile to the closest goal, None if there is no way
+    pub fn distance(&self, x: i32, y: i32) -> Option<i32> {
+        if !in_maze(x, y) {
+            return None;
+        }
+        self.distances[x as usize][y as usize]
+    }
+
+    // the neighbour of the tile closest to a goal - one step towards it
+    // None on a goal or if no neighbour is closer
+    pub fn step_towards(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        let current = self.distance_from(x, y)?;
+        self.best_neighbour(x, y, |distance| distance < current, Reverse)
+    }
+
+    // the neighbour of the tile furthest from every goal - one step away from them (fleeing)
+    // None if no neighbour is further (cornered)
+    pub fn step_away(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        let current = self.distance_from(x, y)?;
+        self.best_neighbour(x, y, |distance| distance > current, |distance| distance)
+    }
+
+    // the distance of the tile someone moves from
+    // with avoid_entities he blocks his own tile, so it is one more than his closest neighbour
+    fn distance_from(&self, x: i32, y: i32) -> Option<i32> {
+        self.distance(x, y).or_else(|| {
+            DIRECTIONS
+                .iter()
+                .filter_map(|(dx, dy)| self.distance(x + dx, y + dy))
+                .min()
+                .map(|distance| distance + 1)
+        })
+    }
+
+    // the neighbour with a distance that is_better and the highest score, the first one on a tie
+    fn best_neighbour<K: Ord>(
+        &self,
+        x: i32,
+        y: i32,
+        is_better: impl Fn(i32) -> bool,
+        score: impl Fn(i32) -> K
+    ) -> Option<(i32, i32)> {
+        let mut best: Option<((i32, i32), i32)> = None;
+
+        for (dx, dy) in DIRECTIONS {
+            let (next_x, next_y) = (x + dx, y + dy);
+            let distance = match self.distance(next_x, next_y) {
+                Some(distance) if is_better(distance) => distance,
+                _ => continue
+            };
+
+            if best.is_none_or(|(_, best_distance)| score(distance) > score(best_distance)) {
+                best = Some(((next_x, next_y), distance));
+            }
+        }
+
+        best.map(|(tile, _)| tile)
+    }
+}
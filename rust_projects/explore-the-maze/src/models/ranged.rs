@@ -0,0 +1,286 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tcod::colors::*;
+
+use crate::models::awareness::*;
+use crate::models::combat::*;
+use crate::models::entity::*;
+use crate::models::item::*;
+use crate::models::maze::*;
+use crate::models::messages::*;
+use crate::models::util::*;
+
+// a bow shoots the arrows from the inventory this far and this hard
+pub const BOW_RANGE: i32 = 8;
+pub const BOW_POWER: i32 = 4;
+
+// a dagger is thrown at a tile, it lands on the floor whether it hits or not
+pub const DAGGER_RANGE: i32 = 5;
+pub const DAGGER_POWER: i32 = 3;
+
+// the chance (in %) a shot hits the fighter it flies at, a little less for every tile it has flown
+pub const HIT_CHANCE: i32 = 95;
+pub const HIT_CHANCE_PER_TILE: i32 = 5;
+
+// a fighter that shoots (the archer goblins) - how far, how hard and how many arrows it has left
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Ranged {
+    pub range: i32,
+    pub power: i32,
+    pub ammo: u32
+}
+
+// something flying through the maze this turn - the window draws it tile by tile (main.rs)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Projectile {
+    pub path: Vec<(i32, i32)>,
+    pub glyph: char,
+    pub color: Color
+}
+
+/// @title line
+/// @author GeorgiKostadinovPro
+/// @notice the tiles of a straight line
+/// @dev custom fn to walk the Bresenham line from one tile towards another, without the first tile
+/// the line goes on past the target (the way a missed shot flies) until it is length tiles long
+pub fn line(from: (i32, i32), to: (i32, i32), length: i32) -> Vec<(i32, i32)> {
+    let (dx, dy) = ((to.0 - from.0).abs(), -(to.1 - from.1).abs());
+    let (step_x, step_y) = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+    if dx == 0 && dy == 0 {
+        return vec![];
+    }
+
+    let mut tiles = vec![];
+    let (mut x, mut y) = from;
+    let mut error = dx + dy;
+    while (tiles.len() as i32) < length {
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+        tiles.push((x, y));
+    }
+
+    tiles
+}
+
+/// @title is_line_of_fire_clear
+/// @author GeorgiKostadinovPro
+/// @notice check if a shot can fly from one tile to another
+/// @dev custom fn to check that no wall, no closed door and no blocking entity stands on the line between the two tiles
+pub fn is_line_of_fire_clear(from: (i32, i32), to: (i32, i32), maze: &Maze, entities: &[Entity]) -> bool {
+    let length = (to.0 - from.0).abs().max((to.1 - from.1).abs());
+    let tiles = line(from, to, length);
+
+    tiles.last() == Some(&to)
+        && tiles[..tiles.len() - 1]
+            .iter()
+            .all(|&(x, y)| !maze[x as usize][y as usize].block_sight && !is_blocked(x, y, maze, entities))
+}
+
+/// @title shoot
+/// @author GeorgiKostadinovPro
+/// @notice shoot (or throw) something at a tile
+/// @dev custom fn to fly the projectile along the line of fire until a wall stops it or it hits a fighter
+/// every fighter on the way may be hit (HIT_CHANCE less HIT_CHANCE_PER_TILE for every tile flown), a miss flies on
+/// a hit does power - defense physical damage (less for those who resist it, combat.rs), the xp of a kill goes to the shooter
+/// item is what flies (an arrow, a dagger), it looks like its definition
+/// returns the fighter hit (if any) and the tile the projectile ended on
+pub fn shoot(
+    shooter_id: usize,
+    target: (i32, i32),
+    range: i32,
+    power: i32,
+    item: Item,
+    game: &mut Game,
+    entities: &mut [Entity]
+) -> (Option<usize>, (i32, i32)) {
+    let from = entities[shooter_id].get_pos();
+    let projectile = projectile_name(item);
+    let mut flown = vec![];
+    let mut hit = None;
+
+    for (x, y) in line(from, target, range) {
+        // a wall or a closed door stops it, like it blocks the line of fire
+        if x < 0 || y < 0 || x >= MAZE_WIDTH || y >= MAZE_HEIGHT
+            || game.maze[x as usize][y as usize].blocked
+            || game.maze[x as usize][y as usize].block_sight
+        {
+            break;
+        }
+        flown.push((x, y));
+
+        let Some(id) = entities.iter().position(|e| e.get_pos() == (x, y) && e.is_blocking && e.fighter.is_some()) else {
+            continue;
+        };
+
+        let chance = HIT_CHANCE - HIT_CHANCE_PER_TILE * flown.len() as i32;
+        if game.rng.gen_range(0, 100) < chance {
+            hit = Some(id);
+            break;
+        }
+        game.messages.add(
+            format!("{}'s {} misses {}.", entities[shooter_id].name, projectile, entities[id].name),
+            WHITE,
+            MessageCategory::Combat
+        );
+    }
+
+    let landing = flown.last().copied().unwrap_or(from);
+    let color = game.definitions.items.iter().find(|definition| definition.item == item).map_or(WHITE, |definition| definition.color);
+    let glyph = projectile_glyph(from, target);
+    game.projectiles.push(Projectile { path: flown, glyph, color });
+
+    // a shot is as loud as a fight where it lands
+    make_noise(game, landing.0, landing.1, COMBAT_NOISE);
+
+    if let Some(id) = hit {
+        let (shooter, target) = mut_two(entities, shooter_id, id);
+        let damage = resist(power - target.defense(game), DamageType::Physical, target);
+        if damage > 0 {
+            game.messages.add(
+                format!("{}'s {} hits {} for {} hit points.", shooter.name, projectile, target.name, damage),
+                WHITE,
+                MessageCategory::Combat
+            );
+            if let Some(xp) = target.take_damage(damage, game)
+                && let Some(fighter) = shooter.fighter.as_mut()
+            {
+                fighter.xp += xp;
+            }
+        } else {
+            game.messages.add(
+                format!("{}'s {} hits {} but it has no effect!", shooter.name, projectile, target.name),
+                WHITE,
+                MessageCategory::Combat
+            );
+        }
+    }
+
+    (hit, landing)
+}
+
+/// @title fire_bow
+/// @author GeorgiKostadinovPro
+/// @notice the player shoots an arrow with his bow
+/// @dev custom fn to shoot an arrow from the inventory at a tile in the FOV within BOW_RANGE
+/// it needs an equipped bow and arrows, a missed arrow lands on the floor (it can be picked up again)
+/// returns TookTurn if the arrow was shot
+pub fn fire_bow(target: (i32, i32), game: &mut Game, entities: &mut Vec<Entity>) -> PlayerAction {
+    let has_bow = game.inventory.iter().any(|item| {
+        item.item == Some(Item::Bow) && item.equipment.is_some_and(|equipment| equipment.is_equipped)
+    });
+    if !has_bow {
+        game.messages.add("You have no bow equipped.", RED, MessageCategory::Combat);
+        return PlayerAction::DidntTakeTurn;
+    }
+
+    let Some(arrows) = game.inventory.iter().position(|item| item.item == Some(Item::Arrows)) else {
+        game.messages.add("You have no arrows left.", RED, MessageCategory::Combat);
+        return PlayerAction::DidntTakeTurn;
+    };
+
+    if target == entities[PLAYER].get_pos() || !is_valid_target(target.0, target.1, Some(BOW_RANGE as f32), game, entities) {
+        game.messages.add("You cannot shoot there.", RED, MessageCategory::Combat);
+        return PlayerAction::DidntTakeTurn;
+    }
+
+    // one arrow less, the last one frees its slot
+    game.inventory[arrows].count -= 1;
+    if game.inventory[arrows].count == 0 {
+        game.inventory.remove(arrows);
+    }
+
+    let (hit, landing) = shoot(PLAYER, target, BOW_RANGE, BOW_POWER, Item::Arrows, game, entities);
+    if hit.is_none() {
+        drop_projectile(Item::Arrows, landing, game, entities);
+    }
+    PlayerAction::TookTurn
+}
+
+/// @title throw_dagger
+/// @author GeorgiKostadinovPro
+/// @notice the player throws a dagger from the inventory
+/// @dev custom fn to throw the dagger at a tile in the FOV within DAGGER_RANGE, it lands where it stops
+pub fn throw_dagger(inventory_id: usize, target: Option<(i32, i32)>, game: &mut Game, entities: &mut Vec<Entity>) -> UseResult {
+    let target = target.filter(|&(x, y)| {
+        (x, y) != entities[PLAYER].get_pos() && is_valid_target(x, y, Some(DAGGER_RANGE as f32), game, entities)
+    });
+    let Some(target) = target else {
+        game.messages.add("Cancelled", WHITE, MessageCategory::System);
+        return UseResult::Cancelled;
+    };
+
+    // it leaves the inventory for the floor, it is not destroyed
+    let mut dagger = game.inventory.remove(inventory_id);
+    let (_, (x, y)) = shoot(PLAYER, target, DAGGER_RANGE, DAGGER_POWER, Item::Dagger, game, entities);
+    dagger.set_pos(x, y);
+    entities.push(dagger);
+
+    UseResult::UsedUp
+}
+
+/// @title monster_shoot
+/// @author GeorgiKostadinovPro
+/// @notice a monster shoots the player
+/// @dev custom fn to let a ranged monster shoot the player if he is in range and nothing stands in the way
+/// returns true if it shot (that is its turn), false - it has to get closer (or it is out of arrows)
+pub fn monster_shoot(monster_id: usize, game: &mut Game, entities: &mut Vec<Entity>) -> bool {
+    let Some(ranged) = entities[monster_id].fighter.and_then(|f| f.ranged) else {
+        return false;
+    };
+
+    let (from, to) = (entities[monster_id].get_pos(), entities[PLAYER].get_pos());
+    let is_in_range = entities[monster_id].distance(to.0, to.1) <= ranged.range as f32;
+    if ranged.ammo == 0 || !is_in_range || !is_line_of_fire_clear(from, to, &game.maze, entities) {
+        return false;
+    }
+
+    if let Some(ranged) = entities[monster_id].fighter.as_mut().and_then(|f| f.ranged.as_mut()) {
+        ranged.ammo -= 1;
+    }
+
+    let (hit, landing) = shoot(monster_id, to, ranged.range, ranged.power, Item::Arrows, game, entities);
+    if hit.is_none() {
+        drop_projectile(Item::Arrows, landing, game, entities);
+    }
+    true
+}
+
+// a missed arrow lies on the floor where it stopped - a new one from the definitions (if there are arrows in them)
+fn drop_projectile(item: Item, (x, y): (i32, i32), game: &mut Game, entities: &mut Vec<Entity>) {
+    if let Some(definition) = game.definitions.items.iter().find(|definition| definition.item == item) {
+        let mut projectile = definition.spawn(x, y);
+        projectile.count = 1;
+        entities.push(projectile);
+    }
+}
+
+// what a projectile is called in the messages - one arrow of the stack flies, the rest are thrown as they are
+fn projectile_name(item: Item) -> &'static str {
+    match item {
+        Item::Arrows => "arrow",
+        Item::Dagger => "dagger",
+        _ => "projectile"
+    }
+}
+
+// the glyph of a projectile flying in the direction of the target - | - / \
+fn projectile_glyph(from: (i32, i32), to: (i32, i32)) -> char {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    if dy.abs() * 2 < dx.abs() {
+        '-'
+    } else if dx.abs() * 2 < dy.abs() {
+        '|'
+    } else if (dx > 0) == (dy > 0) {
+        '\\'
+    } else {
+        '/'
+    }
+}
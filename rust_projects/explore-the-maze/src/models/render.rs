@@ -0,0 +1,441 @@
+use std::str::FromStr;
+use tcod::colors::*;
+use crate::models::entity::*;
+use crate::models::experience::*;
+use crate::models::look::*;
+use crate::models::maze::*;
+use crate::models::messages::*;
+use crate::models::tcod_db::*;
+
+// the backends the game can be shown with, chosen with --backend on the command line
+// Tcod - the libtcod window (SDL, the arial10x10.png font and a graphical display)
+// Terminal - ANSI escape codes in the terminal the game is started from, e.g. over SSH
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BackendKind {
+    Tcod,
+    Terminal
+}
+
+// backend names on the command line: tcod, terminal
+impl FromStr for BackendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tcod" => Ok(BackendKind::Tcod),
+            "terminal" => Ok(BackendKind::Terminal),
+            _ => Err(format!("unknown backend '{}', expected tcod or terminal", s))
+        }
+    }
+}
+
+// a character cell of a panel - the glyph in its color over the background color
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cell {
+    pub glyph: char,
+    pub foreground: Color,
+    pub background: Color
+}
+
+impl Cell {
+    // nothing drawn - black
+    pub const EMPTY: Cell = Cell { glyph: ' ', foreground: WHITE, background: BLACK };
+}
+
+// a rectangle of cells to draw on - the maze, the GUI panel, a menu or the whole screen
+// the game draws the same panels whatever the backend, the backend only shows the screen (Backend::present)
+// drawing outside the panel is cut off
+#[derive(Clone, Debug, PartialEq)]
+pub struct Panel {
+    width: i32,
+    height: i32,
+    cells: Vec<Cell>
+}
+
+impl Panel {
+    pub fn new(width: i32, height: i32) -> Self {
+        Panel { width, height, cells: vec![Cell::EMPTY; (width * height) as usize] }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    // every cell back to black
+    pub fn clear(&mut self) {
+        self.cells.fill(Cell::EMPTY);
+    }
+
+    // the cell on (x, y), None outside the panel
+    pub fn cell(&self, x: i32, y: i32) -> Option<Cell> {
+        self.index(x, y).map(|i| self.cells[i])
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        (x >= 0 && y >= 0 && x < self.width && y < self.height).then(|| (y * self.width + x) as usize)
+    }
+
+    // draw a glyph in its color, the background stays
+    pub fn put_char(&mut self, x: i32, y: i32, glyph: char, color: Color) {
+        if let Some(i) = self.index(x, y) {
+            self.cells[i].glyph = glyph;
+            self.cells[i].foreground = color;
+        }
+    }
+
+    // color the background of a cell, the glyph stays
+    pub fn set_background(&mut self, x: i32, y: i32, color: Color) {
+        if let Some(i) = self.index(x, y) {
+            self.cells[i].background = color;
+        }
+    }
+
+    // color the background of a rectangle (e.g. a bar)
+    pub fn fill(&mut self, x: i32, y: i32, width: i32, height: i32, color: Color) {
+        for cell_x in x..x + width {
+            for cell_y in y..y + height {
+                self.set_background(cell_x, cell_y, color);
+            }
+        }
+    }
+
+    // print a line of text starting at (x, y)
+    pub fn print(&mut self, x: i32, y: i32, text: &str, color: Color) {
+        for (i, glyph) in text.chars().enumerate() {
+            self.put_char(x + i as i32, y, glyph, color);
+        }
+    }
+
+    // print a line of text centered on x
+    pub fn print_center(&mut self, x: i32, y: i32, text: &str, color: Color) {
+        self.print(x - text.chars().count() as i32 / 2, y, text, color);
+    }
+
+    // print a text wrapped to the width, line by line from (x, y), returns the number of lines
+    pub fn print_rect(&mut self, x: i32, y: i32, width: i32, text: &str, color: Color) -> i32 {
+        let lines = wrap_lines(text, width);
+        for (i, line) in lines.iter().enumerate() {
+            self.print(x, y + i as i32, line, color);
+        }
+        lines.len() as i32
+    }
+
+    // copy the source panel on this one with its top left corner on (x, y)
+    // background_alpha - 1.0 covers the background, less lets this panel's background show through (e.g. a menu)
+    pub fn blit(&mut self, source: &Panel, x: i32, y: i32, background_alpha: f32) {
+        for source_y in 0..source.height {
+            for source_x in 0..source.width {
+                let Some(i) = self.index(x + source_x, y + source_y) else {
+                    continue;
+                };
+                let cell = source.cells[(source_y * source.width + source_x) as usize];
+                self.cells[i] = Cell {
+                    background: blend(self.cells[i].background, cell.background, background_alpha),
+                    ..cell
+                };
+            }
+        }
+    }
+
+    // the glyphs of a row of the panel e.g. to check what is shown on it
+    pub fn line(&self, y: i32) -> String {
+        (0..self.width).filter_map(|x| self.cell(x, y)).map(|cell| cell.glyph).collect()
+    }
+}
+
+// a color between from (alpha 0.0) and to (alpha 1.0)
+fn blend(from: Color, to: Color, alpha: f32) -> Color {
+    let mix = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * alpha).round() as u8;
+    Color { r: mix(from.r, to.r), g: mix(from.g, to.g), b: mix(from.b, to.b) }
+}
+
+// the lines of a text wrapped to the width, its own line breaks kept (an empty line stays empty)
+fn wrap_lines(text: &str, width: i32) -> Vec<String> {
+    text.split('\n').flat_map(|line| wrap_text(line, width.max(1) as usize)).collect()
+}
+
+/// @title text_height
+/// @author GeorgiKostadinovPro
+/// @notice how many lines a text takes
+/// @dev custom fn to count the lines of a text wrapped to the width, as Panel::print_rect prints it
+pub fn text_height(text: &str, width: i32) -> i32 {
+    wrap_lines(text, width).len() as i32
+}
+
+// a key press named like in the key bindings file - "up", "kp8", "escape" or its character e.g. "h" (keys.rs)
+// name - None for the keys that cannot be bound (shift, F1, ...)
+// alt - pressed with Alt (Alt+Enter toggles the full screen)
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyPress {
+    pub name: Option<String>,
+    pub alt: bool
+}
+
+impl KeyPress {
+    // e.g. key.is("escape")
+    pub fn is(&self, name: &str) -> bool {
+        self.name.as_deref() == Some(name)
+    }
+
+    // the character of a letter, digit or symbol key, None for the other keys
+    pub fn char(&self) -> Option<char> {
+        let mut chars = self.name.as_deref()?.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None
+        }
+    }
+}
+
+// the mouse over the cell (x, y) of the screen, left / right - the button was just pressed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mouse {
+    pub x: i32,
+    pub y: i32,
+    pub left: bool,
+    pub right: bool
+}
+
+// the input of the player
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    Key(KeyPress),
+    Mouse(Mouse)
+}
+
+// what the game is shown with - the libtcod window (tcod_backend.rs) or the terminal (terminal_backend.rs)
+// it shows the screen drawn by the game and reads the keys and the mouse
+pub trait Backend {
+    // show the screen panel (SCREEN_WIDTH x SCREEN_HEIGHT)
+    fn present(&mut self, screen: &Panel);
+
+    // the next key press or mouse event, None if there is none - it does not wait for one
+    fn check_for_event(&mut self) -> Option<Event>;
+
+    // wait for the next key press
+    fn wait_for_keypress(&mut self) -> KeyPress;
+
+    // the player closed the window (or stopped the terminal game with Ctrl+C)
+    fn window_closed(&self) -> bool;
+
+    // Alt+Enter, nothing happens where there is no full screen
+    fn toggle_fullscreen(&mut self);
+}
+
+// everything drawn on the screen and the backend it is shown with
+// root - the whole screen, offscreen - the maze, gui_panel - the panel under the maze
+// look_at - the tile under the mouse or the look cursor, described in the GUI panel
+pub struct Gui {
+    pub backend: Box<dyn Backend>,
+    pub root: Panel,
+    pub offscreen: Panel,
+    pub gui_panel: Panel,
+    pub look_at: Option<(i32, i32)>
+}
+
+impl Gui {
+    pub fn new(backend: Box<dyn Backend>) -> Self {
+        Gui {
+            backend,
+            root: Panel::new(SCREEN_WIDTH, SCREEN_HEIGHT),
+            // maze is smaller than root console, the empty space will be used for healthy bar, messages, etc
+            offscreen: Panel::new(MAZE_WIDTH, MAZE_HEIGHT),
+            // Maze width == Screen width, Panel height = screen - maze
+            gui_panel: Panel::new(MAZE_WIDTH, PANEL_HEIGHT),
+            look_at: None
+        }
+    }
+
+    // show the root on the backend
+    pub fn flush(&mut self) {
+        self.backend.present(&self.root);
+    }
+}
+
+/// @title render_bar
+/// @author GeorgiKostadinovPro
+/// @notice render the bar in GUI panel (HP, EXP, etc)
+/// @dev custom fn to render a bar in the GUI panel under the maze to display HP, EXP, etc
+#[allow(clippy::too_many_arguments)]
+pub fn render_bar(
+    panel: &mut Panel,
+    x: i32,
+    y: i32,
+    total_width: i32,
+    name: &str,
+    value: i32,
+    maximum: i32,
+    bar_color: Color,
+    back_color: Color
+) {
+    // render a bar (HP, experience, etc). First calculate the width of the bar
+    let bar_width = (value as f32 / maximum as f32 * total_width as f32) as i32;
+
+    // render the background bar
+    panel.fill(x, y, total_width, 1, back_color);
+
+    // now render the bar on top
+    // bar can change e.g. HP decreases due to monster attack
+    if bar_width > 0 {
+        panel.fill(x, y, bar_width, 1, bar_color);
+    }
+
+    // value and max will be shown above the bar for extra clarity
+    // a caption will also be presented to indicate if the bar is HP, EXP, etc
+    panel.print_center(x + total_width / 2, y, &format!("{}: {}/{}", name, value, maximum), WHITE);
+}
+
+/// @title render_game
+/// @author GeorgiKostadinovPro
+/// @notice render the whole maze with its elements and entities
+/// @dev custom fn to render a custom jagged maze with its elements and entities
+/// it only draws on the root, the caller shows it with gui.flush()
+pub fn render_game(gui: &mut Gui, game: &Game, entities: &[Entity]) {
+    // the FOV is computed by the game itself every time the player moves (engine.rs)
+    // here it is only read to know what to draw
+
+    // go through all tiles, and set their background color
+    // visit each inner vector
+    for x in 0..MAZE_WIDTH {
+        // visit each element in vector
+        for y in 0..MAZE_HEIGHT {
+            // check if location is visible
+            let is_visible = game.fov.is_in_fov(x, y);
+
+            // if view is blocked then this is a wall
+            let is_wall = game.maze[x as usize][y as usize].block_sight;
+
+            // if explored only then color the tile, all other tiles are not visible
+            // visible tiles are already marked as explored by compute_fov
+            let is_explored = game.maze[x as usize][y as usize].is_explored;
+
+            // if wall or ground is visible then lighten them
+            // otherwise is not visible set dark colors
+            let color = match (is_visible, is_wall) {
+                // outside of field of view:
+                (false, true) => COLOR_DARK_WALL,
+                (false, false) => COLOR_DARK_GROUND,
+                // inside fov:
+                (true, true) => COLOR_LIGHT_WALL,
+                (true, false) => COLOR_LIGHT_GROUND
+            };
+
+            // only show explored tiles (any visible tile is explored already)
+            // if tile is not explored or yet to be explored then do not color it
+            // tiles are black until explored
+            if is_explored {
+                gui.offscreen.set_background(x, y, color);
+            }
+
+            // a door, a found trap or a cracked wall is drawn over its explored tile (features.rs)
+            if is_explored && let Some((glyph, glyph_color)) = game.maze[x as usize][y as usize].feature.and_then(|f| f.glyph()) {
+                gui.offscreen.put_char(x, y, glyph, glyph_color);
+            }
+        }
+    }
+
+    // draw all entities in the list
+    // if entity is in FOV then draw it
+    // if entity is dead then allow player to go over it
+    let mut to_draw: Vec<_> = entities.iter().collect();
+
+    // sort so that non-blocking objects come first
+    // a player will be drawn above the dead monster
+    to_draw.sort_by(|e1, e2| { e1.is_blocking.cmp(&e2.is_blocking) });
+
+    // the stairs stay on the explored maze once seen (always_visible)
+    for entity in &to_draw {
+        let is_explored = game.maze[entity.x as usize][entity.y as usize].is_explored;
+        if game.fov.is_in_fov(entity.x, entity.y) || (entity.always_visible && is_explored) {
+            entity.draw(&mut gui.offscreen);
+        }
+    }
+
+    // blit the contents of "offscreen" to the root
+    // From now on, the offscreen panel will represent only the map
+    gui.root.blit(&gui.offscreen, 0, 0, 1.0);
+
+    // re-initialize the gui panel to black, call render_bar to display the player’s HP,
+    // then show the panel on the root
+    gui.gui_panel.clear();
+
+    // show the player's stats
+    let hp = entities[PLAYER].fighter.map_or(0, |f| f.hp);
+    let max_hp = entities[PLAYER].max_hp(game);
+
+    render_bar(
+        &mut gui.gui_panel,
+        1,
+        1,
+        BAR_WIDTH,
+        "HP",
+        hp,
+        max_hp,
+        LIGHT_RED,
+        DARKER_RED,
+    );
+
+    // show the status effects on the player under the HP bar, each in its color with the turns left
+    // the ones that do not fit the width of the bar are left out
+    let mut x = 1;
+    for status in &entities[PLAYER].effects {
+        let text = format!("{} {}", status.effect.name(), status.turns);
+        if x + text.len() as i32 > BAR_WIDTH + 1 {
+            break;
+        }
+        gui.gui_panel.print(x, 2, &text, status.effect.color());
+        x += text.len() as i32 + 1;
+    }
+
+    // show the player's experience towards the next level
+    let xp = entities[PLAYER].fighter.map_or(0, |f| f.xp);
+    let level = entities[PLAYER].level;
+
+    render_bar(
+        &mut gui.gui_panel,
+        1,
+        3,
+        BAR_WIDTH,
+        &format!("Lvl {} XP", level),
+        xp,
+        level_up_xp(level),
+        LIGHT_VIOLET,
+        DARKER_VIOLET,
+    );
+
+    // show how deep in the dungeon the player is
+    gui.gui_panel.print_rect(1, 4, BAR_WIDTH, &format!("Dungeon level: {}", game.depth), WHITE);
+
+    // show the seed of the maze so the level can be reproduced (--seed)
+    gui.gui_panel.print_rect(1, 5, BAR_WIDTH, &format!("Seed: {}", game.seed), LIGHT_GREY);
+    gui.gui_panel.print_rect(1, 6, BAR_WIDTH, &format!("Turn: {}", game.turn), LIGHT_GREY);
+
+    // show what is under the mouse (or the look cursor) on the first line of the panel
+    if let Some((x, y)) = gui.look_at
+        && let Some(description) = describe_tile(x, y, game, entities)
+    {
+        gui.gui_panel.print(1, 0, &description, LIGHT_GREY);
+    }
+
+    // print the game messages, one line at a time
+    let mut y = MSG_HEIGHT as i32;
+    for message in game.messages.messages.iter().rev() {
+        let msg_height = text_height(&message.text, MSG_WIDTH);
+        y -= msg_height;
+
+        // y < 1 => draw on the look line or above the gui panel
+        // since we run out of space stop printing messages
+        if y < 1 {
+            break;
+        }
+
+        gui.gui_panel.print_rect(MSG_X, y, MSG_WIDTH, &message.text, message.color);
+    }
+
+    // blit the contents of `panel` to the root
+    gui.root.blit(&gui.gui_panel, 0, PANEL_Y, 1.0);
+}
@@ -0,0 +1,136 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+use crate::models::definitions::*;
+use crate::models::engine::*;
+use crate::models::entity::*;
+use crate::models::generators::*;
+use crate::models::maze::*;
+
+// bump the version every time the replay file changes (like the save file)
+pub const REPLAY_VERSION: u32 = 1;
+
+// how many turns a second a replay is played back in the window, unless --replay-speed says otherwise
+pub const REPLAY_SPEED: u32 = 10;
+
+// a recorded game - enough to play it again turn by turn
+// seed, generator - the new game it started from (engine.rs)
+// commands - every command played, in the script format (up, use 0 12 5, ...), a walk as its steps
+// checksum - of the game at the end of the recording, the playback must end up the same
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub generator: Option<Generator>,
+    pub commands: Vec<String>,
+    pub checksum: u64
+}
+
+impl Replay {
+    // the replay of a recorded game as it is now, None if the game is not recorded
+    pub fn from_game(game: &Game, entities: &[Entity]) -> Option<Self> {
+        let commands = game.recording.as_ref()?;
+        Some(Replay {
+            version: REPLAY_VERSION,
+            seed: game.seed,
+            generator: game.generator,
+            commands: commands.iter().map(|command| command.to_string()).collect(),
+            checksum: checksum(game, entities)
+        })
+    }
+}
+
+/// @title start_recording
+/// @author GeorgiKostadinovPro
+/// @notice record the game for a replay
+/// @dev custom fn to remember every command played from now on (engine.rs) - only a new game can be replayed
+pub fn start_recording(game: &mut Game) {
+    game.recording = Some(vec![]);
+}
+
+/// @title checksum
+/// @author GeorgiKostadinovPro
+/// @notice a fingerprint of the game state
+/// @dev custom fn to hash (64-bit FNV-1a) the entities, the inventory, the maze and the turn as json
+/// the same on every machine and every build, unlike the std hasher
+pub fn checksum(game: &Game, entities: &[Entity]) -> u64 {
+    let state = (entities, &game.inventory, &game.maze, game.depth, game.turn);
+    let json = serde_json::to_vec(&state).expect("the game state can always be written as json");
+
+    json.iter().fold(0xcbf29ce484222325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+/// @title save_replay
+/// @author GeorgiKostadinovPro
+/// @notice write a replay to disk
+/// @dev custom fn to write the replay as json
+pub fn save_replay<P: AsRef<Path>>(path: P, replay: &Replay) -> Result<(), Box<dyn Error>> {
+    let json = serde_json::to_string_pretty(replay)?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+/// @title load_replay
+/// @author GeorgiKostadinovPro
+/// @notice read a replay from disk
+/// @dev custom fn to read a replay file, check its version and that every command can be played
+/// an error names the offending command e.g. commands[3]: unknown command 'jump'
+pub fn load_replay<P: AsRef<Path>>(path: P) -> Result<Replay, Box<dyn Error>> {
+    let json = fs::read_to_string(path)?;
+    let replay: Replay = serde_json::from_str(&json)?;
+
+    if replay.version != REPLAY_VERSION {
+        return Err(format!(
+            "the replay file version {} is not supported (expected {})",
+            replay.version, REPLAY_VERSION
+        ).into());
+    }
+
+    for (i, command) in replay.commands.iter().enumerate() {
+        command.parse::<Command>().map_err(|err| format!("commands[{}]: {}", i, err))?;
+    }
+
+    Ok(replay)
+}
+
+/// @title play_back
+/// @author GeorgiKostadinovPro
+/// @notice play a recorded game again
+/// @dev custom fn to start the recorded new game and play its commands one by one
+/// after_command runs after every command - the window draws the turn there, false stops the playback
+/// returns the game as it is at the end (check it with verify_replay)
+pub fn play_back(
+    replay: &Replay,
+    definitions: Definitions,
+    mut after_command: impl FnMut(&Game, &[Entity]) -> bool
+) -> (Game, Vec<Entity>) {
+    let (mut game, mut entities) = new_game(replay.seed, definitions, replay.generator);
+
+    // the commands were checked when the replay was loaded
+    for command in replay.commands.iter().filter_map(|command| command.parse::<Command>().ok()) {
+        play_turn(command, &mut game, &mut entities);
+        if !after_command(&game, &entities) {
+            break;
+        }
+    }
+
+    (game, entities)
+}
+
+/// @title verify_replay
+/// @author GeorgiKostadinovPro
+/// @notice check that a playback ended like the recorded game
+/// @dev custom fn to compare the checksum of the played back game with the recorded one
+/// a mismatch means the game played differently (other definitions, a bug, a changed rule)
+pub fn verify_replay(replay: &Replay, game: &Game, entities: &[Entity]) -> Result<(), Box<dyn Error>> {
+    let played = checksum(game, entities);
+    if played != replay.checksum {
+        return Err(format!(
+            "the replay does not match: checksum {:016x} recorded, {:016x} played",
+            replay.checksum, played
+        ).into());
+    }
+    Ok(())
+}
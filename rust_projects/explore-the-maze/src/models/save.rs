@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::models::definitions::*;
+use crate::models::engine::*;
+use crate::models::entity::*;
+use crate::models::fov::*;
+use crate::models::generators::*;
+use crate::models::maze::*;
+use crate::models::messages::*;
+use crate::models::util::*;
+
+// default save file, next to the game
+pub const SAVE_FILE: &str = "savegame";
+
+// bump the version every time the saved data changes
+// an older (or newer) save file is rejected instead of loaded wrong
+pub const SAVE_VERSION: u32 = 15;
+
+// what is written to disk - borrows the game so saving does not clone the maze
+// the FOV is not saved, it is computed from the maze on load (with the saved algorithm)
+// the rng state cannot be saved, a seed rolled from it is saved instead to reseed it on load (reseed_like_saved does it for the running game)
+#[derive(Serialize)]
+struct SaveData<'a> {
+    version: u32,
+    seed: u64,
+    rng_seed: u64,
+    maze: &'a Maze,
+    messages: &'a Messages,
+    inventory: &'a [Entity],
+    entities: &'a [Entity],
+    depth: u32,
+    floors: &'a BTreeMap<u32, Floor>,
+    generator: Option<Generator>,
+    turn: u32,
+    fov_algorithm: FovAlgorithm
+}
+
+// what is read from disk
+#[derive(Deserialize)]
+struct LoadData {
+    seed: u64,
+    rng_seed: u64,
+    maze: Maze,
+    messages: Messages,
+    inventory: Vec<Entity>,
+    entities: Vec<Entity>,
+    depth: u32,
+    floors: BTreeMap<u32, Floor>,
+    generator: Option<Generator>,
+    turn: u32,
+    fov_algorithm: FovAlgorithm
+}
+
+/// @title save_game
+/// @author GeorgiKostadinovPro
+/// @notice save the game to disk
+/// @dev custom fn to write the maze (with fog of war), all entities, the inventory and the message log as versioned json
+/// the other visited floors of the dungeon are saved too, so the player can still climb back up
+/// the running game is not changed - to play on like the loaded one it is reseeded by the caller (reseed_like_saved)
+pub fn save_game<P: AsRef<Path>>(path: P, game: &Game, entities: &[Entity]) -> Result<(), Box<dyn Error>> {
+    let data = SaveData {
+        version: SAVE_VERSION,
+        seed: game.seed,
+        rng_seed: saved_rng_seed(game),
+        maze: &game.maze,
+        messages: &game.messages,
+        inventory: &game.inventory,
+        entities,
+        depth: game.depth,
+        floors: &game.floors,
+        generator: game.generator,
+        turn: game.turn,
+        fov_algorithm: game.fov_algorithm
+    };
+
+    let json = serde_json::to_string(&data)?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+/// @title reseed_like_saved
+/// @author GeorgiKostadinovPro
+/// @notice let a saved game play on like it will when loaded
+/// @dev custom fn to reseed the rng of the running game with the seed its save file got (saved_rng_seed)
+pub fn reseed_like_saved(game: &mut Game) {
+    game.rng = rng_from_seed(saved_rng_seed(game));
+}
+
+// the seed a save file reseeds the rng with, rolled from a copy so the rng of the game stays as it is
+fn saved_rng_seed(game: &Game) -> u64 {
+    game.rng.clone().r#gen()
+}
+
+/// @title load_game
+/// @author GeorgiKostadinovPro
+/// @notice load a saved game from disk
+/// @dev custom fn to read a save file, check its version and rebuild the game with its FOV
+/// the floors not generated yet are spawned from the current definitions (they are not saved)
+pub fn load_game<P: AsRef<Path>>(path: P, definitions: Definitions) -> Result<(Game, Vec<Entity>), Box<dyn Error>> {
+    let json = fs::read_to_string(path)?;
+
+    // read the version first, the rest of the file may not match the current structs
+    let value: serde_json::Value = serde_json::from_str(&json)?;
+    let version = value
+        .get("version")
+        .and_then(|version| version.as_u64())
+        .ok_or("the save file has no version")?;
+
+    if version != SAVE_VERSION as u64 {
+        return Err(format!(
+            "the save file version {} is not supported (expected {})",
+            version, SAVE_VERSION
+        ).into());
+    }
+
+    let data: LoadData = serde_json::from_value(value)?;
+
+    if data.entities.is_empty() {
+        return Err("the save file has no player".into());
+    }
+
+    // every floor, the current one and the ones left behind, must fit the FOV map
+    let has_maze_size = |maze: &Maze| {
+        maze.len() == MAZE_WIDTH as usize
+            && maze.iter().all(|column| column.len() == MAZE_HEIGHT as usize)
+    };
+    if !has_maze_size(&data.maze) || !data.floors.values().all(|floor| has_maze_size(&floor.maze)) {
+        return Err(format!("the saved maze is not {}x{}", MAZE_WIDTH, MAZE_HEIGHT).into());
+    }
+
+    if data.depth == 0 || data.floors.contains_key(&data.depth) {
+        return Err(format!("the saved depth {} is not valid", data.depth).into());
+    }
+
+    let mut game = Game {
+        maze: data.maze,
+        messages: data.messages,
+        seed: data.seed,
+        fov: FovMap::new(MAZE_WIDTH, MAZE_HEIGHT),
+        fov_algorithm: data.fov_algorithm,
+        inventory: data.inventory,
+        rng: rng_from_seed(data.rng_seed),
+        depth: data.depth,
+        floors: data.floors,
+        definitions,
+        generator: data.generator,
+        turn: data.turn,
+        // a replay starts from a new game, a continued one is not recorded
+        recording: None,
+        noises: vec![],
+        projectiles: vec![]
+    };
+
+    compute_fov(&mut game, &data.entities);
+
+    Ok((game, data.entities))
+}
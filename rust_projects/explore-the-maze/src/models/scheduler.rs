@@ -0,0 +1,72 @@
+use crate::models::ai::*;
+use crate::models::awareness::*;
+use crate::models::effects::*;
+use crate::models::entity::*;
+use crate::models::maze::*;
+
+// the energy an entity needs to act, also what a normal action costs
+pub const ENERGY_THRESHOLD: i32 = 100;
+
+// the energy a fighter of normal speed gains every tick of the game clock - exactly one action
+// faster ones (bats) gain more and act more than once a tick, slower ones (trolls) skip some ticks
+pub const NORMAL_SPEED: i32 = 100;
+
+/// @title speed
+/// @author GeorgiKostadinovPro
+/// @notice how fast an entity acts right now
+/// @dev custom fn to get the energy the entity gains every tick - its fighter speed, doubled while it is hasted
+/// 0 for everything that is not a fighter (items, stairs, the dead)
+pub fn speed(entity: &Entity) -> i32 {
+    let base_speed = entity.fighter.map_or(0, |f| f.speed);
+    if has_effect(entity, Effect::Haste) {
+        base_speed * 2
+    } else {
+        base_speed
+    }
+}
+
+/// @title is_ready
+/// @author GeorgiKostadinovPro
+/// @notice check if an entity has the energy to act
+/// @dev custom fn to compare the energy of the entity with ENERGY_THRESHOLD
+pub fn is_ready(entity: &Entity) -> bool {
+    entity.energy >= ENERGY_THRESHOLD
+}
+
+/// @title advance_time
+/// @author GeorgiKostadinovPro
+/// @notice let the world act until the player can act again
+/// @dev custom fn to run the game clock tick by tick once the player has spent his energy
+/// every tick each living fighter gains its speed in energy, then every monster acts as long as it has enough
+/// (a normal action each time), then the status effects tick (effects.rs) and the turn counter goes up
+/// returns the num of ticks (turns) that passed - 0 if the player still has the energy for another action
+pub fn advance_time(game: &mut Game, entities: &mut Vec<Entity>) -> u32 {
+    let mut ticks = 0;
+
+    while !is_ready(&entities[PLAYER]) && entities[PLAYER].is_alive {
+        for entity in entities.iter_mut().filter(|entity| entity.is_alive) {
+            entity.energy += speed(entity);
+        }
+
+        // the fights of the last tick are heard before anyone acts
+        hear_noises(game, entities);
+
+        // a scroll may have killed the player himself (fireball)
+        for id in 0..entities.len() {
+            while entities[id].ai.is_some() && entities[id].is_alive && entities[PLAYER].is_alive && is_ready(&entities[id]) {
+                entities[id].energy -= ENERGY_THRESHOLD;
+                ai_take_turn(id, game, entities);
+            }
+        }
+
+        // poison, regeneration and the rest act once a tick, then wear off a turn
+        tick_effects(game, entities);
+
+        // the next turn starts, its messages are stamped with it
+        ticks += 1;
+        game.turn += 1;
+        game.messages.turn = game.turn;
+    }
+
+    ticks
+}
@@ -0,0 +1,115 @@
+use tcod::console::*;
+use tcod::input::{self, Key};
+use crate::models::render::*;
+use crate::models::tcod_db::*;
+
+// the libtcod window - an SDL window drawn with the arial10x10.png font, it needs a graphical display
+pub struct TcodBackend {
+    root: Root
+}
+
+impl TcodBackend {
+    // create a new window
+    // default values for not specified options
+    // no Default - opening a window is not a default value
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        // limit the fps to 20
+        tcod::system::set_fps(LIMIT_FPS);
+
+        let root = Root::initializer()
+            .font("arial10x10.png", FontLayout::Tcod)
+            .font_type(FontType::Greyscale)
+            .size(SCREEN_WIDTH, SCREEN_HEIGHT)
+            .title(GAME_TITLE)
+            .init();
+
+        TcodBackend { root }
+    }
+}
+
+impl Backend for TcodBackend {
+    // every cell of the screen on the root console, then flushed to the window (it waits for the next frame)
+    fn present(&mut self, screen: &Panel) {
+        for y in 0..screen.height() {
+            for x in 0..screen.width() {
+                if let Some(cell) = screen.cell(x, y) {
+                    self.root.put_char_ex(x, y, cell.glyph, cell.foreground, cell.background);
+                }
+            }
+        }
+        self.root.flush();
+    }
+
+    fn check_for_event(&mut self) -> Option<Event> {
+        match input::check_for_event(input::KEY_PRESS | input::MOUSE) {
+            Some((_, input::Event::Key(key))) => Some(Event::Key(key_press(key))),
+            Some((_, input::Event::Mouse(mouse))) => Some(Event::Mouse(Mouse {
+                x: mouse.cx as i32,
+                y: mouse.cy as i32,
+                left: mouse.lbutton_pressed,
+                right: mouse.rbutton_pressed
+            })),
+            None => None
+        }
+    }
+
+    fn wait_for_keypress(&mut self) -> KeyPress {
+        key_press(self.root.wait_for_keypress(true))
+    }
+
+    fn window_closed(&self) -> bool {
+        self.root.window_closed()
+    }
+
+    fn toggle_fullscreen(&mut self) {
+        let fullscreen = self.root.is_fullscreen();
+        self.root.set_fullscreen(!fullscreen);
+    }
+}
+
+fn key_press(key: Key) -> KeyPress {
+    KeyPress { name: key_name(key), alt: key.alt }
+}
+
+/// @title key_name
+/// @author GeorgiKostadinovPro
+/// @notice the name of a pressed key in the key bindings
+/// @dev custom fn to name a key press like the key bindings file does - "up", "kp8", "escape" or its character e.g. "h"
+/// None for keys that cannot be bound (shift, F1, ...)
+fn key_name(key: Key) -> Option<String> {
+    use tcod::input::KeyCode::*;
+
+    let name = match key.code {
+        Up => "up",
+        Down => "down",
+        Left => "left",
+        Right => "right",
+        Home => "home",
+        End => "end",
+        PageUp => "pageup",
+        PageDown => "pagedown",
+        Insert => "insert",
+        Delete => "delete",
+        Enter => "enter",
+        Escape => "escape",
+        Spacebar => "space",
+        Tab => "tab",
+        Backspace => "backspace",
+        NumPad0 => "kp0",
+        NumPad1 => "kp1",
+        NumPad2 => "kp2",
+        NumPad3 => "kp3",
+        NumPad4 => "kp4",
+        NumPad5 => "kp5",
+        NumPad6 => "kp6",
+        NumPad7 => "kp7",
+        NumPad8 => "kp8",
+        NumPad9 => "kp9",
+        NumPadEnter => "kpenter",
+        // letters, digits and symbols are named by their character
+        _ if key.printable != '\0' && !key.printable.is_control() => return Some(key.printable.to_string()),
+        _ => return None
+    };
+    Some(name.to_string())
+}
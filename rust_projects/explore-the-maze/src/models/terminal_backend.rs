@@ -0,0 +1,188 @@
+use std::io::{self, Stdout, Write};
+use std::time::Duration;
+use crossterm::event::{self, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind};
+use crossterm::style::{self, Colors};
+use crossterm::{cursor, execute, queue, terminal};
+use tcod::colors::Color;
+use crate::models::render::*;
+use crate::models::tcod_db::*;
+
+// how long check_for_event waits for the player - the game loop runs at LIMIT_FPS like in the window
+const FRAME: Duration = Duration::from_millis(1000 / LIMIT_FPS as u64);
+
+// the terminal the game is started from, e.g. over SSH - no display and no font needed
+// the cells are drawn with 24 bit ANSI colors, the keys and the mouse are read in raw mode (crossterm)
+// shown - the screen last sent to the terminal, only the cells that changed since are sent again
+// is_closed - Ctrl+C was pressed (the raw mode does not let it stop the game)
+pub struct TerminalBackend {
+    stdout: Stdout,
+    shown: Option<Panel>,
+    is_closed: bool
+}
+
+impl TerminalBackend {
+    // take over the terminal: raw mode, the alternate screen (the shell comes back as it was) and the mouse
+    // an error if the terminal is smaller than the screen of the game
+    pub fn new() -> io::Result<Self> {
+        let (width, height) = terminal::size()?;
+        if (width as i32) < SCREEN_WIDTH || (height as i32) < SCREEN_HEIGHT {
+            return Err(io::Error::other(format!(
+                "the terminal is {}x{}, the game needs at least {}x{}",
+                width, height, SCREEN_WIDTH, SCREEN_HEIGHT
+            )));
+        }
+
+        terminal::enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(
+            stdout,
+            terminal::EnterAlternateScreen,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::Hide,
+            event::EnableMouseCapture
+        )?;
+
+        Ok(TerminalBackend { stdout, shown: None, is_closed: false })
+    }
+
+    // send the cells that changed since the last frame
+    fn draw(&mut self, screen: &Panel) -> io::Result<()> {
+        for y in 0..screen.height() {
+            for x in 0..screen.width() {
+                let Some(cell) = screen.cell(x, y) else {
+                    continue;
+                };
+                if self.shown.as_ref().and_then(|shown| shown.cell(x, y)) == Some(cell) {
+                    continue;
+                }
+
+                queue!(
+                    self.stdout,
+                    cursor::MoveTo(x as u16, y as u16),
+                    style::SetColors(Colors::new(rgb(cell.foreground), rgb(cell.background))),
+                    style::Print(cell.glyph)
+                )?;
+            }
+        }
+
+        self.stdout.flush()?;
+        self.shown = Some(screen.clone());
+        Ok(())
+    }
+
+    // the key presses and the mouse, None for anything else
+    // Ctrl+C closes the game, a resized terminal is drawn again from scratch
+    fn event(&mut self, event: event::Event) -> Option<Event> {
+        match event {
+            event::Event::Key(key) if key.kind != KeyEventKind::Release => {
+                if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                    self.is_closed = true;
+                }
+                Some(Event::Key(KeyPress { name: key_name(key), alt: key.modifiers.contains(KeyModifiers::ALT) }))
+            }
+            event::Event::Mouse(mouse) => {
+                let (left, right) = match mouse.kind {
+                    MouseEventKind::Down(MouseButton::Left) => (true, false),
+                    MouseEventKind::Down(MouseButton::Right) => (false, true),
+                    MouseEventKind::Moved | MouseEventKind::Drag(_) => (false, false),
+                    _ => return None
+                };
+                Some(Event::Mouse(Mouse { x: mouse.column as i32, y: mouse.row as i32, left, right }))
+            }
+            event::Event::Resize(_, _) => {
+                let _ = execute!(self.stdout, terminal::Clear(terminal::ClearType::All));
+                self.shown = None;
+                None
+            }
+            _ => None
+        }
+    }
+}
+
+// the terminal is given back as it was found, also when the game panics
+impl Drop for TerminalBackend {
+    fn drop(&mut self) {
+        let _ = execute!(
+            self.stdout,
+            event::DisableMouseCapture,
+            style::ResetColor,
+            cursor::Show,
+            terminal::LeaveAlternateScreen
+        );
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+impl Backend for TerminalBackend {
+    // a frame that could not be written is sent again whole with the next one
+    fn present(&mut self, screen: &Panel) {
+        if self.draw(screen).is_err() {
+            self.shown = None;
+        }
+    }
+
+    // waits a frame at most, so the game loop does not spin
+    fn check_for_event(&mut self) -> Option<Event> {
+        if !event::poll(FRAME).unwrap_or(false) {
+            return None;
+        }
+        let event = event::read().ok()?;
+        self.event(event)
+    }
+
+    // a terminal that cannot be read any more closes the game
+    fn wait_for_keypress(&mut self) -> KeyPress {
+        while !self.is_closed {
+            match event::read() {
+                Ok(event) => {
+                    if let Some(Event::Key(key)) = self.event(event) {
+                        return key;
+                    }
+                }
+                Err(_) => self.is_closed = true
+            }
+        }
+        KeyPress { name: Some("escape".to_string()), alt: false }
+    }
+
+    fn window_closed(&self) -> bool {
+        self.is_closed
+    }
+
+    // the terminal is as large as the player makes it
+    fn toggle_fullscreen(&mut self) {}
+}
+
+fn rgb(color: Color) -> style::Color {
+    style::Color::Rgb { r: color.r, g: color.g, b: color.b }
+}
+
+/// @title key_name
+/// @author GeorgiKostadinovPro
+/// @notice the name of a pressed key in the key bindings
+/// @dev custom fn to name a terminal key press like the key bindings file does - "up", "escape" or its character e.g. "h"
+/// a terminal does not tell the numpad apart - it sends its digits (or arrows), never "kp8"
+/// None for keys that cannot be bound (F1, ...)
+pub fn key_name(key: KeyEvent) -> Option<String> {
+    let name = match key.code {
+        KeyCode::Up => "up",
+        KeyCode::Down => "down",
+        KeyCode::Left => "left",
+        KeyCode::Right => "right",
+        KeyCode::Home => "home",
+        KeyCode::End => "end",
+        KeyCode::PageUp => "pageup",
+        KeyCode::PageDown => "pagedown",
+        KeyCode::Insert => "insert",
+        KeyCode::Delete => "delete",
+        KeyCode::Enter => "enter",
+        KeyCode::Esc => "escape",
+        KeyCode::Char(' ') => "space",
+        KeyCode::Tab => "tab",
+        KeyCode::Backspace => "backspace",
+        // letters, digits and symbols are named by their character
+        KeyCode::Char(c) if !c.is_control() => return Some(c.to_string()),
+        _ => return None
+    };
+    Some(name.to_string())
+}
@@ -0,0 +1,135 @@
+use tcod::colors::*;
+
+use crate::models::engine::*;
+use crate::models::entity::*;
+use crate::models::features::*;
+use crate::models::maze::*;
+use crate::models::messages::*;
+use crate::models::pathfinding::*;
+
+// where a walk over many turns goes
+// Unexplored - the closest tile the player has not seen yet (auto-explore), again and again until all is seen
+// Tile - an explored tile the player picked (click-to-travel)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Destination {
+    Unexplored,
+    Tile(i32, i32)
+}
+
+/// @title walk
+/// @author GeorgiKostadinovPro
+/// @notice walk the player to a destination over many turns
+/// @dev custom fn to move the player one step at a time (every step is a full turn, the monsters act too)
+/// the walk stops when a monster is in view, on a found item, on arrival or when there is no way further
+/// after_step runs after every step - the window draws the step there, false stops the walk (a key press)
+/// returns the num of turns walked
+pub fn walk(
+    destination: Destination,
+    game: &mut Game,
+    entities: &mut Vec<Entity>,
+    mut after_step: impl FnMut(&mut Game, &mut Vec<Entity>) -> bool
+) -> u32 {
+    let mut turns = 0;
+
+    while entities[PLAYER].is_alive {
+        // a monster in view needs the player's full attention
+        if let Some(monster) = monster_in_view(game, entities) {
+            let message = if turns == 0 {
+                format!("Not with the {} in view!", monster)
+            } else {
+                format!("You spot the {} and stop.", monster)
+            };
+            game.messages.add(message, LIGHT_RED, MessageCategory::Combat);
+            break;
+        }
+
+        let Some((x, y)) = next_step(destination, game, entities) else {
+            match destination {
+                Destination::Unexplored => game.messages.add("There is nothing left to explore.", WHITE, MessageCategory::System),
+                Destination::Tile(x, y) if entities[PLAYER].get_pos() != (x, y) => {
+                    game.messages.add("You cannot find a way there.", WHITE, MessageCategory::System)
+                }
+                Destination::Tile(..) => {}
+            }
+            break;
+        };
+
+        let (player_x, player_y) = entities[PLAYER].get_pos();
+        let is_closed_door = game.maze[x as usize][y as usize].feature == Some(Feature::Door { is_open: false });
+        play_turn(Command::Move(x - player_x, y - player_y), game, entities);
+        turns += 1;
+
+        // something stepped in the way in the monsters' turn and the player bumped into it (or a trap took him away)
+        // a closed door is opened on the way, the walk goes on through it
+        if entities[PLAYER].get_pos() != (x, y) && !is_closed_door {
+            break;
+        }
+
+        // an item is worth a stop, the player may want to pick it up
+        if let Some(item) = item_under_player(entities) {
+            game.messages.add(format!("You find a {}.", item), LIGHT_VIOLET, MessageCategory::Loot);
+            break;
+        }
+
+        if !after_step(game, entities) {
+            break;
+        }
+    }
+
+    turns
+}
+
+// the tile to step on next, None if the walk is over (arrived, nothing left to explore, no way)
+// the monsters are walked around, if they block every way the walk goes on until one comes into view
+fn next_step(destination: Destination, game: &Game, entities: &[Entity]) -> Option<(i32, i32)> {
+    let (player_x, player_y) = entities[PLAYER].get_pos();
+
+    match destination {
+        Destination::Unexplored => {
+            let mut unexplored = vec![];
+            for x in 0..MAZE_WIDTH {
+                for y in 0..MAZE_HEIGHT {
+                    let tile = game.maze[x as usize][y as usize];
+                    if !tile.is_explored && !tile.blocked {
+                        unexplored.push((x, y));
+                    }
+                }
+            }
+
+            DijkstraMap::new(&unexplored, &game.maze, entities, true)
+                .step_towards(player_x, player_y)
+                .or_else(|| DijkstraMap::new(&unexplored, &game.maze, entities, false).step_towards(player_x, player_y))
+        }
+        Destination::Tile(x, y) => {
+            // only where the player has been (or seen) - he does not know the way anywhere else
+            let is_known = x >= 0 && y >= 0 && x < MAZE_WIDTH && y < MAZE_HEIGHT
+                && game.maze[x as usize][y as usize].is_explored;
+            if !is_known {
+                return None;
+            }
+
+            find_path((player_x, player_y), (x, y), &game.maze, entities, true)
+                .or_else(|| find_path((player_x, player_y), (x, y), &game.maze, entities, false))?
+                .first()
+                .copied()
+        }
+    }
+}
+
+// the name of a living monster the player can see
+fn monster_in_view(game: &Game, entities: &[Entity]) -> Option<String> {
+    entities
+        .iter()
+        .skip(PLAYER + 1)
+        .find(|entity| entity.ai.is_some() && entity.is_alive && game.fov.is_in_fov(entity.x, entity.y))
+        .map(|monster| monster.name.clone())
+}
+
+// the name of an item lying where the player stands
+fn item_under_player(entities: &[Entity]) -> Option<String> {
+    let player_pos = entities[PLAYER].get_pos();
+    entities
+        .iter()
+        .find(|entity| entity.item.is_some() && entity.get_pos() == player_pos)
+        .map(|item| item.name.clone())
+}
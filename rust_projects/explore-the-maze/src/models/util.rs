@@ -1,3 +1,14 @@
+use rand::{Isaac64Rng, SeedableRng};
+
+// the random number generator used to build the maze
+// seeded explicitly so the same seed always yields the same maze and monsters
+pub type GameRng = Isaac64Rng;
+
+// create the game rng from a seed
+pub fn rng_from_seed(seed: u64) -> GameRng {
+    Isaac64Rng::from_seed(&[seed])
+}
+
 // helper to split a slize into two to mutate two borrows
 pub fn mut_two<T>(slice: &mut [T], i: usize, j: usize) -> (&mut T, &mut T) {
     assert!(i != j);
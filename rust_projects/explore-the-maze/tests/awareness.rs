@@ -0,0 +1,98 @@
+mod common;
+use common::*;
+
+use explore_the_maze::models::awareness::*;
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::look::*;
+use explore_the_maze::models::maze::*;
+
+#[test]
+fn new_floors_have_sleeping_and_wandering_monsters() {
+    let mut asleep = 0;
+    let mut wandering = 0;
+    for seed in 0..10 {
+        let simulation = Simulation::new(seed);
+        for monster in simulation.entities.iter().filter(|entity| entity.ai.is_some()) {
+            match monster.awareness {
+                Awareness::Asleep => asleep += 1,
+                Awareness::Wandering => wandering += 1,
+                awareness => panic!("a new monster is {}", awareness)
+            }
+        }
+    }
+    assert!(asleep > 0 && wandering > 0);
+}
+
+#[test]
+fn a_sleeping_monster_wakes_up_next_to_the_player() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities.push(orc(11, 10));
+    simulation.entities[1].awareness = Awareness::Asleep;
+    assert_eq!(names_under(11, 10, &simulation.game, &simulation.entities), vec!["orc (asleep)"]);
+
+    // waking up takes its turn, the next one it attacks
+    simulation.step(Command::Wait);
+    assert_eq!(simulation.entities[1].awareness, Awareness::Wandering);
+    assert_eq!(simulation.game.messages.messages.last().unwrap().text, "The orc wakes up!");
+    assert_eq!(hp(simulation.player()), 30);
+
+    simulation.step(Command::Wait);
+    assert_eq!(simulation.entities[1].awareness, Awareness::Hunting { last_seen: (10, 10) });
+    assert_eq!(hp(simulation.player()), 27);
+}
+
+#[test]
+fn the_noise_of_a_fight_wakes_the_monsters_around() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    // a wall so the sleepers do not see the fight, only hear it
+    for x in 10..=30 {
+        simulation.game.maze[x][14] = Tile::wall();
+    }
+    simulation.entities.push(orc(11, 10));
+    simulation.entities.push(orc(11, 10 + COMBAT_NOISE - 1));
+    simulation.entities.push(orc(30, 20));
+    simulation.entities[2].awareness = Awareness::Asleep;
+    simulation.entities[3].awareness = Awareness::Asleep;
+
+    // attacking the orc next to the player - the one in earshot comes looking, the far one sleeps on
+    simulation.step(Command::Move(1, 0));
+    assert!(matches!(simulation.entities[2].awareness, Awareness::Searching { last_seen: (11, 10), .. }));
+    assert_eq!(simulation.entities[3].awareness, Awareness::Asleep);
+}
+
+#[test]
+fn a_monster_searches_where_it_last_saw_the_player() {
+    let mut simulation = Simulation::new(5);
+    // two rooms joined by a door at (20, 15)
+    arena(&mut simulation, 10, 10, 30, 20);
+    for y in 10..=20 {
+        if y != 15 {
+            simulation.game.maze[20][y] = Tile::wall();
+        }
+    }
+    simulation.entities[PLAYER].set_pos(19, 15);
+    simulation.entities.push(orc(25, 15));
+    compute_fov(&mut simulation.game, &simulation.entities);
+
+    simulation.step(Command::Wait);
+    assert_eq!(simulation.entities[1].awareness, Awareness::Hunting { last_seen: (19, 15) });
+
+    // out of sight behind the wall - the orc goes to the door he was seen at
+    simulation.step(Command::Move(0, -1));
+    simulation.step(Command::Move(0, -1));
+    simulation.step(Command::Move(0, -1));
+    assert!(matches!(simulation.entities[1].awareness, Awareness::Searching { last_seen: (19, 15), .. }));
+
+    // nobody there - it looks around a few turns, then gives up
+    // the player is walled in a corner, so the wandering orc cannot come across him again
+    simulation.entities[PLAYER].set_pos(10, 10);
+    for (x, y) in [(11, 10), (10, 11), (11, 11)] {
+        simulation.game.maze[x][y] = Tile::wall();
+    }
+    compute_fov(&mut simulation.game, &simulation.entities);
+    simulation.run(vec![Command::Wait; 5 + SEARCH_TURNS as usize]);
+    assert_eq!(simulation.entities[1].awareness, Awareness::Wandering);
+}
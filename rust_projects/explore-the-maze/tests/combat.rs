@@ -0,0 +1,172 @@
+mod common;
+use common::*;
+
+use explore_the_maze::models::combat::*;
+use explore_the_maze::models::definitions::*;
+use explore_the_maze::models::effects::*;
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::maze::*;
+use explore_the_maze::models::util::*;
+
+fn monster(name: &str, x: i32, y: i32) -> Entity {
+    Definitions::default().monsters.iter().find(|monster| monster.name == name).unwrap().spawn(x, y)
+}
+
+fn last_message(simulation: &Simulation) -> &str {
+    &simulation.game.messages.messages.last().unwrap().text
+}
+
+// the player next to a sturdy orc that does not fight back
+fn sparring(seed: u64) -> Simulation {
+    let mut simulation = Simulation::new(seed);
+    arena(&mut simulation, 10, 10, 20, 20);
+    let mut dummy = orc(11, 10);
+    dummy.ai = None;
+    dummy.fighter.as_mut().unwrap().max_hp = 1000;
+    dummy.fighter.as_mut().unwrap().hp = 1000;
+    simulation.entities.push(dummy);
+    simulation
+}
+
+#[test]
+fn dice_are_written_like_on_the_table() {
+    assert_eq!("2d4".parse::<Dice>(), Ok(Dice::new(2, 4)));
+    assert_eq!(Dice::new(1, 6).to_string(), "1d6");
+    assert_eq!(Dice::new(2, 4).range(), (2, 8));
+    assert_eq!(Dice::default().range(), (0, 0));
+    assert!("d4".parse::<Dice>().unwrap_err().contains("invalid dice 'd4'"));
+
+    let mut rng = rng_from_seed(3);
+    let rolls: Vec<i32> = (0..200).map(|_| Dice::new(2, 4).roll(&mut rng)).collect();
+    assert!(rolls.iter().all(|roll| (2..=8).contains(roll)));
+    assert!(rolls.contains(&2) && rolls.contains(&8));
+    assert_eq!(Dice::default().roll(&mut rng), 0);
+}
+
+#[test]
+fn hits_roll_the_damage_dice_on_top_of_the_power() {
+    let mut simulation = sparring(5);
+    simulation.entities[PLAYER].fighter.as_mut().unwrap().damage = Dice::new(1, 4);
+
+    let mut damages = vec![];
+    for _ in 0..40 {
+        let before = hp(&simulation.entities[1]);
+        simulation.step(Command::Move(1, 0));
+        damages.push(before - hp(&simulation.entities[1]));
+    }
+
+    // 5 power + 1d4 against no defense
+    assert!(damages.iter().all(|damage| (6..=9).contains(damage)), "{:?}", damages);
+    assert!(damages.contains(&6) && damages.contains(&9));
+}
+
+#[test]
+fn misses_dodges_and_critical_hits() {
+    let mut simulation = sparring(5);
+    simulation.entities[PLAYER].fighter.as_mut().unwrap().accuracy = 0;
+    simulation.step(Command::Move(1, 0));
+    assert_eq!(last_message(&simulation), "go4ko attacks orc but misses.");
+
+    simulation.entities[PLAYER].fighter.as_mut().unwrap().accuracy = 100;
+    simulation.entities[1].fighter.as_mut().unwrap().evasion = 100;
+    simulation.step(Command::Move(1, 0));
+    assert_eq!(last_message(&simulation), "go4ko attacks orc but orc dodges the blow.");
+    assert_eq!(hp(&simulation.entities[1]), 1000);
+
+    // twice the power, the defense is taken after
+    simulation.entities[1].fighter.as_mut().unwrap().evasion = 0;
+    simulation.entities[1].fighter.as_mut().unwrap().defense = 1;
+    simulation.entities[PLAYER].fighter.as_mut().unwrap().crit = 100;
+    simulation.step(Command::Move(1, 0));
+    assert_eq!(last_message(&simulation), "go4ko attacks orc with a critical hit for 9 hit points!");
+    assert_eq!(hp(&simulation.entities[1]), 1000 - 9);
+}
+
+#[test]
+fn the_same_seed_fights_the_same() {
+    let fight = |seed| {
+        let mut simulation = sparring(seed);
+        let fighter = simulation.entities[PLAYER].fighter.as_mut().unwrap();
+        (fighter.accuracy, fighter.crit, fighter.damage) = (PLAYER_ACCURACY, 30, Dice::new(1, 6));
+        simulation.entities[1].fighter.as_mut().unwrap().evasion = 20;
+        simulation.run(vec![Command::Move(1, 0); 30]);
+        simulation.game.messages.messages.iter().map(|m| m.text.clone()).collect::<Vec<_>>()
+    };
+
+    let messages = fight(8);
+    assert_eq!(messages, fight(8));
+    assert_ne!(messages, fight(9));
+
+    // every outcome shows up in a long enough fight
+    for outcome in ["but misses", "dodges the blow", "with a critical hit", "for"] {
+        assert!(messages.iter().any(|text| text.contains(outcome)), "{}", outcome);
+    }
+}
+
+#[test]
+fn resistances_cut_the_damage_of_their_kind() {
+    let definitions = Definitions::default();
+    let spider = definitions.monsters.iter().find(|m| m.name == "giant spider").unwrap();
+    assert_eq!(spider.damage_type, DamageType::Poison);
+    assert_eq!(spider.resistances.poison, 100);
+
+    // trolls burn well: 12 fireball damage + 50%
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities.push(monster("troll", 16, 13));
+    simulation.entities[1].ai = None;
+    simulation.game.inventory.push(scroll(Item::Fireball));
+    simulation.step(Command::UseItem(0, Some((16, 13))));
+    assert!(simulation.game.messages.messages.iter().any(|m| m.text == "The troll gets burned for 18 hit points."));
+    assert!(!simulation.entities[1].is_alive);
+
+    // more than immune is still immune - no healing, nothing burned
+    let mut fireproof = monster("orc", 18, 13);
+    fireproof.ai = None;
+    fireproof.fighter.as_mut().unwrap().resistances = Resistances { fire: 150, ..Default::default() };
+    assert_eq!(resist(12, DamageType::Fire, &fireproof), 0);
+    simulation.entities.push(fireproof);
+    simulation.game.inventory.push(scroll(Item::Fireball));
+    simulation.step(Command::UseItem(0, Some((18, 13))));
+    assert_eq!(hp(&simulation.entities[2]), 10);
+    assert_eq!(last_message(&simulation), "The orc resists fire damage.");
+
+    // the spider does not mind its own poison
+    let mut spider = monster("giant spider", 16, 13);
+    spider.ai = None;
+    simulation.entities.push(spider);
+    apply_effect(&mut simulation.entities[3], StatusEffect::new(Effect::Poison, 3, 2), &mut simulation.game);
+    simulation.run(vec![Command::Wait; 3]);
+    assert_eq!(hp(&simulation.entities[3]), 8);
+
+    // an orc that shrugs off half of every blow
+    let mut simulation = sparring(5);
+    simulation.entities[1].fighter.as_mut().unwrap().resistances = Resistances { physical: 50, ..Default::default() };
+    simulation.step(Command::Move(1, 0));
+    assert_eq!(hp(&simulation.entities[1]), 1000 - 2);
+    assert_eq!(last_message(&simulation), "orc resists physical damage.");
+}
+
+#[test]
+fn poisonous_bites_say_so() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities.push(monster("giant spider", 11, 10));
+    steady(&mut simulation.entities[1]);
+
+    simulation.step(Command::Wait);
+    let bite = simulation.game.messages.messages.iter().find(|m| m.text.starts_with("giant spider attacks")).unwrap();
+    assert_eq!(bite.text, "giant spider attacks go4ko for 2 hit points of poison damage.");
+}
+
+#[test]
+fn invalid_chances_are_rejected() {
+    let json = include_str!("../data/definitions.json").replacen("\"damage\": \"1d2\"", "\"damage\": \"1d2\", \"accuracy\": 101", 1);
+    let err = parse_definitions(&json).unwrap_err().to_string();
+    assert!(err.contains("monsters[0] \"orc\": accuracy must be from 0 to 100"), "{}", err);
+
+    let json = include_str!("../data/definitions.json").replacen("\"damage\": \"1d2\"", "\"damage\": \"2x\"", 1);
+    assert!(parse_definitions(&json).unwrap_err().to_string().contains("invalid dice '2x'"));
+}
@@ -0,0 +1,120 @@
+// helpers shared by the integration tests
+#![allow(dead_code)]
+
+use tcod::colors::*;
+
+use explore_the_maze::models::combat::*;
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::fov::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::maze::*;
+use explore_the_maze::models::scheduler::*;
+
+// replace the generated maze with a single empty room (x1, y1) - (x2, y2) inclusive
+// and keep only the player in it, so a test controls exactly who is where
+// the player fights steady too - he always hits for his power and never dodges (like the orcs below)
+pub fn arena(simulation: &mut Simulation, x1: i32, y1: i32, x2: i32, y2: i32) {
+    let mut maze = vec![vec![Tile::wall(); MAZE_HEIGHT as usize]; MAZE_WIDTH as usize];
+    for x in x1..=x2 {
+        for y in y1..=y2 {
+            maze[x as usize][y as usize] = Tile::empty();
+        }
+    }
+
+    simulation.game.maze = maze;
+    simulation.entities.truncate(1);
+    simulation.entities[PLAYER].set_pos(x1, y1);
+    steady(&mut simulation.entities[PLAYER]);
+
+    compute_fov(&mut simulation.game, &simulation.entities);
+}
+
+// no rolls in the fights of the fighter - it always hits, for its power, never critically and never dodges
+pub fn steady(entity: &mut Entity) {
+    if let Some(fighter) = entity.fighter.as_mut() {
+        fighter.accuracy = 100;
+        fighter.evasion = 0;
+        fighter.crit = 0;
+        fighter.damage = Dice::default();
+    }
+}
+
+pub fn orc(x: i32, y: i32) -> Entity {
+    let mut orc = Entity::new(x, y, 'o', DESATURATED_GREEN, "orc", true);
+    orc.is_alive = true;
+    orc.ai = Some(Ai::Basic);
+    orc.fighter = Some(Fighter {
+        max_hp: 10,
+        hp: 10,
+        defense: 0,
+        power: 5,
+        xp: 35,
+        on_death: DeathCallback::Monster,
+        on_hit: None,
+        speed: NORMAL_SPEED,
+        sight: TORCH_RADIUS,
+        ranged: None,
+        accuracy: 100,
+        evasion: 0,
+        crit: 0,
+        damage: Dice::default(),
+        damage_type: DamageType::Physical,
+        resistances: Resistances::default()
+    });
+    orc
+}
+
+pub fn hp(entity: &Entity) -> i32 {
+    entity.fighter.map_or(0, |f| f.hp)
+}
+
+pub fn potion(x: i32, y: i32) -> Entity {
+    let mut potion = Entity::new(x, y, '!', VIOLET, "healing potion", false);
+    potion.item = Some(Item::Heal);
+    potion
+}
+
+pub fn scroll(item: Item) -> Entity {
+    let mut scroll = Entity::new(0, 0, '#', LIGHT_YELLOW, "scroll", false);
+    scroll.item = Some(item);
+    scroll
+}
+
+// put the player on the stairs of the current floor (teleport, no turns taken)
+pub fn stand_on_stairs(simulation: &mut Simulation, stairs: Stairs) {
+    let (x, y) = simulation
+        .entities
+        .iter()
+        .find(|entity| entity.stairs == Some(stairs))
+        .map(|entity| entity.get_pos())
+        .expect("no such stairs on this floor");
+    simulation.entities[PLAYER].set_pos(x, y);
+}
+
+pub fn xp(entity: &Entity) -> i32 {
+    entity.fighter.map_or(0, |f| f.xp)
+}
+
+// an unequipped item for the slot with the given bonuses (power, defense, max hp)
+pub fn gear(item: Item, slot: Slot, power: i32, defense: i32, max_hp: i32) -> Entity {
+    let mut gear = Entity::new(0, 0, '/', SKY, &format!("{:?}", item).to_lowercase(), false);
+    gear.item = Some(item);
+    gear.equipment = Some(Equipment {
+        slot,
+        is_equipped: false,
+        power_bonus: power,
+        defense_bonus: defense,
+        max_hp_bonus: max_hp
+    });
+    gear
+}
+
+pub fn is_equipped(item: &Entity) -> bool {
+    item.equipment.is_some_and(|e| e.is_equipped)
+}
+
+// the layout of a maze without the fog of war (it changes with every look around)
+pub fn walls(maze: &Maze) -> Vec<Vec<bool>> {
+    maze.iter().map(|column| column.iter().map(|tile| tile.blocked).collect()).collect()
+}
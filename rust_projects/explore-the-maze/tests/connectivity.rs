@@ -0,0 +1,125 @@
+use rand::Rng;
+use tcod::colors::*;
+
+mod common;
+use common::*;
+
+use explore_the_maze::models::connectivity::*;
+use explore_the_maze::models::definitions::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::generators::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::maze::*;
+use explore_the_maze::models::util::*;
+
+const GENERATORS: [Generator; 4] = [
+    Generator::Rooms,
+    Generator::Bsp,
+    Generator::Caves,
+    Generator::DrunkardsWalk
+];
+
+// enough seeds to hit the rare layouts (e.g. two tunnels meeting only at a corner)
+const SEEDS: u64 = 1000;
+
+#[test]
+fn every_floor_is_one_open_area_without_overlapping_spawns() {
+    let definitions = Definitions::default();
+
+    for generator in GENERATORS {
+        for seed in 0..SEEDS {
+            let mut entities = vec![Entity::new(0, 0, '@', WHITE, "player", true)];
+            let depth = (seed % 5 + 1) as u32;
+            let maze = create_maze(&mut entities, depth, generator, &definitions, &mut rng_from_seed(seed));
+
+            let start = entities[PLAYER].get_pos();
+            let unreachable = unreachable_areas(&maze, start);
+            assert!(unreachable.is_empty(), "{:?} seed {}: {} unreachable areas", generator, seed, unreachable.len());
+
+            let overlapping = overlapping_spawns(&entities);
+            assert!(overlapping.is_empty(), "{:?} seed {}: {:?} overlap", generator, seed, overlapping);
+
+            for entity in &entities {
+                let (x, y) = entity.get_pos();
+                assert!(!maze[x as usize][y as usize].blocked, "{:?} seed {}: {} in a wall", generator, seed, entity.name);
+            }
+        }
+    }
+}
+
+#[test]
+fn repair_connects_random_noise() {
+    // hundreds of tiny areas in every maze
+    for seed in 0..SEEDS / 2 {
+        let mut rng = rng_from_seed(seed);
+        let mut maze = vec![vec![Tile::wall(); MAZE_HEIGHT as usize]; MAZE_WIDTH as usize];
+        for x in 1..MAZE_WIDTH - 1 {
+            for y in 1..MAZE_HEIGHT - 1 {
+                if rng.r#gen::<f32>() < 0.4 {
+                    maze[x as usize][y as usize] = Tile::empty();
+                }
+            }
+        }
+
+        let start = (MAZE_WIDTH / 2, MAZE_HEIGHT / 2);
+        maze[start.0 as usize][start.1 as usize] = Tile::empty();
+        let areas = open_areas(&maze).len();
+
+        let tunnels = repair_connectivity(&mut maze, start);
+        assert!(tunnels < areas, "seed {}: {} tunnels for {} areas", seed, tunnels, areas);
+        assert_eq!(open_areas(&maze).len(), 1, "seed {}", seed);
+        assert!(unreachable_areas(&maze, start).is_empty());
+
+        // the border is never dug
+        for x in 0..MAZE_WIDTH {
+            assert!(maze[x as usize][0].blocked && maze[x as usize][(MAZE_HEIGHT - 1) as usize].blocked);
+        }
+        for y in 0..MAZE_HEIGHT {
+            assert!(maze[0][y as usize].blocked && maze[(MAZE_WIDTH - 1) as usize][y as usize].blocked);
+        }
+    }
+}
+
+#[test]
+fn unreachable_rooms_are_reported_and_joined_by_the_shortest_tunnel() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 10, 10, 14, 12);
+    let maze = &mut simulation.game.maze;
+
+    // a second room 3 walls to the right of the first one
+    for column in &mut maze[18..=20] {
+        for tile in &mut column[10..=12] {
+            *tile = Tile::empty();
+        }
+    }
+
+    let unreachable = unreachable_areas(maze, (10, 10));
+    assert_eq!(unreachable.len(), 1);
+    assert_eq!(unreachable[0].len(), 3 * 3);
+    assert!(unreachable_areas(maze, (19, 11)).iter().all(|area| area.contains(&(10, 10))));
+
+    assert_eq!(repair_connectivity(maze, (10, 10)), 1);
+    assert!(unreachable_areas(maze, (10, 10)).is_empty());
+
+    // only the 3 walls in between are dug
+    let open = maze.iter().flatten().filter(|tile| !tile.blocked).count();
+    assert_eq!(open, 5 * 3 + 3 * 3 + 3);
+
+    // nothing left to repair
+    assert_eq!(repair_connectivity(maze, (10, 10)), 0);
+}
+
+#[test]
+fn overlapping_spawns_are_reported() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 10, 10, 14, 12);
+    simulation.entities.push(orc(11, 10));
+    simulation.entities.push(orc(12, 10));
+    simulation.entities.push(potion(12, 10));
+    assert!(overlapping_spawns(&simulation.entities).is_empty());
+
+    // a monster on another one and on the player, items under them do not count
+    simulation.entities.push(orc(12, 10));
+    simulation.entities.push(orc(10, 10));
+    assert_eq!(overlapping_spawns(&simulation.entities), vec![(0, 5), (2, 4)]);
+}
@@ -0,0 +1,138 @@
+use std::fs;
+
+use explore_the_maze::models::definitions::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::util::*;
+
+// the built in definitions with a change made to the json text
+fn edited(from: &str, to: &str) -> String {
+    let json = fs::read_to_string("data/definitions.json").unwrap();
+    assert!(json.contains(from), "the definitions file has no '{}'", from);
+    json.replacen(from, to, 1)
+}
+
+fn error_of(json: &str) -> String {
+    parse_definitions(json).expect_err("the definitions should be rejected").to_string()
+}
+
+#[test]
+fn built_in_definitions_are_valid() {
+    let definitions = Definitions::default();
+    let json = fs::read_to_string("data/definitions.json").unwrap();
+    assert_eq!(parse_definitions(&json).unwrap(), definitions);
+
+    let orc = definitions.monsters.iter().find(|m| m.name == "orc").unwrap();
+    assert_eq!((orc.max_hp, orc.defense, orc.power, orc.xp), (10, 0, 5, 35));
+    let troll = definitions.monsters.iter().find(|m| m.name == "troll").unwrap();
+    assert_eq!((troll.max_hp, troll.defense, troll.power, troll.xp), (15, 0, 10, 100));
+}
+
+#[test]
+fn missing_file_falls_back_to_the_built_in_definitions() {
+    let definitions = load_definitions("no/such/definitions.json").unwrap();
+    assert_eq!(definitions, Definitions::default());
+}
+
+#[test]
+fn broken_file_names_the_path() {
+    let path = std::env::temp_dir().join(format!("explore-the-maze-{}-definitions.json", std::process::id()));
+    fs::write(&path, edited("\"max_hp\": 15", "\"max_hp\": -15")).unwrap();
+    let err = load_definitions(&path).err().unwrap().to_string();
+    fs::remove_file(&path).unwrap();
+
+    assert!(err.contains(&path.display().to_string()));
+    assert!(err.contains("monsters[1] \"troll\""));
+}
+
+#[test]
+fn errors_point_to_the_offending_entry() {
+    let err = error_of(&edited("\"max_hp\": 15", "\"max_hp\": 0"));
+    assert!(err.contains("monsters[1] \"troll\""), "{}", err);
+    assert!(err.contains("max_hp"), "{}", err);
+
+    let err = error_of(&edited("\"name\": \"troll\"", "\"name\": \"orc\""));
+    assert!(err.contains("monsters[1] \"orc\": name is already defined"), "{}", err);
+
+    let err = error_of(&edited("\"weight_per_depth\": 10,\n            \"min_depth\": 1", "\"weight_per_depth\": 10,\n            \"min_depth\": 0"));
+    assert!(err.contains("monsters[1] \"troll\": min_depth"), "{}", err);
+
+    let err = error_of(&edited("\"max_weight\": 120", "\"max_weight\": 10"));
+    assert!(err.contains("monsters[1] \"troll\": max_weight"), "{}", err);
+
+    let err = error_of(&edited("\"item\": \"Sword\",\n            \"equipment\": { \"slot\": \"RightHand\", \"power_bonus\": 3, \"defense_bonus\": 0, \"max_hp_bonus\": 0 },", "\"item\": \"Sword\","));
+    assert!(err.contains("items[4] \"sword\": equipment is missing"), "{}", err);
+
+    let err = error_of(&edited("\"item\": \"Heal\",", "\"item\": \"Heal\", \"equipment\": { \"slot\": \"Head\", \"power_bonus\": 0, \"defense_bonus\": 0, \"max_hp_bonus\": 1 },"));
+    assert!(err.contains("items[0] \"healing potion\""), "{}", err);
+
+    let err = error_of(&edited("\"item\": \"Dagger\",", "\"item\": \"Dagger\", \"count\": 3,"));
+    assert!(err.contains("items[11] \"dagger\": only arrows can have a count"), "{}", err);
+
+    let err = error_of(&edited("\"range\": 6", "\"range\": 0"));
+    assert!(err.contains("monsters[4] \"archer goblin\": ranged range"), "{}", err);
+}
+
+#[test]
+fn malformed_json_points_to_the_line() {
+    // a typo in a field name and an unknown item kind are reported by serde with the line
+    let err = error_of(&edited("\"power\": 10", "\"powr\": 10"));
+    assert!(err.contains("powr") && err.contains("line"), "{}", err);
+
+    let err = error_of(&edited("\"item\": \"Confuse\"", "\"item\": \"Teleport\""));
+    assert!(err.contains("Teleport") && err.contains("line"), "{}", err);
+}
+
+#[test]
+fn the_first_floor_needs_a_monster() {
+    let json = edited("\"weight\": 80", "\"weight\": 0");
+    let json = json.replacen("\"weight\": 20", "\"weight\": 0", 1);
+    assert!(error_of(&json).contains("depth 1"));
+}
+
+#[test]
+fn monsters_and_items_are_spawned_from_the_definitions() {
+    // only goblins, and a helmet never shows up before depth 5
+    let json = edited("\"name\": \"orc\"", "\"name\": \"goblin\"")
+        .replacen("\"weight\": 20", "\"weight\": 0", 1)
+        .replacen("\"weight_per_depth\": 10", "\"weight_per_depth\": 0", 1)
+        .replacen("\"max_hp_bonus\": 10 },\n            \"weight\": 5,\n            \"min_depth\": 1", "\"max_hp_bonus\": 10 },\n            \"weight\": 5,\n            \"min_depth\": 5", 1);
+    let definitions = parse_definitions(&json).unwrap();
+
+    for seed in 0..20 {
+        let simulation = Simulation::with_options(seed, definitions.clone(), None);
+        for entity in &simulation.entities[1..] {
+            if let Some(fighter) = entity.fighter {
+                assert_eq!(entity.name, "goblin");
+                assert_eq!(fighter.max_hp, 10);
+            }
+            assert_ne!(entity.item, Some(Item::Helmet));
+        }
+    }
+}
+
+#[test]
+fn weights_follow_the_depth() {
+    let definitions = Definitions::default();
+    let troll = definitions.monsters.iter().find(|m| m.name == "troll").unwrap();
+    assert_eq!(troll.weight_at(1), 20);
+    assert_eq!(troll.weight_at(3), 40);
+    assert_eq!(troll.weight_at(11), 120);
+    assert_eq!(troll.weight_at(30), 120);
+
+    let mut late = troll.clone();
+    late.min_depth = 4;
+    assert_eq!(late.weight_at(3), 0);
+    assert_eq!(late.weight_at(4), 20);
+
+    let mut rng = rng_from_seed(1);
+    let weights = [0, 0];
+    assert!(choose_weighted(&weights, |w| *w, &mut rng).is_none());
+
+    // only the entries with a weight are ever picked
+    let weights = [0, 3, 0, 1];
+    for _ in 0..100 {
+        let picked = *choose_weighted(&weights, |w| *w, &mut rng).unwrap();
+        assert!(picked == 3 || picked == 1);
+    }
+}
@@ -0,0 +1,150 @@
+use std::fs;
+
+mod common;
+use common::*;
+
+use explore_the_maze::models::definitions::*;
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::maze::*;
+use explore_the_maze::models::save::*;
+
+fn count_stairs(simulation: &Simulation, stairs: Stairs) -> usize {
+    simulation.entities.iter().filter(|entity| entity.stairs == Some(stairs)).count()
+}
+
+#[test]
+fn first_floor_has_only_stairs_down() {
+    for seed in 0..20 {
+        let simulation = Simulation::new(seed);
+
+        assert_eq!(simulation.game.depth, 1);
+        assert_eq!(count_stairs(&simulation, Stairs::Down), 1);
+        assert_eq!(count_stairs(&simulation, Stairs::Up), 0);
+
+        let stairs = simulation.entities.iter().find(|e| e.stairs.is_some()).unwrap();
+        let (x, y) = stairs.get_pos();
+        assert!(!simulation.game.maze[x as usize][y as usize].blocked);
+        assert!(!stairs.is_blocking);
+        assert!(stairs.always_visible);
+    }
+}
+
+#[test]
+fn descending_needs_the_stairs_under_the_player() {
+    let mut simulation = Simulation::new(4);
+    arena(&mut simulation, 10, 10, 20, 20);
+
+    assert_eq!(simulation.step(Command::Descend), PlayerAction::DidntTakeTurn);
+    assert_eq!(simulation.step(Command::Climb), PlayerAction::DidntTakeTurn);
+    assert_eq!(simulation.game.depth, 1);
+    assert!(simulation.game.floors.is_empty());
+}
+
+#[test]
+fn descending_generates_a_new_floor_with_stairs_back_up() {
+    let mut simulation = Simulation::new(4);
+    let first_maze = simulation.game.maze.clone();
+    simulation.game.inventory.push(potion(0, 0));
+
+    stand_on_stairs(&mut simulation, Stairs::Down);
+    assert_eq!(simulation.step(Command::Descend), PlayerAction::DidntTakeTurn);
+
+    assert_eq!(simulation.game.depth, 2);
+    assert_ne!(simulation.game.maze, first_maze);
+    assert_eq!(simulation.game.floors.len(), 1);
+    assert_eq!(simulation.game.floors[&1].maze, first_maze);
+
+    // the player arrives on the stairs up, with his inventory
+    let up = simulation.entities.iter().find(|e| e.stairs == Some(Stairs::Up)).unwrap();
+    assert_eq!(up.get_pos(), simulation.player().get_pos());
+    assert_eq!(count_stairs(&simulation, Stairs::Down), 1);
+    assert_eq!(simulation.game.inventory.len(), 1);
+
+    // the FOV moved to the new floor
+    let (x, y) = simulation.player().get_pos();
+    assert!(simulation.game.fov.is_in_fov(x, y));
+    assert!(simulation.game.maze[x as usize][y as usize].is_explored);
+}
+
+#[test]
+fn climbing_back_up_restores_the_floor_as_it_was_left() {
+    let mut simulation = Simulation::new(11);
+    stand_on_stairs(&mut simulation, Stairs::Down);
+    let stairs_down = simulation.player().get_pos();
+    let first_maze = simulation.game.maze.clone();
+    let first_entities: Vec<String> = simulation.entities.iter().map(|e| e.name.clone()).collect();
+
+    simulation.step(Command::Descend);
+    let second_maze = simulation.game.maze.clone();
+    simulation.step(Command::Climb);
+
+    // back on the stairs down of the first floor, everything where it was
+    assert_eq!(simulation.game.depth, 1);
+    assert_eq!(simulation.player().get_pos(), stairs_down);
+    assert_eq!(walls(&simulation.game.maze), walls(&first_maze));
+    let entities: Vec<String> = simulation.entities.iter().map(|e| e.name.clone()).collect();
+    assert_eq!(entities, first_entities);
+
+    // the second floor is kept too
+    assert_eq!(simulation.game.floors.len(), 1);
+    assert_eq!(simulation.game.floors[&2].maze, second_maze);
+
+    // and going down again leads to the same floor, not a new one
+    simulation.step(Command::Descend);
+    assert_eq!(simulation.game.depth, 2);
+    assert_eq!(simulation.game.maze, second_maze);
+    assert!(simulation.game.floors.contains_key(&1));
+}
+
+#[test]
+fn deeper_floors_are_harder() {
+    assert_eq!(max_monsters_in_room(1), 3);
+    assert!(max_monsters_in_room(5) > max_monsters_in_room(1));
+    assert!(max_monsters_in_room(10) > max_monsters_in_room(5));
+
+    // more trolls among the monsters deeper down
+    let definitions = Definitions::default();
+    let troll_chance = |depth: u32| {
+        let weight = |name: &str| {
+            definitions.monsters.iter().find(|m| m.name == name).unwrap().weight_at(depth) as f32
+        };
+        weight("troll") / (weight("troll") + weight("orc"))
+    };
+    assert!((troll_chance(1) - 0.2).abs() < f32::EPSILON);
+    assert!(troll_chance(3) > troll_chance(1));
+    assert!(troll_chance(20) <= 0.6);
+
+    // by the seventh floor a troll is as likely as an orc
+    assert!(troll_chance(7) >= 0.5);
+}
+
+#[test]
+fn saved_dungeon_keeps_every_visited_floor() {
+    let path = std::env::temp_dir().join(format!("explore-the-maze-{}-dungeon", std::process::id()));
+    let mut simulation = Simulation::new(21);
+    stand_on_stairs(&mut simulation, Stairs::Down);
+    simulation.step(Command::Descend);
+
+    save_game(&path, &simulation.game, &simulation.entities).unwrap();
+    let (game, entities) = load_game(&path, Definitions::default()).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(game.depth, 2);
+    assert_eq!(game.floors, simulation.game.floors);
+    assert_eq!(entities, simulation.entities);
+
+    // the player can still climb back up in the loaded game
+    let mut loaded = Simulation { game, entities, turns: 0 };
+    loaded.step(Command::Climb);
+    assert_eq!(loaded.game.depth, 1);
+    let down = loaded.entities.iter().find(|e| e.stairs == Some(Stairs::Down)).unwrap();
+    assert_eq!(down.get_pos(), loaded.player().get_pos());
+}
+
+#[test]
+fn stairs_commands_parse() {
+    assert_eq!("descend".parse(), Ok(Command::Descend));
+    assert_eq!("climb".parse(), Ok(Command::Climb));
+}
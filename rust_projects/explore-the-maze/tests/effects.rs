@@ -0,0 +1,131 @@
+mod common;
+use common::*;
+
+use explore_the_maze::models::definitions::*;
+use explore_the_maze::models::effects::*;
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::maze::*;
+
+#[test]
+fn poison_hurts_every_turn_until_it_wears_off() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    apply_effect(&mut simulation.entities[PLAYER], StatusEffect::new(Effect::Poison, 3, 2), &mut simulation.game);
+    assert!(has_effect(simulation.player(), Effect::Poison));
+
+    simulation.run(vec![Command::Wait; 3]);
+    assert_eq!(hp(simulation.player()), 30 - 3 * 2);
+    assert!(simulation.player().effects.is_empty());
+    assert!(simulation.game.messages.messages.iter().any(|m| m.text == "You are no longer poisoned."));
+
+    // nothing more once it is gone
+    simulation.step(Command::Wait);
+    assert_eq!(hp(simulation.player()), 24);
+}
+
+#[test]
+fn the_same_effect_does_not_stack() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    apply_effect(&mut simulation.entities[PLAYER], StatusEffect::new(Effect::Poison, 3, 1), &mut simulation.game);
+    apply_effect(&mut simulation.entities[PLAYER], StatusEffect::new(Effect::Poison, 5, 2), &mut simulation.game);
+
+    assert_eq!(simulation.player().effects, vec![StatusEffect::new(Effect::Poison, 5, 2)]);
+}
+
+#[test]
+fn potions_put_effects_on_the_player() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities[PLAYER].fighter.as_mut().unwrap().hp = 15;
+    simulation.game.inventory.push(scroll(Item::Regenerate));
+    simulation.game.inventory.push(scroll(Item::Haste));
+
+    // regeneration heals at the end of the turn it is drunk
+    assert_eq!(simulation.step(Command::UseItem(0, None)), PlayerAction::TookTurn);
+    assert_eq!(hp(simulation.player()), 15 + REGENERATION_AMOUNT);
+    simulation.step(Command::UseItem(0, None));
+
+    assert!(simulation.game.inventory.is_empty());
+    assert!(has_effect(simulation.player(), Effect::Regeneration));
+    assert!(has_effect(simulation.player(), Effect::Haste));
+
+    // never over the max hp - it wears off with the turns of the game, the hasted player waits twice a turn
+    simulation.run(vec![Command::Wait; 2 * REGENERATION_TURNS as usize]);
+    assert_eq!(hp(simulation.player()), 30);
+    assert!(!has_effect(simulation.player(), Effect::Regeneration));
+}
+
+#[test]
+fn a_hasted_player_acts_twice_for_every_monster_turn() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities[PLAYER].set_pos(10, 15);
+    simulation.entities.push(orc(18, 15));
+    apply_effect(&mut simulation.entities[PLAYER], StatusEffect::new(Effect::Haste, HASTE_TURNS, 0), &mut simulation.game);
+
+    simulation.run(vec![Command::Wait; 4]);
+    assert_eq!(simulation.entities[1].get_pos(), (16, 15));
+
+    // a hasted monster catches up twice as fast
+    simulation.entities[PLAYER].effects.clear();
+    apply_effect(&mut simulation.entities[1], StatusEffect::new(Effect::Haste, HASTE_TURNS, 0), &mut simulation.game);
+    simulation.run(vec![Command::Wait; 2]);
+    assert_eq!(simulation.entities[1].get_pos(), (12, 15));
+}
+
+#[test]
+fn a_spider_bite_poisons_and_the_poison_kills_for_the_player() {
+    let definitions = Definitions::default();
+    let spider = definitions.monsters.iter().find(|m| m.name == "giant spider").unwrap();
+    assert_eq!(spider.on_hit.map(|status| status.effect), Some(Effect::Poison));
+
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities.push(spider.spawn(11, 10));
+    steady(&mut simulation.entities[1]);
+
+    // 4 power - 2 defense, then the poison in the same turn
+    simulation.step(Command::Wait);
+    assert!(has_effect(simulation.player(), Effect::Poison));
+    assert_eq!(hp(simulation.player()), 30 - 2 - 1);
+
+    // a poisoned monster dies on its own, its xp still goes to the player
+    let mut orc = orc(20, 20);
+    orc.fighter.as_mut().unwrap().hp = 1;
+    simulation.entities.push(orc);
+    apply_effect(&mut simulation.entities[2], StatusEffect::new(Effect::Poison, 5, 1), &mut simulation.game);
+    simulation.entities[1].ai = None;
+    simulation.step(Command::Wait);
+
+    assert!(!simulation.entities[2].is_alive);
+    assert!(simulation.entities[2].effects.is_empty());
+    assert_eq!(xp(simulation.player()), 35);
+}
+
+#[test]
+fn a_confused_player_stumbles_around() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities[PLAYER].set_pos(20, 15);
+    apply_effect(&mut simulation.entities[PLAYER], StatusEffect::new(Effect::Confusion, 20, 0), &mut simulation.game);
+
+    // walking right 20 times in a straight line never ends up 20 tiles right
+    let mut positions = vec![];
+    for _ in 0..20 {
+        simulation.step(Command::Move(1, 0));
+        positions.push(simulation.player().get_pos());
+    }
+    assert!(positions.iter().any(|&(_, y)| y != 15));
+    assert!(!has_effect(simulation.player(), Effect::Confusion));
+}
+
+#[test]
+fn invalid_on_hit_effects_are_rejected() {
+    let json = std::fs::read_to_string(DEFINITIONS_FILE).unwrap();
+    let broken = json.replacen("\"turns\": 5", "\"turns\": 0", 1);
+    let err = parse_definitions(&broken).expect_err("the definitions should be rejected").to_string();
+    assert!(err.contains("monsters[2] \"giant spider\": on_hit turns"), "{}", err);
+}
@@ -0,0 +1,116 @@
+mod common;
+use common::*;
+
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::equipment::*;
+use explore_the_maze::models::headless::*;
+
+#[test]
+fn using_equipment_toggles_it_and_takes_a_turn() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.game.inventory.push(gear(Item::Sword, Slot::RightHand, 3, 0, 0));
+
+    assert_eq!(simulation.step(Command::UseItem(0, None)), PlayerAction::TookTurn);
+    assert!(is_equipped(&simulation.game.inventory[0]));
+    assert_eq!(simulation.player().power(&simulation.game), 5 + 3);
+
+    // the item stays in the inventory while equipped and after unequipping it
+    assert_eq!(simulation.step(Command::UseItem(0, None)), PlayerAction::TookTurn);
+    assert_eq!(simulation.game.inventory.len(), 1);
+    assert!(!is_equipped(&simulation.game.inventory[0]));
+    assert_eq!(simulation.player().power(&simulation.game), 5);
+}
+
+#[test]
+fn one_item_per_slot() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.game.inventory.push(gear(Item::Sword, Slot::RightHand, 3, 0, 0));
+    simulation.game.inventory.push(gear(Item::Sword, Slot::RightHand, 5, 0, 0));
+    simulation.game.inventory.push(gear(Item::Shield, Slot::LeftHand, 0, 1, 0));
+
+    simulation.step(Command::UseItem(0, None));
+    simulation.step(Command::UseItem(2, None));
+    simulation.step(Command::UseItem(1, None));
+
+    // the second sword replaced the first one, the shield is still on
+    assert!(!is_equipped(&simulation.game.inventory[0]));
+    assert!(is_equipped(&simulation.game.inventory[1]));
+    assert!(is_equipped(&simulation.game.inventory[2]));
+    assert_eq!(get_equipped_in_slot(Slot::RightHand, &simulation.game.inventory), Some(1));
+    assert_eq!(get_equipped_in_slot(Slot::Head, &simulation.game.inventory), None);
+
+    assert_eq!(simulation.player().power(&simulation.game), 5 + 5);
+    assert_eq!(simulation.player().defense(&simulation.game), 2 + 1);
+}
+
+#[test]
+fn equipment_bonuses_count_in_combat() {
+    let mut simulation = Simulation::new(3);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.game.inventory.push(gear(Item::Sword, Slot::RightHand, 5, 0, 0));
+    simulation.game.inventory.push(gear(Item::Shield, Slot::LeftHand, 0, 3, 0));
+    simulation.step(Command::UseItem(0, None));
+    simulation.step(Command::UseItem(1, None));
+    simulation.entities.push(orc(11, 10));
+
+    // 10 power kills the orc with a single hit
+    simulation.step(Command::Move(1, 0));
+    assert!(!simulation.entities[1].is_alive);
+
+    // 5 power - 5 defense => the orc does no damage
+    simulation.entities.push(orc(10, 11));
+    simulation.step(Command::Move(-1, 0));
+    assert_eq!(hp(simulation.player()), 30);
+}
+
+#[test]
+fn max_hp_bonus_heals_further_and_is_cut_on_unequip() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.game.inventory.push(gear(Item::Helmet, Slot::Head, 0, 0, 10));
+    simulation.game.inventory.push(potion(0, 0));
+    simulation.game.inventory.push(potion(0, 0));
+    simulation.game.inventory.push(potion(0, 0));
+
+    simulation.step(Command::UseItem(0, None));
+    assert_eq!(simulation.player().max_hp(&simulation.game), 40);
+
+    // at base max hp, but the helmet lets the potions heal over it
+    simulation.step(Command::UseItem(1, None));
+    simulation.step(Command::UseItem(1, None));
+    assert_eq!(hp(simulation.player()), 30 + 2 * 4);
+
+    // without the helmet the hp is back within the base max hp
+    simulation.step(Command::UseItem(0, None));
+    assert_eq!(hp(simulation.player()), 30);
+    assert_eq!(simulation.step(Command::UseItem(1, None)), PlayerAction::DidntTakeTurn);
+}
+
+#[test]
+fn monsters_fight_with_their_base_stats() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.game.inventory.push(gear(Item::Sword, Slot::RightHand, 3, 0, 0));
+    simulation.step(Command::UseItem(0, None));
+    simulation.entities.push(orc(15, 15));
+
+    assert_eq!(simulation.entities[1].power(&simulation.game), 5);
+    assert_eq!(simulation.entities[1].defense(&simulation.game), 0);
+}
+
+#[test]
+fn generated_equipment_is_not_equipped() {
+    let mut found = 0;
+    for seed in 0..50 {
+        let simulation = Simulation::new(seed);
+        for item in simulation.entities.iter().filter(|e| e.equipment.is_some()) {
+            assert!(!is_equipped(item));
+            assert!(matches!(item.item, Some(Item::Sword | Item::Shield | Item::Helmet)));
+            found += 1;
+        }
+    }
+    assert!(found > 0);
+}
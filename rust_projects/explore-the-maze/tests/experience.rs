@@ -0,0 +1,107 @@
+mod common;
+use common::*;
+
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::experience::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::maze::*;
+
+#[test]
+fn killing_a_monster_gives_its_xp() {
+    let mut simulation = Simulation::new(3);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.entities.push(orc(11, 10));
+
+    simulation.run(vec![Command::Move(1, 0); 2]);
+
+    assert!(!simulation.entities[1].is_alive);
+    assert_eq!(xp(simulation.player()), 35);
+    let message = &simulation.game.messages.messages.last().unwrap().text;
+    assert!(message.contains("35 experience points"));
+}
+
+#[test]
+fn spells_give_the_xp_of_their_victims() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities.push(orc(16, 12));
+    simulation.entities.push(orc(17, 12));
+    simulation.entities.push(orc(12, 10));
+    simulation.game.inventory.push(scroll(Item::Fireball));
+    simulation.game.inventory.push(scroll(Item::Lightning));
+
+    simulation.step(Command::UseItem(0, Some((16, 12))));
+    assert_eq!(xp(simulation.player()), 70);
+
+    simulation.step(Command::UseItem(0, None));
+    assert_eq!(xp(simulation.player()), 105);
+}
+
+#[test]
+fn thresholds_grow_with_every_level() {
+    assert_eq!(level_up_xp(1), LEVEL_UP_BASE + LEVEL_UP_FACTOR);
+    assert!(level_up_xp(2) > level_up_xp(1));
+    assert_eq!(level_up_xp(3) - level_up_xp(2), LEVEL_UP_FACTOR);
+}
+
+#[test]
+fn level_up_raises_the_chosen_stat() {
+    let mut simulation = Simulation::new(3);
+    arena(&mut simulation, 10, 10, 20, 20);
+
+    // not enough xp yet
+    assert!(!can_level_up(simulation.player()));
+    assert_eq!(simulation.step(Command::LevelUp(Stat::Power)), PlayerAction::DidntTakeTurn);
+    assert_eq!(simulation.player().level, 1);
+
+    // enough for two levels, with some xp left over
+    let needed = level_up_xp(1) + level_up_xp(2);
+    simulation.entities[PLAYER].fighter.as_mut().unwrap().xp = needed + 10;
+    simulation.entities[PLAYER].fighter.as_mut().unwrap().hp = 25;
+
+    simulation.step(Command::LevelUp(Stat::MaxHp));
+    let fighter = simulation.player().fighter.unwrap();
+    assert_eq!(simulation.player().level, 2);
+    assert_eq!(fighter.max_hp, 30 + LEVEL_UP_HP);
+    assert_eq!(fighter.hp, 25 + LEVEL_UP_HP);
+    assert!(can_level_up(simulation.player()));
+
+    simulation.step(Command::LevelUp(Stat::Defense));
+    let fighter = simulation.player().fighter.unwrap();
+    assert_eq!(simulation.player().level, 3);
+    assert_eq!(fighter.defense, 2 + LEVEL_UP_DEFENSE);
+    assert_eq!(fighter.power, 5);
+    assert_eq!(fighter.xp, 10);
+    assert!(!can_level_up(simulation.player()));
+
+    // leveling up does not give the monsters a turn
+    assert_eq!(simulation.turns, 0);
+}
+
+#[test]
+fn player_is_told_once_to_level_up() {
+    let mut simulation = Simulation::new(3);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.entities[PLAYER].fighter.as_mut().unwrap().xp = level_up_xp(1) - 35;
+    simulation.entities.push(orc(11, 10));
+
+    simulation.run(vec![Command::Move(1, 0); 4]);
+
+    let told = simulation
+        .game
+        .messages
+        .messages
+        .iter()
+        .filter(|message| message.text.contains("Time to level up"))
+        .count();
+    assert_eq!(told, 1);
+}
+
+#[test]
+fn level_up_commands_parse() {
+    assert_eq!("levelup hp".parse(), Ok(Command::LevelUp(Stat::MaxHp)));
+    assert_eq!("levelup power".parse(), Ok(Command::LevelUp(Stat::Power)));
+    assert_eq!("levelup defense".parse(), Ok(Command::LevelUp(Stat::Defense)));
+    assert!("levelup luck".parse::<Command>().is_err());
+}
@@ -0,0 +1,204 @@
+mod common;
+use common::*;
+
+use explore_the_maze::models::awareness::*;
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::features::*;
+use explore_the_maze::models::generators::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::look::*;
+use explore_the_maze::models::maze::*;
+use explore_the_maze::models::util::*;
+
+fn last_message(simulation: &Simulation) -> &str {
+    &simulation.game.messages.messages.last().unwrap().text
+}
+
+fn feature(simulation: &Simulation, x: i32, y: i32) -> Option<Feature> {
+    simulation.game.maze[x as usize][y as usize].feature
+}
+
+// the player at (14, 15) in an empty room 10..=20 x 10..=20 cut in two by a wall at x = 15 with a closed door in the middle
+fn doorway() -> Simulation {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 20, 20);
+    for y in 10..=20 {
+        simulation.game.maze[15][y] = Tile::wall();
+    }
+    simulation.game.maze[15][15] = Tile::door();
+    simulation.entities[PLAYER].set_pos(14, 15);
+    compute_fov(&mut simulation.game, &simulation.entities);
+    simulation
+}
+
+#[test]
+fn doors_are_opened_by_walking_into_them_and_closed_again() {
+    let mut simulation = doorway();
+    assert!(!simulation.game.fov.is_in_fov(17, 15));
+
+    // opening takes the turn, the player stays where he is but sees through
+    assert_eq!(simulation.step(Command::Move(1, 0)), PlayerAction::TookTurn);
+    assert_eq!(simulation.player().get_pos(), (14, 15));
+    assert_eq!(feature(&simulation, 15, 15), Some(Feature::Door { is_open: true }));
+    assert_eq!(last_message(&simulation), "You open the door.");
+    assert!(simulation.game.fov.is_in_fov(17, 15));
+
+    simulation.step(Command::Move(1, 0));
+    assert_eq!(simulation.player().get_pos(), (15, 15));
+
+    // not while standing in it
+    assert_eq!(simulation.step(Command::Close), PlayerAction::DidntTakeTurn);
+    assert_eq!(last_message(&simulation), "There is no open door next to you to close.");
+
+    simulation.step(Command::Move(1, 0));
+    assert_eq!(simulation.step(Command::Close), PlayerAction::TookTurn);
+    assert_eq!(feature(&simulation, 15, 15), Some(Feature::Door { is_open: false }));
+    assert!(!simulation.game.fov.is_in_fov(13, 15));
+    assert_eq!(describe_tile(15, 15, &simulation.game, &simulation.entities).unwrap(), "closed door");
+}
+
+#[test]
+fn monsters_open_doors_on_their_way() {
+    let mut simulation = doorway();
+    let mut orc = orc(16, 15);
+    orc.awareness = Awareness::Searching { last_seen: (14, 15), turns: SEARCH_TURNS };
+    simulation.entities.push(orc);
+
+    simulation.step(Command::Wait);
+    assert_eq!(feature(&simulation, 15, 15), Some(Feature::Door { is_open: true }));
+    assert_eq!(simulation.entities[1].get_pos(), (16, 15));
+    assert!(simulation.game.fov.is_in_fov(16, 15));
+}
+
+#[test]
+fn searching_finds_the_traps_around() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.game.maze[12][10] = Tile::trap(Trap::Spike);
+    simulation.game.maze[10][(10 + SEARCH_RADIUS + 1) as usize] = Tile::trap(Trap::Alarm);
+    compute_fov(&mut simulation.game, &simulation.entities);
+    assert_eq!(describe_tile(12, 10, &simulation.game, &simulation.entities).unwrap(), "floor");
+
+    for _ in 0..20 {
+        assert_eq!(simulation.step(Command::Search), PlayerAction::TookTurn);
+        if feature(&simulation, 12, 10) == Some(Feature::Trap { trap: Trap::Spike, is_found: true }) {
+            break;
+        }
+    }
+
+    assert_eq!(feature(&simulation, 12, 10), Some(Feature::Trap { trap: Trap::Spike, is_found: true }));
+    assert_eq!(last_message(&simulation), "You find a spike trap!");
+    assert_eq!(describe_tile(12, 10, &simulation.game, &simulation.entities).unwrap(), "spike trap");
+
+    // too far away to be found
+    assert_eq!(feature(&simulation, 10, 13), Some(Feature::Trap { trap: Trap::Alarm, is_found: false }));
+}
+
+#[test]
+fn a_hidden_trap_springs_once() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.game.maze[11][10] = Tile::trap(Trap::Spike);
+
+    simulation.step(Command::Move(1, 0));
+    assert_eq!(hp(simulation.player()), 30 - SPIKE_DAMAGE);
+    assert_eq!(last_message(&simulation), "You step on a spike trap! The spikes pierce you for 6 hit points.");
+
+    // a found one is stepped over carefully
+    simulation.run(vec![Command::Move(-1, 0), Command::Move(1, 0)]);
+    assert_eq!(hp(simulation.player()), 30 - SPIKE_DAMAGE);
+}
+
+#[test]
+fn teleport_and_alarm_traps() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 60, 20);
+    simulation.game.maze[11][10] = Tile::trap(Trap::Teleport);
+    simulation.step(Command::Move(1, 0));
+    assert_ne!(simulation.player().get_pos(), (11, 10));
+    assert!(simulation.game.fov.is_in_fov(simulation.player().x, simulation.player().y));
+
+    // nowhere to land - the player stays on the trap
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 11, 10);
+    simulation.game.maze[10][10] = Tile::trap(Trap::Alarm);
+    simulation.game.maze[11][10] = Tile::trap(Trap::Teleport);
+    simulation.step(Command::Move(1, 0));
+    assert_eq!(simulation.player().get_pos(), (11, 10));
+
+    // the bell wakes up a monster far away
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 60, 20);
+    simulation.game.maze[11][10] = Tile::trap(Trap::Alarm);
+    let mut orc = orc(35, 20);
+    orc.awareness = Awareness::Asleep;
+    simulation.entities.push(orc);
+
+    simulation.step(Command::Move(1, 0));
+    assert!(matches!(simulation.entities[1].awareness, Awareness::Searching { .. } | Awareness::Hunting { .. }));
+}
+
+#[test]
+fn cracked_walls_crumble_under_the_blows() {
+    let mut simulation = doorway();
+    simulation.game.maze[15][15] = Tile::cracked_wall();
+
+    // 5 power against 10 hit points
+    simulation.step(Command::Move(1, 0));
+    assert_eq!(last_message(&simulation), "You hit the cracked wall.");
+    assert_eq!(feature(&simulation, 15, 15), Some(Feature::CrackedWall { hp: CRACKED_WALL_HP - 5 }));
+
+    simulation.step(Command::Move(1, 0));
+    assert_eq!(last_message(&simulation), "The cracked wall crumbles to rubble!");
+    assert_eq!(simulation.game.maze[15][15], Tile { is_explored: true, ..Tile::empty() });
+
+    simulation.step(Command::Move(1, 0));
+    assert_eq!(simulation.player().get_pos(), (15, 15));
+}
+
+#[test]
+fn rooms_and_tunnels_get_doors_traps_and_cracked_walls() {
+    let mut features = vec![];
+    for generator in [Generator::Rooms, Generator::Bsp] {
+        for seed in 0..5 {
+            let layout = generator.generate(&mut rng_from_seed(seed));
+            let maze = &layout.maze;
+
+            // nothing on the tiles the monsters, items and stairs spawn on
+            for region in &layout.regions {
+                assert!(region.tiles.iter().all(|&(x, y)| maze[x as usize][y as usize].feature.is_none()));
+            }
+
+            for x in 0..MAZE_WIDTH as usize {
+                for y in 0..MAZE_HEIGHT as usize {
+                    let Some(feature) = maze[x][y].feature else { continue };
+                    features.push(feature);
+
+                    // a door stands between two walls, a cracked wall between two open tiles
+                    let (left, right) = (maze[x - 1][y].blocked, maze[x + 1][y].blocked);
+                    let (up, down) = (maze[x][y - 1].blocked, maze[x][y + 1].blocked);
+                    match feature {
+                        Feature::Door { .. } => assert!((left && right) || (up && down)),
+                        Feature::CrackedWall { .. } => assert!((!left && !right) || (!up && !down)),
+                        Feature::Trap { is_found, .. } => assert!(!is_found)
+                    }
+                }
+            }
+        }
+    }
+
+    assert!(features.iter().any(|f| matches!(f, Feature::Door { is_open: false })));
+    assert!(features.iter().any(|f| matches!(f, Feature::Trap { .. })));
+    assert!(features.iter().any(|f| matches!(f, Feature::CrackedWall { .. })));
+
+    // the caves have no rooms and tunnels
+    let caves = Generator::Caves.generate(&mut rng_from_seed(1));
+    assert!(caves.maze.iter().flatten().all(|tile| tile.feature.is_none()));
+}
+
+#[test]
+fn search_and_close_are_script_commands() {
+    assert_eq!("search".parse(), Ok(Command::Search));
+    assert_eq!(Command::Close.to_string(), "close");
+}
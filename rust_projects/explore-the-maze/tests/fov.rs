@@ -0,0 +1,124 @@
+mod common;
+use common::*;
+
+use explore_the_maze::models::awareness::*;
+use explore_the_maze::models::definitions::*;
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::fov::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::maze::*;
+
+const ALGORITHMS: [FovAlgorithm; 3] = [FovAlgorithm::Shadowcasting, FovAlgorithm::Symmetric, FovAlgorithm::Permissive];
+
+// an empty room 10..=30 x 10..=20 with a wall in the middle of it at (20, 15)
+fn room_with_a_pillar() -> Maze {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.game.maze[20][15] = Tile::wall();
+    simulation.game.maze
+}
+
+#[test]
+fn walls_cast_shadows_and_are_lit() {
+    let maze = room_with_a_pillar();
+
+    for algorithm in ALGORITHMS {
+        let fov = field_of_view(&maze, 15, 15, TORCH_RADIUS, algorithm);
+        assert!(fov.is_in_fov(15, 15), "{}", algorithm);
+        assert!(fov.is_in_fov(19, 15) && fov.is_in_fov(20, 15), "{}", algorithm);
+        assert!(!fov.is_in_fov(22, 15), "{}", algorithm);
+
+        // the walls of the room are seen, nothing behind them
+        assert!(fov.is_in_fov(15, 9) && !fov.is_in_fov(15, 8), "{}", algorithm);
+        assert!(fov.is_in_fov(18, 12), "{}", algorithm);
+    }
+}
+
+#[test]
+fn the_radius_limits_the_sight() {
+    let maze = room_with_a_pillar();
+
+    for algorithm in ALGORITHMS {
+        let fov = field_of_view(&maze, 12, 12, 4, algorithm);
+        assert!(fov.is_in_fov(16, 12) && !fov.is_in_fov(17, 12), "{}", algorithm);
+        assert!(fov.is_in_fov(14, 14) && !fov.is_in_fov(15, 15), "{}", algorithm);
+        assert!(!fov.is_in_fov(-1, 12) && !fov.is_in_fov(12, MAZE_HEIGHT), "{}", algorithm);
+    }
+}
+
+#[test]
+fn symmetric_shadowcasting_sees_both_ways() {
+    let simulation = Simulation::new(7);
+    let maze = &simulation.game.maze;
+    let (x, y) = simulation.player().get_pos();
+
+    let fov = field_of_view(maze, x, y, TORCH_RADIUS, FovAlgorithm::Symmetric);
+    for tx in 0..MAZE_WIDTH {
+        for ty in 0..MAZE_HEIGHT {
+            if maze[tx as usize][ty as usize].block_sight {
+                continue;
+            }
+            let sees_back = field_of_view(maze, tx, ty, TORCH_RADIUS, FovAlgorithm::Symmetric).is_in_fov(x, y);
+            assert_eq!(fov.is_in_fov(tx, ty), sees_back, "({}, {})", tx, ty);
+        }
+    }
+}
+
+#[test]
+fn permissive_sees_around_a_pillar() {
+    // right next to the pillar (diagonally) - only a line from the edge of the eye tile gets past it
+    let maze = room_with_a_pillar();
+
+    assert!(!field_of_view(&maze, 18, 14, TORCH_RADIUS, FovAlgorithm::Shadowcasting).is_in_fov(23, 16));
+    assert!(!field_of_view(&maze, 18, 14, TORCH_RADIUS, FovAlgorithm::Symmetric).is_in_fov(23, 16));
+    assert!(field_of_view(&maze, 18, 14, TORCH_RADIUS, FovAlgorithm::Permissive).is_in_fov(23, 16));
+}
+
+#[test]
+fn the_algorithm_is_switched_with_a_command() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    assert_eq!(simulation.game.fov_algorithm, FOV_ALGO);
+
+    assert_eq!("fov permissive".parse::<Command>(), Ok(Command::Fov(FovAlgorithm::Permissive)));
+    assert!("fov basic".parse::<Command>().unwrap_err().contains("unknown fov algorithm 'basic'"));
+
+    // free, like leveling up
+    assert_eq!(simulation.step(Command::Fov(FovAlgorithm::Permissive)), PlayerAction::DidntTakeTurn);
+    assert_eq!(simulation.game.fov_algorithm, FovAlgorithm::Permissive);
+    assert_eq!(simulation.game.turn, 1);
+    assert_eq!(simulation.game.messages.messages.last().unwrap().text, "You now see with permissive field of view.");
+    assert_eq!(FovAlgorithm::Permissive.next(), FovAlgorithm::Shadowcasting);
+}
+
+#[test]
+fn monsters_see_as_far_as_their_sight() {
+    let definitions = Definitions::default();
+    let spider = definitions.monsters.iter().find(|m| m.name == "giant spider").unwrap();
+    assert_eq!(spider.sight, 6);
+    assert!(definitions.monsters.iter().find(|m| m.name == "orc").is_some_and(|orc| orc.sight == TORCH_RADIUS));
+
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities[PLAYER].set_pos(10, 15);
+    simulation.entities.push(spider.spawn(18, 15));
+    simulation.entities.push(orc(10, 10));
+    // the spider looks around where it stands (awareness.rs)
+    simulation.entities[1].awareness = Awareness::Searching { last_seen: (18, 15), turns: 10 };
+    simulation.entities[2].set_pos(10, 20);
+    compute_fov(&mut simulation.game, &simulation.entities);
+
+    // the player sees the spider, the spider does not see him yet - the orc does
+    assert!(simulation.game.fov.is_in_fov(18, 15));
+    simulation.step(Command::Wait);
+    assert_eq!(simulation.entities[1].get_pos(), (18, 15));
+    assert_eq!(simulation.entities[2].get_pos(), (10, 19));
+    assert!(matches!(simulation.entities[1].awareness, Awareness::Searching { .. }));
+    assert_eq!(simulation.entities[2].awareness, Awareness::Hunting { last_seen: (10, 15) });
+
+    // close enough
+    simulation.step(Command::Move(1, 0));
+    simulation.step(Command::Move(1, 0));
+    assert_eq!(simulation.entities[1].get_pos(), (17, 15));
+}
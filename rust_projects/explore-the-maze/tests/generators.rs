@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+
+mod common;
+use common::*;
+
+use explore_the_maze::models::connectivity::*;
+use explore_the_maze::models::definitions::*;
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::generators::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::maze::*;
+use explore_the_maze::models::util::*;
+
+const GENERATORS: [Generator; 4] = [
+    Generator::Rooms,
+    Generator::Bsp,
+    Generator::Caves,
+    Generator::DrunkardsWalk
+];
+
+#[test]
+fn every_generator_keeps_the_border_and_spawns_on_open_tiles() {
+    for generator in GENERATORS {
+        for seed in 0..30 {
+            let Layout { maze, regions } = generator.generate(&mut rng_from_seed(seed));
+
+            assert_eq!(maze.len(), MAZE_WIDTH as usize);
+            assert!(maze.iter().all(|column| column.len() == MAZE_HEIGHT as usize));
+
+            // the border is never dug - moving never leaves the maze
+            for x in 0..MAZE_WIDTH {
+                assert!(maze[x as usize][0].blocked && maze[x as usize][(MAZE_HEIGHT - 1) as usize].blocked);
+            }
+            for y in 0..MAZE_HEIGHT {
+                assert!(maze[0][y as usize].blocked && maze[(MAZE_WIDTH - 1) as usize][y as usize].blocked);
+            }
+
+            assert!(!regions.is_empty(), "{:?} seed {} has no regions", generator, seed);
+            for region in &regions {
+                assert!(region.tiles.contains(&region.center));
+                for &(x, y) in &region.tiles {
+                    assert!(!maze[x as usize][y as usize].blocked);
+                }
+            }
+
+            // regions never share tiles
+            let tiles: usize = regions.iter().map(|region| region.tiles.len()).sum();
+            let unique: HashSet<_> = regions.iter().flat_map(|region| region.tiles.iter()).collect();
+            assert_eq!(tiles, unique.len());
+        }
+    }
+}
+
+#[test]
+fn generators_are_reproducible_and_different() {
+    for generator in GENERATORS {
+        let first = generator.generate(&mut rng_from_seed(7));
+        let second = generator.generate(&mut rng_from_seed(7));
+        assert_eq!(first, second);
+    }
+
+    let layouts: Vec<Maze> = GENERATORS
+        .iter()
+        .map(|generator| generator.generate(&mut rng_from_seed(7)).maze)
+        .collect();
+    for i in 0..layouts.len() {
+        for j in i + 1..layouts.len() {
+            assert_ne!(layouts[i], layouts[j]);
+        }
+    }
+}
+
+#[test]
+fn caves_and_tunnels_are_one_open_area() {
+    for generator in [Generator::Caves, Generator::DrunkardsWalk] {
+        for seed in 0..30 {
+            let Layout { maze, regions } = generator.generate(&mut rng_from_seed(seed));
+
+            assert_eq!(open_areas(&maze).len(), 1, "{:?} seed {}", generator, seed);
+            assert!(regions.len() >= 2);
+        }
+    }
+}
+
+#[test]
+fn flood_fill_stays_inside_the_walls() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 10, 10, 14, 12);
+
+    let reached = flood_fill(&simulation.game.maze, (10, 10));
+    assert_eq!(reached.len(), 5 * 3);
+    assert!(flood_fill(&simulation.game.maze, (0, 0)).is_empty());
+}
+
+#[test]
+fn generator_changes_with_the_depth_unless_chosen() {
+    assert_eq!(Generator::for_depth(1), Generator::Rooms);
+    assert_eq!(Generator::for_depth(2), Generator::Bsp);
+    assert_eq!(Generator::for_depth(3), Generator::Caves);
+    assert_eq!(Generator::for_depth(4), Generator::DrunkardsWalk);
+    assert_eq!(Generator::for_depth(5), Generator::Rooms);
+
+    // a chosen generator lays out every floor
+    let mut simulation = Simulation::with_options(3, Definitions::default(), Some(Generator::Caves));
+    let expected = Generator::Caves.generate(&mut rng_from_seed(3)).maze;
+    assert_eq!(walls(&simulation.game.maze), walls(&expected));
+
+    stand_on_stairs(&mut simulation, Stairs::Down);
+    simulation.step(Command::Descend);
+    assert_eq!(open_areas(&simulation.game.maze).len(), 1);
+    let (x, y) = simulation.player().get_pos();
+    assert!(!simulation.game.maze[x as usize][y as usize].blocked);
+}
+
+#[test]
+fn the_player_and_the_stairs_are_placed_by_every_generator() {
+    for generator in GENERATORS {
+        for seed in 0..10 {
+            let simulation = Simulation::with_options(seed, Definitions::default(), Some(generator));
+            let (x, y) = simulation.player().get_pos();
+            assert!(!simulation.game.maze[x as usize][y as usize].blocked);
+
+            let stairs = simulation.entities.iter().find(|e| e.stairs == Some(Stairs::Down)).unwrap();
+            let (x, y) = stairs.get_pos();
+            assert!(!simulation.game.maze[x as usize][y as usize].blocked);
+
+            for entity in &simulation.entities {
+                let (x, y) = entity.get_pos();
+                assert!(!simulation.game.maze[x as usize][y as usize].blocked);
+            }
+        }
+    }
+}
+
+#[test]
+fn generator_names_parse() {
+    assert_eq!("rooms".parse(), Ok(Generator::Rooms));
+    assert_eq!("bsp".parse(), Ok(Generator::Bsp));
+    assert_eq!("caves".parse(), Ok(Generator::Caves));
+    assert_eq!("drunkard".parse(), Ok(Generator::DrunkardsWalk));
+    assert!("maze".parse::<Generator>().is_err());
+}
@@ -0,0 +1,213 @@
+use rand::Rng;
+
+mod common;
+use common::*;
+
+use explore_the_maze::models::awareness::*;
+use explore_the_maze::models::connectivity::*;
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::maze::*;
+use explore_the_maze::models::util::*;
+
+#[test]
+fn same_seed_generates_the_same_maze_and_monsters() {
+    let first = Simulation::new(42);
+    let second = Simulation::new(42);
+
+    assert_eq!(first.game.maze, second.game.maze);
+    assert_eq!(first.entities.len(), second.entities.len());
+
+    for (a, b) in first.entities.iter().zip(second.entities.iter()) {
+        assert_eq!(a.get_pos(), b.get_pos());
+        assert_eq!(a.name, b.name);
+        assert_eq!(a.fighter, b.fighter);
+    }
+}
+
+#[test]
+fn different_seeds_generate_different_mazes() {
+    let first = Simulation::new(1);
+    let second = Simulation::new(2);
+
+    assert_ne!(first.game.maze, second.game.maze);
+}
+
+#[test]
+fn new_game_starts_with_the_fov_computed() {
+    let simulation = Simulation::new(7);
+    let (x, y) = simulation.player().get_pos();
+
+    assert!(simulation.game.fov.is_in_fov(x, y));
+    assert!(simulation.game.maze[x as usize][y as usize].is_explored);
+    assert!(!simulation.game.maze[x as usize][y as usize].blocked);
+}
+
+#[test]
+fn walls_block_the_player_but_the_turn_is_taken() {
+    let mut simulation = Simulation::new(3);
+    arena(&mut simulation, 10, 10, 20, 20);
+
+    let action = simulation.step(Command::Move(-1, 0));
+
+    assert_eq!(action, PlayerAction::TookTurn);
+    assert_eq!(simulation.player().get_pos(), (10, 10));
+    assert_eq!(simulation.turns, 1);
+}
+
+#[test]
+fn moving_explores_new_tiles() {
+    let mut simulation = Simulation::new(3);
+    arena(&mut simulation, 10, 10, 60, 12);
+    assert!(!simulation.game.maze[55][11].is_explored);
+
+    simulation.run(vec![Command::Move(1, 0); 40]);
+
+    assert_eq!(simulation.player().get_pos(), (50, 10));
+    assert!(simulation.game.maze[55][11].is_explored);
+}
+
+#[test]
+fn monster_in_fov_chases_and_attacks_the_player() {
+    let mut simulation = Simulation::new(3);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.entities.push(orc(15, 10));
+
+    // bump into the wall to wait while the orc comes closer
+    simulation.run(vec![Command::Move(-1, 0); 4]);
+    assert_eq!(simulation.entities[1].get_pos(), (11, 10));
+    assert_eq!(hp(simulation.player()), 30);
+
+    // now it is next to the player and attacks: 5 power - 2 defense
+    simulation.step(Command::Move(-1, 0));
+    assert_eq!(hp(simulation.player()), 27);
+}
+
+#[test]
+fn monster_paths_around_walls() {
+    let mut simulation = Simulation::new(3);
+    arena(&mut simulation, 10, 10, 20, 20);
+
+    // a chasm between the player and the orc - it blocks the way but not the sight
+    // the only way around is through the gap at the bottom (y >= 15)
+    for y in 10..15 {
+        simulation.game.maze[15][y] = Tile { blocked: true, block_sight: false, ..Tile::empty() };
+    }
+    simulation.entities[PLAYER].set_pos(12, 10);
+    simulation.entities.push(orc(18, 10));
+    compute_fov(&mut simulation.game, &simulation.entities);
+
+    // 5 steps down, 5 to the left, 5 back up
+    simulation.run(vec![Command::Move(0, -1); 15]);
+    let (x, y) = simulation.entities[1].get_pos();
+    assert_eq!((x - 12).abs() + (y - 10).abs(), 1);
+    assert_eq!(hp(simulation.player()), 30);
+
+    simulation.step(Command::Move(0, -1));
+    assert_eq!(hp(simulation.player()), 27);
+}
+
+#[test]
+fn monster_outside_fov_does_not_move() {
+    let mut simulation = Simulation::new(3);
+    arena(&mut simulation, 10, 10, 60, 12);
+    // asleep - a wandering one would stumble around whether it sees the player or not
+    let mut orc = orc(60, 12);
+    orc.awareness = Awareness::Asleep;
+    simulation.entities.push(orc);
+
+    simulation.run(vec![Command::Move(-1, 0); 3]);
+
+    assert_eq!(simulation.entities[1].get_pos(), (60, 12));
+}
+
+#[test]
+fn player_kills_a_monster() {
+    let mut simulation = Simulation::new(3);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.entities.push(orc(11, 10));
+
+    // 5 power - 0 defense => the orc dies after the second hit
+    simulation.run(vec![Command::Move(1, 0); 2]);
+
+    let remains = &simulation.entities[1];
+    assert!(!remains.is_alive);
+    assert!(!remains.is_blocking);
+    assert!(remains.fighter.is_none());
+    assert_eq!(remains.name, "remains of orc");
+
+    // it attacked only once, on its turn after the first hit
+    assert_eq!(hp(simulation.player()), 27);
+
+    // the player can walk over the remains
+    simulation.step(Command::Move(1, 0));
+    assert_eq!(simulation.player().get_pos(), (11, 10));
+}
+
+#[test]
+fn dead_player_cannot_act_but_can_exit() {
+    let mut simulation = Simulation::new(3);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.entities.push(orc(11, 10));
+    simulation.entities[PLAYER].fighter.as_mut().unwrap().hp = 1;
+
+    simulation.step(Command::Move(-1, 0));
+    assert!(simulation.is_over());
+
+    assert_eq!(simulation.step(Command::Move(0, 1)), PlayerAction::DidntTakeTurn);
+    assert_eq!(simulation.step(Command::Exit), PlayerAction::Exit);
+}
+
+#[test]
+fn scripted_commands_parse() {
+    assert_eq!("up".parse(), Ok(Command::Move(0, -1)));
+    assert_eq!(" left ".parse(), Ok(Command::Move(-1, 0)));
+    assert_eq!("exit".parse(), Ok(Command::Exit));
+    assert!("jump".parse::<Command>().is_err());
+}
+
+#[test]
+fn full_games_keep_the_game_state_consistent() {
+    let directions = [
+        Command::Move(0, -1),
+        Command::Move(0, 1),
+        Command::Move(-1, 0),
+        Command::Move(1, 0)
+    ];
+
+    for seed in 0..20 {
+        let mut simulation = Simulation::new(seed);
+        let mut rng = rng_from_seed(seed);
+
+        let script: Vec<Command> = (0..1000)
+            .map(|_| directions[rng.gen_range(0, directions.len())])
+            .collect();
+        let played = simulation.run(script);
+
+        // the script ends early only if the player died
+        assert!(played == 1000 || simulation.is_over());
+        assert_eq!(simulation.is_over(), hp(simulation.player()) <= 0);
+
+        for entity in &simulation.entities {
+            let (x, y) = entity.get_pos();
+
+            // nobody walks through walls
+            assert!(!simulation.game.maze[x as usize][y as usize].blocked);
+
+            // the dead do not fight and do not block
+            if entity.name.starts_with("remains of") {
+                assert!(!entity.is_alive);
+                assert!(entity.fighter.is_none());
+                assert!(!entity.is_blocking);
+            }
+
+            if let Some(fighter) = entity.fighter {
+                assert!(fighter.hp <= fighter.max_hp);
+            }
+        }
+
+        // nobody ends up standing on someone else
+        assert!(overlapping_spawns(&simulation.entities).is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+mod common;
+use common::*;
+
+use explore_the_maze::models::effects::*;
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::item::*;
+use explore_the_maze::models::maze::*;
+
+#[test]
+fn player_picks_up_the_item_under_him() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.entities.push(potion(11, 10));
+
+    // nothing under the player yet
+    assert_eq!(simulation.step(Command::PickUp), PlayerAction::DidntTakeTurn);
+
+    simulation.step(Command::Move(1, 0));
+    assert_eq!(simulation.step(Command::PickUp), PlayerAction::TookTurn);
+
+    assert_eq!(simulation.entities.len(), 1);
+    assert_eq!(simulation.game.inventory.len(), 1);
+    assert_eq!(simulation.game.inventory[0].item, Some(Item::Heal));
+}
+
+#[test]
+fn full_inventory_leaves_the_item_on_the_floor() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 20, 20);
+    for _ in 0..INVENTORY_SIZE {
+        simulation.game.inventory.push(potion(0, 0));
+    }
+    simulation.entities.push(potion(10, 10));
+
+    assert_eq!(simulation.step(Command::PickUp), PlayerAction::DidntTakeTurn);
+
+    assert_eq!(simulation.entities.len(), 2);
+    assert_eq!(simulation.game.inventory.len(), INVENTORY_SIZE);
+}
+
+#[test]
+fn healing_potion_heals_up_to_max_hp() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.game.inventory.push(potion(0, 0));
+    simulation.game.inventory.push(potion(0, 0));
+    simulation.entities[PLAYER].fighter.as_mut().unwrap().hp = 25;
+
+    assert_eq!(simulation.step(Command::UseItem(0, None)), PlayerAction::TookTurn);
+    assert_eq!(hp(simulation.player()), 25 + HEAL_AMOUNT);
+
+    // only 1 hp missing, the potion does not heal over the max
+    assert_eq!(simulation.step(Command::UseItem(0, None)), PlayerAction::TookTurn);
+    assert_eq!(hp(simulation.player()), 30);
+    assert!(simulation.game.inventory.is_empty());
+}
+
+#[test]
+fn healing_at_full_health_keeps_the_potion() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.game.inventory.push(potion(0, 0));
+
+    assert_eq!(simulation.step(Command::UseItem(0, None)), PlayerAction::DidntTakeTurn);
+    assert_eq!(simulation.game.inventory.len(), 1);
+
+    // no such item
+    assert_eq!(simulation.step(Command::UseItem(3, None)), PlayerAction::DidntTakeTurn);
+}
+
+#[test]
+fn items_are_placed_on_free_tiles() {
+    for seed in 0..50 {
+        let simulation = Simulation::new(seed);
+
+        for item in simulation.entities.iter().filter(|e| e.item.is_some()) {
+            let (x, y) = item.get_pos();
+            assert!(!simulation.game.maze[x as usize][y as usize].blocked);
+            assert!(!item.is_blocking);
+        }
+    }
+
+    // some seeds must place items at all
+    let items: usize = (0..50)
+        .map(|seed| Simulation::new(seed).entities.iter().filter(|e| e.item.is_some()).count())
+        .sum();
+    assert!(items > 0);
+}
+
+#[test]
+fn item_commands_parse() {
+    assert_eq!("pickup".parse(), Ok(Command::PickUp));
+    assert_eq!("use 2".parse(), Ok(Command::UseItem(2, None)));
+    assert!("use".parse::<Command>().is_err());
+    assert!("use x".parse::<Command>().is_err());
+    assert_eq!("use 1 12 7".parse(), Ok(Command::UseItem(1, Some((12, 7)))));
+    assert!("use 1 12".parse::<Command>().is_err());
+}
+
+#[test]
+fn lightning_strikes_the_closest_monster_in_fov() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities.push(orc(14, 10));
+    simulation.entities.push(orc(12, 12));
+    simulation.game.inventory.push(scroll(Item::Lightning));
+
+    assert_eq!(simulation.step(Command::UseItem(0, None)), PlayerAction::TookTurn);
+
+    // 40 damage kills the closer orc, the other one is untouched
+    assert!(!simulation.entities[2].is_alive);
+    assert_eq!(hp(&simulation.entities[1]), 10);
+    assert!(simulation.game.inventory.is_empty());
+}
+
+#[test]
+fn lightning_without_a_monster_in_range_keeps_the_scroll() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities.push(orc(10 + LIGHTNING_RANGE + 2, 10));
+    simulation.game.inventory.push(scroll(Item::Lightning));
+
+    assert_eq!(simulation.step(Command::UseItem(0, None)), PlayerAction::DidntTakeTurn);
+    assert_eq!(simulation.game.inventory.len(), 1);
+}
+
+#[test]
+fn fireball_burns_everything_around_the_target() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities.push(orc(16, 12));
+    simulation.entities.push(orc(17, 12));
+    simulation.entities.push(orc(26, 12));
+    simulation.game.inventory.push(scroll(Item::Fireball));
+
+    // needs a target
+    assert_eq!(simulation.step(Command::UseItem(0, None)), PlayerAction::DidntTakeTurn);
+    assert!(needs_target(Item::Fireball));
+
+    assert_eq!(simulation.step(Command::UseItem(0, Some((16, 12)))), PlayerAction::TookTurn);
+
+    // 12 damage kills both orcs within the radius, the far one and the player are not burned
+    assert!(!simulation.entities[1].is_alive);
+    assert!(!simulation.entities[2].is_alive);
+    assert!(simulation.entities[3].is_alive);
+    assert_eq!(hp(simulation.player()), 30);
+}
+
+#[test]
+fn fireball_cannot_be_aimed_outside_the_fov() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.game.inventory.push(scroll(Item::Fireball));
+
+    // a wall tile far from the room is not visible
+    assert_eq!(simulation.step(Command::UseItem(0, Some((70, 40)))), PlayerAction::DidntTakeTurn);
+    assert_eq!(simulation.game.inventory.len(), 1);
+}
+
+#[test]
+fn confused_monster_stumbles_around_and_recovers() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities[PLAYER].set_pos(10, 15);
+    simulation.entities.push(orc(11, 15));
+    simulation.game.inventory.push(scroll(Item::Confuse));
+
+    assert_eq!(simulation.step(Command::UseItem(0, Some((11, 15)))), PlayerAction::TookTurn);
+    assert!(has_effect(&simulation.entities[1], Effect::Confusion));
+
+    // confused monsters do not attack: wait (bump the wall) while it stumbles around
+    simulation.run(vec![Command::Move(-1, 0); CONFUSE_NUM_TURNS as usize - 1]);
+    assert_eq!(hp(simulation.player()), 30);
+
+    // then it remembers what it was doing
+    assert!(simulation.entities[1].effects.is_empty());
+    assert_eq!(simulation.entities[1].ai, Some(Ai::Basic));
+}
+
+#[test]
+fn confusion_needs_a_monster_within_range() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities.push(orc(10 + CONFUSE_RANGE + 2, 10));
+    simulation.game.inventory.push(scroll(Item::Confuse));
+
+    // an empty tile
+    assert_eq!(simulation.step(Command::UseItem(0, Some((12, 12)))), PlayerAction::DidntTakeTurn);
+
+    // a monster out of range
+    let far = (10 + CONFUSE_RANGE + 2, 10);
+    assert_eq!(simulation.step(Command::UseItem(0, Some(far))), PlayerAction::DidntTakeTurn);
+    assert_eq!(simulation.game.inventory.len(), 1);
+}
@@ -0,0 +1,113 @@
+use std::fs;
+
+mod common;
+use common::*;
+
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::keys::*;
+
+fn error_of(json: &str) -> String {
+    parse_key_bindings(json).expect_err("the key bindings should be rejected").to_string()
+}
+
+#[test]
+fn shipped_file_is_the_default_arrow_keys() {
+    let json = fs::read_to_string(KEYS_FILE).unwrap();
+    assert_eq!(parse_key_bindings(&json).unwrap(), KeyBindings::default());
+    assert_eq!(load_key_bindings("no/such/keys.json").unwrap(), KeyBindings::default());
+}
+
+#[test]
+fn presets_bind_their_directions() {
+    let numpad = KeyBindings::preset(Preset::Numpad);
+    assert_eq!(numpad.action("kp7"), Some(Action::UpLeft));
+    assert_eq!(numpad.action("kp3"), Some(Action::DownRight));
+    assert_eq!(numpad.action("kp5"), Some(Action::Wait));
+
+    let vi = KeyBindings::preset(Preset::Vi);
+    let moves: Vec<Option<(i32, i32)>> = ["h", "j", "k", "l", "y", "u", "b", "n"]
+        .iter()
+        .map(|key| vi.action(key).and_then(Action::direction))
+        .collect();
+    assert_eq!(moves, vec![
+        Some((-1, 0)), Some((0, 1)), Some((0, -1)), Some((1, 0)),
+        Some((-1, -1)), Some((1, -1)), Some((-1, 1)), Some((1, 1))
+    ]);
+
+    // the arrows and the commands work in every preset, the arrows preset has no diagonals
+    for preset in [Preset::Arrows, Preset::Numpad, Preset::Vi] {
+        let keys = KeyBindings::preset(preset);
+        assert_eq!(keys.action("up"), Some(Action::Up));
+        assert_eq!(keys.action("g"), Some(Action::PickUp));
+        assert_eq!(keys.action("escape"), Some(Action::Exit));
+    }
+    assert_eq!(KeyBindings::default().action("h"), None);
+}
+
+#[test]
+fn file_bindings_override_the_preset() {
+    let keys = parse_key_bindings(r#"{ "preset": "vi", "bindings": { "h": "help", "space": "wait", "kp9": "up_right" } }"#).unwrap();
+    assert_eq!(keys.action("h"), Some(Action::Help));
+    assert_eq!(keys.action("space"), Some(Action::Wait));
+    assert_eq!(keys.action("kp9"), Some(Action::UpRight));
+    assert_eq!(keys.action("l"), Some(Action::Right));
+}
+
+#[test]
+fn invalid_bindings_are_rejected() {
+    let err = error_of(r#"{ "preset": "vi", "bindings": { "kp10": "up" } }"#);
+    assert!(err.contains("bindings \"kp10\": unknown key"), "{}", err);
+
+    let err = error_of(r#"{ "preset": "emacs" }"#);
+    assert!(err.contains("emacs"), "{}", err);
+
+    let err = error_of(r#"{ "preset": "vi", "bindings": { "q": "quit" } }"#);
+    assert!(err.contains("quit"), "{}", err);
+
+    let err = error_of(r#"{ "preset": "arrows", "bindings": { "escape": "wait" } }"#);
+    assert!(err.contains("no key is bound to exit"), "{}", err);
+}
+
+#[test]
+fn help_lists_the_active_bindings() {
+    let keys = parse_key_bindings(r#"{ "preset": "vi", "bindings": { "w": "wait" } }"#).unwrap();
+    let help = keys.help();
+
+    let up = help.iter().find(|line| line.contains("move / attack up-left")).unwrap();
+    assert!(up.starts_with("y "), "{}", up);
+    let wait = help.iter().find(|line| line.contains("wait a turn")).unwrap();
+    assert!(wait.starts_with("., w "), "{}", wait);
+
+    // nothing is bound to the diagonals with the arrows, so the help does not mention them
+    assert!(!KeyBindings::default().help().iter().any(|line| line.contains("up-left")));
+}
+
+#[test]
+fn waiting_and_diagonal_moves_take_a_turn() {
+    let mut simulation = Simulation::new(3);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.entities.push(orc(14, 14));
+
+    assert_eq!(simulation.step(Command::Wait), PlayerAction::TookTurn);
+    assert_eq!(simulation.player().get_pos(), (10, 10));
+    assert_ne!(simulation.entities[1].get_pos(), (14, 14));
+
+    assert_eq!(simulation.step(Command::Move(1, 1)), PlayerAction::TookTurn);
+    assert_eq!(simulation.player().get_pos(), (11, 11));
+    assert_eq!("downright".parse(), Ok(Command::Move(1, 1)));
+    assert_eq!("wait".parse(), Ok(Command::Wait));
+}
+
+#[test]
+fn monsters_attack_diagonally() {
+    let mut simulation = Simulation::new(3);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.entities.push(orc(11, 11));
+
+    // 5 power - 2 defense
+    simulation.step(Command::Wait);
+    assert_eq!(simulation.entities[1].get_pos(), (11, 11));
+    assert_eq!(hp(simulation.player()), 27);
+}
@@ -0,0 +1,63 @@
+mod common;
+use common::*;
+
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::look::*;
+use explore_the_maze::models::maze::*;
+
+#[test]
+fn look_names_what_the_player_sees_on_a_tile() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 10, 10, 20, 12);
+    simulation.entities.push(potion(12, 10));
+    simulation.entities.push(orc(12, 10));
+    let (game, entities) = (&simulation.game, &simulation.entities);
+
+    // the orc stands on the potion - it is drawn on top, so it is named first (with what it is up to)
+    assert_eq!(names_under(12, 10, game, entities), vec!["orc (wandering)", "healing potion"]);
+    assert_eq!(describe_tile(12, 10, game, entities).unwrap(), "orc (wandering), healing potion (floor)");
+
+    assert_eq!(describe_tile(10, 10, game, entities).unwrap(), "go4ko (floor)");
+    assert_eq!(describe_tile(15, 11, game, entities).unwrap(), "floor");
+    assert_eq!(describe_tile(15, 9, game, entities).unwrap(), "wall");
+
+    // outside of the maze there is nothing to describe
+    assert_eq!(describe_tile(-1, 5, game, entities), None);
+    assert_eq!(describe_tile(MAZE_WIDTH, 5, game, entities), None);
+}
+
+#[test]
+fn look_does_not_reveal_what_is_out_of_sight() {
+    let mut simulation = Simulation::new(1);
+    // a corridor longer than the torch radius
+    arena(&mut simulation, 5, 10, 60, 10);
+    simulation.entities.push(orc(50, 10));
+    let (game, entities) = (&simulation.game, &simulation.entities);
+
+    assert!(names_under(50, 10, game, entities).is_empty());
+    assert_eq!(describe_tile(50, 10, game, entities).unwrap(), "unexplored");
+    assert_eq!(tile_name(50, 10, game), "unexplored");
+}
+
+#[test]
+fn look_remembers_the_stairs_but_not_the_monsters() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 5, 10, 60, 10);
+    simulation.entities.push(orc(8, 10));
+
+    let mut stairs = Entity::new(9, 10, '>', tcod::colors::WHITE, "stairs down", false);
+    stairs.always_visible = true;
+    simulation.entities.push(stairs);
+
+    // walk away until both are out of sight (the orc stays, it has no ai)
+    simulation.entities[1].ai = None;
+    simulation.run(vec![Command::Move(1, 0); 30]);
+    let (game, entities) = (&simulation.game, &simulation.entities);
+    assert!(!game.fov.is_in_fov(9, 10));
+
+    // explored, so the stairs are still drawn and named - the orc may have walked away
+    assert_eq!(describe_tile(9, 10, game, entities).unwrap(), "stairs down (floor)");
+    assert_eq!(describe_tile(8, 10, game, entities).unwrap(), "floor");
+}
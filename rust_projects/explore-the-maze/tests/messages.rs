@@ -0,0 +1,109 @@
+mod common;
+use common::*;
+
+use tcod::colors::*;
+
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::messages::*;
+
+#[test]
+fn messages_are_stamped_with_the_turn_they_happened_on() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 10, 10, 20, 12);
+    // an orc without an ai stays where it is
+    let mut orc = orc(13, 10);
+    orc.ai = None;
+    simulation.entities.push(orc);
+    assert_eq!(simulation.game.turn, 1);
+    assert_eq!(simulation.game.messages.messages[0].turn, 1);
+
+    // two steps, then the orc is next to the player
+    simulation.run(vec![Command::Move(1, 0), Command::Move(1, 0), Command::Move(1, 0)]);
+    assert_eq!(simulation.game.turn, 4);
+
+    let attack = simulation
+        .game
+        .messages
+        .messages
+        .iter()
+        .find(|message| message.text.starts_with("go4ko attacks"))
+        .unwrap();
+    assert_eq!(attack.turn, 3);
+    assert_eq!(attack.category, MessageCategory::Combat);
+
+    // no turn taken, the same turn goes on
+    simulation.step(Command::Descend);
+    assert_eq!(simulation.game.turn, 4);
+    assert_eq!(simulation.game.messages.messages.last().unwrap().turn, 4);
+}
+
+#[test]
+fn walks_count_every_step_as_a_turn() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 5, 10, 60, 10);
+
+    simulation.step(Command::Explore);
+    assert_eq!(simulation.game.turn, 1 + simulation.turns);
+}
+
+#[test]
+fn messages_are_filtered_by_category() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 10, 10, 20, 12);
+    simulation.entities.push(orc(11, 10));
+    simulation.entities.push(potion(10, 11));
+
+    simulation.step(Command::Move(1, 0));
+    simulation.step(Command::Move(0, 1));
+    simulation.step(Command::PickUp);
+    simulation.step(Command::Climb);
+
+    let messages = &simulation.game.messages;
+    let combat = messages.filtered(&[MessageCategory::Combat]);
+    let loot = messages.filtered(&[MessageCategory::Loot]);
+    let system = messages.filtered(&[MessageCategory::System]);
+
+    assert!(!combat.is_empty() && combat.iter().all(|m| m.text.contains("attacks")));
+    assert_eq!(loot.len(), 1);
+    assert!(loot[0].text.starts_with("You picked up"));
+    assert_eq!(system.last().unwrap().text, "There are no stairs up here.");
+
+    // everything, the oldest first
+    assert_eq!(messages.filtered(&MessageCategory::ALL).len(), messages.messages.len());
+    assert!(messages.filtered(&[]).is_empty());
+}
+
+#[test]
+fn the_log_keeps_only_the_newest_messages() {
+    let mut messages = Messages::new();
+    for i in 0..MAX_MESSAGES + 10 {
+        messages.add(format!("message {}", i), WHITE, MessageCategory::System);
+    }
+
+    assert_eq!(messages.messages.len(), MAX_MESSAGES);
+    assert_eq!(messages.messages[0].text, "message 10");
+    assert_eq!(messages.dropped, 10);
+    assert_eq!(messages.total(), MAX_MESSAGES + 10);
+
+    // the new messages can still be told apart once the old ones are dropped
+    let printed = messages.total();
+    messages.add("new", WHITE, MessageCategory::System);
+    let new: Vec<&str> = messages.since(printed).iter().map(|m| m.text.as_str()).collect();
+    assert_eq!(new, vec!["new"]);
+    assert_eq!(messages.since(0).len(), MAX_MESSAGES);
+}
+
+#[test]
+fn long_messages_wrap_on_spaces() {
+    assert_eq!(wrap_text("The orc attacks go4ko for 3 hit points.", 16), vec![
+        "The orc attacks",
+        "go4ko for 3 hit",
+        "points."
+    ]);
+    assert_eq!(wrap_text("short", 16), vec!["short"]);
+    assert_eq!(wrap_text("", 16), vec![""]);
+
+    // a word longer than the line is cut
+    assert_eq!(wrap_text("a abcdefghij", 4), vec!["a", "abcd", "efgh", "ij"]);
+}
@@ -0,0 +1,137 @@
+mod common;
+use common::*;
+
+use explore_the_maze::models::generators::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::maze::*;
+use explore_the_maze::models::pathfinding::*;
+use explore_the_maze::models::util::*;
+
+// every step of a path is one of the 4 directions onto a tile that is not a wall
+fn assert_walkable(start: (i32, i32), path: &[(i32, i32)], maze: &Maze) {
+    let mut prev = start;
+    for &(x, y) in path {
+        assert_eq!((x - prev.0).abs() + (y - prev.1).abs(), 1, "{:?} -> {:?}", prev, (x, y));
+        assert!(!maze[x as usize][y as usize].blocked);
+        prev = (x, y);
+    }
+}
+
+#[test]
+fn path_in_an_open_room_is_as_long_as_the_distance() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 10, 10, 30, 20);
+    let maze = &simulation.game.maze;
+
+    let path = find_path((10, 10), (30, 20), maze, &simulation.entities, false).unwrap();
+    assert_eq!(path.len(), 20 + 10);
+    assert_eq!(path.last(), Some(&(30, 20)));
+    assert_walkable((10, 10), &path, maze);
+
+    assert_eq!(find_path((10, 10), (10, 10), maze, &simulation.entities, false), Some(vec![]));
+}
+
+#[test]
+fn path_goes_around_walls_and_not_through_them() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 10, 10, 20, 14);
+
+    // a wall across the room with a gap at the bottom
+    for y in 10..14 {
+        simulation.game.maze[15][y] = Tile::wall();
+    }
+
+    let path = find_path((12, 10), (18, 10), &simulation.game.maze, &simulation.entities, false).unwrap();
+    assert_eq!(path.len(), 6 + 2 * 4);
+    assert!(path.contains(&(15, 14)));
+    assert_walkable((12, 10), &path, &simulation.game.maze);
+
+    // close the gap - no way through
+    simulation.game.maze[15][14] = Tile::wall();
+    assert_eq!(find_path((12, 10), (18, 10), &simulation.game.maze, &simulation.entities, false), None);
+    assert_eq!(DijkstraMap::new(&[(18, 10)], &simulation.game.maze, &simulation.entities, false).distance(12, 10), None);
+}
+
+#[test]
+fn path_avoids_entities_only_when_asked_and_always_reaches_the_target() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 10, 10, 20, 12);
+    simulation.entities.push(orc(12, 10));
+    simulation.entities.push(orc(15, 10));
+    let maze = &simulation.game.maze;
+    let entities = &simulation.entities;
+
+    // straight through the first orc
+    let through = find_path((10, 10), (14, 10), maze, entities, false).unwrap();
+    assert!(through.contains(&(12, 10)));
+    assert_eq!(through.len(), 4);
+
+    // around it
+    let around = find_path((10, 10), (14, 10), maze, entities, true).unwrap();
+    assert!(!around.contains(&(12, 10)));
+    assert_eq!(around.len(), 6);
+
+    // the second orc is the target - the path ends on him
+    let attack = find_path((10, 10), (15, 10), maze, entities, true).unwrap();
+    assert_eq!(attack.last(), Some(&(15, 10)));
+    assert!(!attack.contains(&(12, 10)));
+}
+
+#[test]
+fn a_star_finds_the_shortest_path_on_every_generator() {
+    let generators = [Generator::Rooms, Generator::Bsp, Generator::Caves, Generator::DrunkardsWalk];
+
+    for generator in generators {
+        for seed in 0..20 {
+            let Layout { maze, regions } = generator.generate(&mut rng_from_seed(seed));
+            let start = regions[0].center;
+            let map = DijkstraMap::new(&[start], &maze, &[], false);
+
+            for region in &regions {
+                let target = region.center;
+                let Some(path) = find_path(start, target, &maze, &[], false) else {
+                    assert_eq!(map.distance(target.0, target.1), None);
+                    continue;
+                };
+
+                assert_eq!(Some(path.len() as i32), map.distance(target.0, target.1), "{:?} seed {}", generator, seed);
+                assert_walkable(start, &path, &maze);
+            }
+        }
+    }
+}
+
+#[test]
+fn dijkstra_map_leads_to_the_closest_goal_and_away_from_it() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 10, 10, 30, 10);
+    let maze = &simulation.game.maze;
+
+    let map = DijkstraMap::new(&[(10, 10), (30, 10)], maze, &simulation.entities, false);
+    assert_eq!(map.distance(10, 10), Some(0));
+    assert_eq!(map.distance(14, 10), Some(4));
+    assert_eq!(map.distance(26, 10), Some(4));
+    assert_eq!(map.distance(20, 10), Some(10));
+    assert_eq!(map.distance(20, 11), None);
+
+    assert_eq!(map.step_towards(14, 10), Some((13, 10)));
+    assert_eq!(map.step_towards(26, 10), Some((27, 10)));
+    assert_eq!(map.step_towards(10, 10), None);
+
+    // fleeing goes to the middle, where both goals are the furthest
+    assert_eq!(map.step_away(14, 10), Some((15, 10)));
+    assert_eq!(map.step_away(20, 10), None);
+}
+
+#[test]
+fn dijkstra_map_with_entities_still_moves_the_one_asking() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 10, 10, 20, 10);
+    simulation.entities.push(orc(15, 10));
+
+    // the orc blocks his own tile, but knows which way to go
+    let map = DijkstraMap::new(&[(10, 10)], &simulation.game.maze, &simulation.entities, true);
+    assert_eq!(map.distance(15, 10), None);
+    assert_eq!(map.distance(16, 10), None);
+    assert_eq!(map.step_towards(15, 10), Some((14, 10)));
+}
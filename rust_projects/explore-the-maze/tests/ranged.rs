@@ -0,0 +1,202 @@
+mod common;
+use common::*;
+
+use explore_the_maze::models::definitions::*;
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::item::*;
+use explore_the_maze::models::maze::*;
+use explore_the_maze::models::ranged::*;
+
+// an item (or a monster) of the built in definitions
+fn item(name: &str, x: i32, y: i32) -> Entity {
+    Definitions::default().items.iter().find(|item| item.name == name).unwrap().spawn(x, y)
+}
+
+fn monster(name: &str, x: i32, y: i32) -> Entity {
+    Definitions::default().monsters.iter().find(|monster| monster.name == name).unwrap().spawn(x, y)
+}
+
+// the player in an empty room 10..=20 x 10..=20 with an equipped bow and a few arrows
+fn archer(arrows: u32) -> Simulation {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.entities[PLAYER].set_pos(10, 15);
+    compute_fov(&mut simulation.game, &simulation.entities);
+
+    simulation.game.inventory.push(item("bow", 0, 0));
+    simulation.step(Command::UseItem(0, None));
+    let mut quiver = item("arrows", 0, 0);
+    quiver.count = arrows;
+    simulation.game.inventory.push(quiver);
+    simulation
+}
+
+fn arrows_on_the_floor(simulation: &Simulation) -> Vec<(i32, i32)> {
+    simulation.entities.iter().filter(|e| e.item == Some(Item::Arrows)).map(|e| e.get_pos()).collect()
+}
+
+#[test]
+fn lines_go_on_past_the_target() {
+    assert_eq!(line((0, 0), (3, 0), 3), vec![(1, 0), (2, 0), (3, 0)]);
+    assert_eq!(line((5, 5), (3, 3), 4), vec![(4, 4), (3, 3), (2, 2), (1, 1)]);
+    assert_eq!(line((1, 1), (1, 1), 5), vec![]);
+
+    // every step is to a neighbouring tile and the line passes the target
+    let tiles = line((0, 0), (4, 2), 8);
+    assert_eq!(tiles.len(), 8);
+    assert!(tiles.contains(&(4, 2)) && tiles.contains(&(8, 4)));
+    assert!(tiles.windows(2).all(|pair| (pair[1].0 - pair[0].0).abs() <= 1 && (pair[1].1 - pair[0].1).abs() <= 1));
+}
+
+#[test]
+fn walls_and_fighters_block_the_line_of_fire() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.game.maze[14][12] = Tile::wall();
+    simulation.entities.push(orc(14, 16));
+    let (maze, entities) = (&simulation.game.maze, &simulation.entities);
+
+    assert!(is_line_of_fire_clear((10, 15), (18, 15), maze, entities));
+    assert!(!is_line_of_fire_clear((10, 12), (18, 12), maze, entities));
+    assert!(!is_line_of_fire_clear((10, 16), (18, 16), maze, entities));
+    // the one shot at does not block the shot
+    assert!(is_line_of_fire_clear((10, 16), (14, 16), maze, entities));
+}
+
+#[test]
+fn a_missed_arrow_lands_on_the_floor() {
+    // nobody there - the arrow flies its whole range, or until the wall stops it
+    let mut simulation = archer(3);
+    assert_eq!(simulation.step(Command::Fire(12, 15)), PlayerAction::TookTurn);
+    assert_eq!(arrows_on_the_floor(&simulation), vec![(10 + BOW_RANGE, 15)]);
+    assert_eq!(simulation.game.projectiles[0].path.len(), BOW_RANGE as usize);
+    assert_eq!(simulation.game.projectiles[0].glyph, '-');
+
+    simulation.game.maze[14][19] = Tile::wall();
+    simulation.step(Command::Fire(12, 17));
+    assert!(arrows_on_the_floor(&simulation).contains(&(13, 18)));
+    assert_eq!(simulation.game.projectiles[0].glyph, '\\');
+    assert_eq!(simulation.game.inventory[1].count, 1);
+
+    // the next turn shoots nothing
+    simulation.step(Command::Wait);
+    assert!(simulation.game.projectiles.is_empty());
+}
+
+#[test]
+fn a_blocked_tile_stops_the_arrow_even_if_it_can_be_seen_through() {
+    let mut simulation = archer(3);
+    simulation.game.maze[14][15] = Tile { blocked: true, block_sight: false, ..Tile::empty() };
+    assert!(!is_line_of_fire_clear((10, 15), (16, 15), &simulation.game.maze, &simulation.entities));
+
+    simulation.step(Command::Fire(16, 15));
+    assert_eq!(arrows_on_the_floor(&simulation), vec![(13, 15)]);
+    assert_eq!(simulation.game.projectiles[0].path.len(), 3);
+}
+
+#[test]
+fn an_arrow_hits_or_misses_the_monster() {
+    let mut simulation = archer(1);
+    simulation.entities.push(orc(13, 15));
+
+    simulation.step(Command::Fire(13, 15));
+    let message = simulation.game.messages.messages.iter().map(|m| m.text.as_str()).find(|text| text.contains("arrow"));
+
+    // a hit does the power of the bow (the orc has no defense), a miss flies on
+    if hp(&simulation.entities[1]) < 10 {
+        assert_eq!(hp(&simulation.entities[1]), 10 - BOW_POWER);
+        assert_eq!(message, Some("go4ko's arrow hits orc for 4 hit points."));
+        assert!(arrows_on_the_floor(&simulation).is_empty());
+    } else {
+        assert_eq!(message, Some("go4ko's arrow misses orc."));
+        assert_eq!(arrows_on_the_floor(&simulation).len(), 1);
+    }
+
+    // the last arrow is gone with its slot
+    assert_eq!(simulation.game.inventory.len(), 1);
+}
+
+#[test]
+fn the_bow_needs_to_be_equipped_and_arrows() {
+    let mut simulation = archer(1);
+    simulation.step(Command::UseItem(0, None));
+    assert_eq!(simulation.step(Command::Fire(12, 15)), PlayerAction::DidntTakeTurn);
+    assert_eq!(simulation.game.messages.messages.last().unwrap().text, "You have no bow equipped.");
+
+    simulation.step(Command::UseItem(0, None));
+    assert_eq!(simulation.step(Command::Fire(12, 15)), PlayerAction::TookTurn);
+    assert_eq!(simulation.step(Command::Fire(12, 15)), PlayerAction::DidntTakeTurn);
+    assert_eq!(simulation.game.messages.messages.last().unwrap().text, "You have no arrows left.");
+
+    // arrows are not used from the inventory, and a tile out of range is not shot at
+    let mut simulation = archer(5);
+    assert_eq!(simulation.step(Command::UseItem(1, None)), PlayerAction::DidntTakeTurn);
+    assert_eq!(simulation.step(Command::Fire(10 + BOW_RANGE + 1, 15)), PlayerAction::DidntTakeTurn);
+    assert_eq!(simulation.game.inventory[1].count, 5);
+}
+
+#[test]
+fn picked_up_arrows_go_onto_the_stack() {
+    let mut simulation = archer(3);
+    for _ in 0..INVENTORY_SIZE - 2 {
+        simulation.game.inventory.push(potion(0, 0));
+    }
+    let mut arrows = item("arrows", 10, 15);
+    arrows.count = 2;
+    simulation.entities.push(arrows);
+
+    // the inventory is full but the arrows take no new slot
+    assert_eq!(simulation.step(Command::PickUp), PlayerAction::TookTurn);
+    assert_eq!(simulation.game.inventory.len(), INVENTORY_SIZE);
+    assert_eq!(simulation.game.inventory[1].count, 5);
+    assert_eq!(simulation.game.messages.messages.last().unwrap().text, "You picked up arrows (5)!");
+}
+
+#[test]
+fn a_thrown_dagger_lands_where_it_stops() {
+    let mut simulation = archer(1);
+    simulation.game.inventory.push(item("dagger", 0, 0));
+    assert!(needs_target(Item::Dagger));
+
+    // too far to throw
+    assert_eq!(simulation.step(Command::UseItem(2, Some((10 + DAGGER_RANGE + 1, 15)))), PlayerAction::DidntTakeTurn);
+    assert_eq!(simulation.game.inventory.len(), 3);
+
+    assert_eq!(simulation.step(Command::UseItem(2, Some((12, 15)))), PlayerAction::TookTurn);
+    assert_eq!(simulation.game.inventory.len(), 2);
+    let dagger = simulation.entities.iter().find(|e| e.item == Some(Item::Dagger)).unwrap();
+    assert_eq!(dagger.get_pos(), (10 + DAGGER_RANGE, 15));
+}
+
+#[test]
+fn archer_goblins_shoot_until_they_run_out_of_arrows() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 20, 20);
+    simulation.entities[PLAYER].set_pos(10, 15);
+    simulation.entities.push(monster("archer goblin", 16, 15));
+    compute_fov(&mut simulation.game, &simulation.entities);
+    let ammo = |simulation: &Simulation| simulation.entities[1].fighter.and_then(|f| f.ranged).map_or(0, |r| r.ammo);
+    assert_eq!(ammo(&simulation), 5);
+
+    // in range - it stays where it is and shoots (3 power against 2 defense)
+    simulation.step(Command::Wait);
+    assert_eq!(simulation.entities[1].get_pos(), (16, 15));
+    assert_eq!(ammo(&simulation), 4);
+    assert!(hp(simulation.player()) == 29 || arrows_on_the_floor(&simulation).len() == 1);
+
+    // out of arrows it comes closer
+    if let Some(ranged) = simulation.entities[1].fighter.as_mut().and_then(|f| f.ranged.as_mut()) {
+        ranged.ammo = 0;
+    }
+    simulation.step(Command::Wait);
+    assert_eq!(simulation.entities[1].get_pos(), (15, 15));
+}
+
+#[test]
+fn fire_is_a_script_command() {
+    assert_eq!("fire 12 7".parse::<Command>(), Ok(Command::Fire(12, 7)));
+    assert_eq!(Command::Fire(12, 7).to_string(), "fire 12 7");
+    assert!("fire 12".parse::<Command>().is_err());
+}
@@ -0,0 +1,110 @@
+mod common;
+use common::*;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tcod::colors::*;
+
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::maze::*;
+use explore_the_maze::models::render::*;
+use explore_the_maze::models::tcod_db::*;
+use explore_the_maze::models::terminal_backend;
+
+// a backend without a screen - the tests read what is drawn from gui.root
+struct Dummy;
+
+impl Backend for Dummy {
+    fn present(&mut self, _screen: &Panel) {}
+
+    fn check_for_event(&mut self) -> Option<Event> {
+        None
+    }
+
+    fn wait_for_keypress(&mut self) -> KeyPress {
+        KeyPress { name: Some("escape".to_string()), alt: false }
+    }
+
+    fn window_closed(&self) -> bool {
+        false
+    }
+
+    fn toggle_fullscreen(&mut self) {}
+}
+
+#[test]
+fn panels_print_wrap_and_cut_off_text() {
+    let mut panel = Panel::new(10, 3);
+    panel.print(1, 0, "hello", WHITE);
+    assert_eq!(panel.line(0), " hello    ");
+
+    // drawn outside the panel is cut off
+    panel.print(7, 1, "maze", WHITE);
+    assert_eq!(panel.line(1), "       maz");
+
+    // the text is wrapped to the width, its own line breaks are kept
+    panel.clear();
+    assert_eq!(panel.print_rect(0, 0, 6, "a long text\nend", RED), 3);
+    assert_eq!(panel.line(0), "a long    ");
+    assert_eq!(panel.line(1), "text      ");
+    assert_eq!(panel.line(2), "end       ");
+    assert_eq!(panel.cell(0, 2).unwrap().foreground, RED);
+    assert_eq!(text_height("a long text\nend", 6), 3);
+    assert_eq!(panel.cell(10, 0), None);
+}
+
+#[test]
+fn blit_copies_the_glyphs_and_blends_the_background() {
+    let mut root = Panel::new(4, 4);
+    root.fill(0, 0, 4, 4, Color { r: 200, g: 0, b: 0 });
+
+    let mut menu = Panel::new(2, 2);
+    menu.print(0, 0, "ok", WHITE);
+    menu.fill(0, 0, 2, 2, Color { r: 0, g: 0, b: 100 });
+    root.blit(&menu, 1, 1, 0.5);
+
+    assert_eq!(root.line(1), " ok ");
+    assert_eq!(root.cell(1, 1).unwrap().background, Color { r: 100, g: 0, b: 50 });
+    // outside of the menu the root stays as it was
+    assert_eq!(root.cell(0, 0).unwrap().background, Color { r: 200, g: 0, b: 0 });
+}
+
+#[test]
+fn render_game_draws_the_maze_and_the_panel_on_the_root() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 10, 10, 20, 12);
+
+    let mut gui = Gui::new(Box::new(Dummy));
+    render_game(&mut gui, &simulation.game, &simulation.entities);
+
+    // the player on his tile of the maze, the HP bar in the panel under it
+    let player = &simulation.entities[PLAYER];
+    assert_eq!(gui.root.cell(10, 10).unwrap().glyph, '@');
+    let hp = player.fighter.unwrap().hp;
+    let max_hp = player.max_hp(&simulation.game);
+    assert!(gui.root.line(PANEL_Y + 1).contains(&format!("HP: {}/{}", hp, max_hp)));
+}
+
+#[test]
+fn backends_are_chosen_by_name() {
+    assert_eq!("tcod".parse::<BackendKind>(), Ok(BackendKind::Tcod));
+    assert_eq!("terminal".parse::<BackendKind>(), Ok(BackendKind::Terminal));
+    assert_eq!(
+        "sdl".parse::<BackendKind>(),
+        Err("unknown backend 'sdl', expected tcod or terminal".to_string())
+    );
+}
+
+#[test]
+fn terminal_keys_are_named_like_the_key_bindings() {
+    let name = |code| terminal_backend::key_name(KeyEvent::new(code, KeyModifiers::NONE));
+    assert_eq!(name(KeyCode::Up), Some("up".to_string()));
+    assert_eq!(name(KeyCode::Esc), Some("escape".to_string()));
+    assert_eq!(name(KeyCode::Char(' ')), Some("space".to_string()));
+    assert_eq!(name(KeyCode::Char('h')), Some("h".to_string()));
+    assert_eq!(name(KeyCode::F(1)), None);
+
+    let key = KeyPress { name: name(KeyCode::Char('>')), alt: false };
+    assert!(key.is(">"));
+    assert_eq!(key.char(), Some('>'));
+    assert_eq!(KeyPress { name: Some("escape".to_string()), alt: false }.char(), None);
+}
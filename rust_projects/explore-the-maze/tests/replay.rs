@@ -0,0 +1,125 @@
+use std::fs;
+
+mod common;
+use common::*;
+
+use explore_the_maze::models::definitions::*;
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::experience::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::replay::*;
+
+// a recorded game of a few turns and a walk
+fn recorded(seed: u64) -> Replay {
+    let mut simulation = Simulation::new(seed);
+    start_recording(&mut simulation.game);
+    simulation.run(vec![Command::Wait, Command::Move(1, 0), Command::Explore, Command::PickUp, Command::Move(0, -1)]);
+    Replay::from_game(&simulation.game, &simulation.entities).unwrap()
+}
+
+fn temp_file(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("explore-the-maze-{}-{}.replay", std::process::id(), name))
+}
+
+#[test]
+fn commands_are_written_in_the_script_format() {
+    let commands = [
+        Command::Move(0, -1),
+        Command::Move(1, 1),
+        Command::Move(0, 0),
+        Command::Wait,
+        Command::PickUp,
+        Command::UseItem(2, None),
+        Command::UseItem(0, Some((12, 5))),
+        Command::Descend,
+        Command::Climb,
+        Command::LevelUp(Stat::Power),
+        Command::Explore,
+        Command::Travel(40, 20),
+        Command::Exit
+    ];
+
+    for command in commands {
+        assert_eq!(command.to_string().parse::<Command>(), Ok(command));
+    }
+    assert_eq!(Command::Move(-1, 1).to_string(), "downleft");
+
+    // one tile at a time - a replay cannot jump the player through the walls (or out of the maze)
+    assert!("move 2 0".parse::<Command>().unwrap_err().contains("one tile at a time"));
+    assert!("move -50 0".parse::<Command>().is_err());
+    assert!("move 0 -1".parse::<Command>().is_ok());
+    assert_eq!(Command::UseItem(0, Some((12, 5))).to_string(), "use 0 12 5");
+}
+
+#[test]
+fn only_a_recorded_game_has_a_replay() {
+    let simulation = Simulation::new(3);
+    assert!(Replay::from_game(&simulation.game, &simulation.entities).is_none());
+
+    // a walk is recorded as its steps - down a hall longer than the player can see
+    let mut simulation = Simulation::new(3);
+    arena(&mut simulation, 10, 10, 60, 12);
+    start_recording(&mut simulation.game);
+    simulation.run(vec![Command::Wait, Command::Move(1, 0), Command::Explore]);
+    let replay = Replay::from_game(&simulation.game, &simulation.entities).unwrap();
+    assert_eq!(replay.seed, 3);
+    assert_eq!(replay.commands[..2], ["wait".to_string(), "right".to_string()]);
+    assert!(!replay.commands.contains(&"explore".to_string()));
+    assert!(replay.commands.len() > 4);
+}
+
+#[test]
+fn a_replay_plays_back_the_same_game() {
+    let replay = recorded(7);
+
+    let mut played = 0;
+    let (game, entities) = play_back(&replay, Definitions::default(), |_, _| {
+        played += 1;
+        true
+    });
+    assert_eq!(played, replay.commands.len());
+    assert!(verify_replay(&replay, &game, &entities).is_ok());
+
+    // stopped half way it is another game
+    let (game, entities) = play_back(&replay, Definitions::default(), |_, _| false);
+    assert!(verify_replay(&replay, &game, &entities).is_err());
+}
+
+#[test]
+fn a_changed_replay_does_not_match() {
+    let mut replay = recorded(7);
+    replay.commands.push("wait".to_string());
+
+    let (game, entities) = play_back(&replay, Definitions::default(), |_, _| true);
+    let err = verify_replay(&replay, &game, &entities).unwrap_err().to_string();
+    assert!(err.contains("the replay does not match"), "{}", err);
+    assert!(err.contains(&format!("{:016x} recorded", replay.checksum)), "{}", err);
+}
+
+#[test]
+fn replays_are_saved_and_loaded() {
+    let replay = recorded(11);
+    let path = temp_file("saved");
+    save_replay(&path, &replay).unwrap();
+    let loaded = load_replay(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.unwrap(), replay);
+}
+
+#[test]
+fn broken_replays_are_rejected() {
+    let mut replay = recorded(11);
+    replay.commands[1] = "jump".to_string();
+    let path = temp_file("broken");
+    save_replay(&path, &replay).unwrap();
+    let err = load_replay(&path).unwrap_err().to_string();
+    assert!(err.contains("commands[1]: unknown command 'jump'"), "{}", err);
+
+    replay.commands[1] = "wait".to_string();
+    replay.version = REPLAY_VERSION + 1;
+    save_replay(&path, &replay).unwrap();
+    let err = load_replay(&path).unwrap_err().to_string();
+    fs::remove_file(&path).unwrap();
+    assert!(err.contains("version"), "{}", err);
+}
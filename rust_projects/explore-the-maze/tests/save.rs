@@ -0,0 +1,101 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+mod common;
+use common::*;
+
+use explore_the_maze::models::definitions::*;
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::fov::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::maze::PLAYER;
+use explore_the_maze::models::messages::*;
+use explore_the_maze::models::save::*;
+
+// every test writes its own file so the tests can run in parallel
+fn save_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("explore-the-maze-{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn saved_game_loads_back_the_same() {
+    let path = save_path("roundtrip");
+    let mut simulation = Simulation::new(42);
+    simulation.run(vec![Command::Move(1, 0), Command::Move(0, 1), Command::Move(-1, 0)]);
+    simulation.step(Command::Fov(FovAlgorithm::Permissive));
+    simulation.game.messages.add("a message to remember", tcod::colors::LIGHT_BLUE, MessageCategory::Loot);
+    simulation.game.inventory.push(potion(0, 0));
+
+    save_game(&path, &simulation.game, &simulation.entities).unwrap();
+    let (game, entities) = load_game(&path, Definitions::default()).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    // maze with the fog of war, every entity with its fighter, ai and death callback, inventory and message log
+    assert_eq!(game.maze, simulation.game.maze);
+    assert_eq!(entities, simulation.entities);
+    assert_eq!(game.messages, simulation.game.messages);
+    assert_eq!(game.inventory, simulation.game.inventory);
+    assert_eq!(game.seed, 42);
+    assert_eq!(game.turn, simulation.game.turn);
+    assert_eq!(game.fov_algorithm, FovAlgorithm::Permissive);
+
+    // the FOV is rebuilt around the player
+    let (x, y) = entities[PLAYER].get_pos();
+    assert!(game.fov.is_in_fov(x, y));
+}
+
+#[test]
+fn loaded_game_plays_on_like_the_original() {
+    let path = save_path("continue");
+    let script = vec![Command::Move(0, -1), Command::Move(1, 0), Command::Move(0, 1), Command::Move(-1, 0)];
+
+    let mut original = Simulation::new(9);
+    original.run(script.clone());
+    save_game(&path, &original.game, &original.entities).unwrap();
+    reseed_like_saved(&mut original.game);
+
+    let (game, entities) = load_game(&path, Definitions::default()).unwrap();
+    fs::remove_file(&path).unwrap();
+    let mut loaded = Simulation { game, entities, turns: original.turns };
+
+    original.run(script.clone());
+    loaded.run(script);
+
+    assert_eq!(loaded.entities, original.entities);
+    assert_eq!(loaded.game.maze, original.game.maze);
+}
+
+#[test]
+fn save_file_of_another_version_is_rejected() {
+    let path = save_path("version");
+    let simulation = Simulation::new(1);
+    save_game(&path, &simulation.game, &simulation.entities).unwrap();
+
+    let json = fs::read_to_string(&path).unwrap();
+    let json = json.replacen(
+        &format!("\"version\":{}", SAVE_VERSION),
+        &format!("\"version\":{}", SAVE_VERSION + 1),
+        1,
+    );
+    fs::write(&path, json).unwrap();
+
+    let err = load_game(&path, Definitions::default()).err().unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert!(err.to_string().contains("version"));
+}
+
+#[test]
+fn missing_or_broken_save_file_is_an_error() {
+    // the menu tells a missing save file apart from a broken one by the io error
+    let err = load_game(save_path("missing"), Definitions::default()).err().unwrap();
+    assert_eq!(err.downcast_ref::<io::Error>().unwrap().kind(), io::ErrorKind::NotFound);
+
+    let path = save_path("broken");
+    fs::write(&path, "{ not json").unwrap();
+    let result = load_game(&path, Definitions::default());
+    fs::remove_file(&path).unwrap();
+
+    assert!(result.err().unwrap().downcast_ref::<io::Error>().is_none());
+}
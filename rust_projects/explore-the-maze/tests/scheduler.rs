@@ -0,0 +1,80 @@
+mod common;
+use common::*;
+
+use explore_the_maze::models::definitions::*;
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::headless::*;
+use explore_the_maze::models::maze::*;
+use explore_the_maze::models::scheduler::*;
+
+// a monster from the built in definitions
+fn monster(name: &str, x: i32, y: i32) -> Entity {
+    let definitions = Definitions::default();
+    definitions.monsters.iter().find(|m| m.name == name).unwrap().spawn(x, y)
+}
+
+#[test]
+fn monsters_get_their_speed_from_the_definitions() {
+    assert_eq!(speed(&monster("orc", 0, 0)), NORMAL_SPEED);
+    assert!(speed(&monster("bat", 0, 0)) > NORMAL_SPEED);
+    assert!(speed(&monster("troll", 0, 0)) < NORMAL_SPEED);
+
+    let json = std::fs::read_to_string(DEFINITIONS_FILE).unwrap();
+    let err = parse_definitions(&json.replacen("\"speed\": 200", "\"speed\": 0", 1)).unwrap_err().to_string();
+    assert!(err.contains("\"bat\": speed must be more than 0"), "{}", err);
+}
+
+#[test]
+fn a_bat_acts_twice_for_every_player_turn() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities[PLAYER].set_pos(10, 15);
+    simulation.entities.push(monster("bat", 18, 15));
+
+    simulation.step(Command::Wait);
+    assert_eq!(simulation.entities[1].get_pos(), (16, 15));
+    assert_eq!(simulation.game.turn, 2);
+}
+
+#[test]
+fn a_troll_skips_some_turns() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities[PLAYER].set_pos(10, 15);
+    simulation.entities.push(monster("troll", 18, 15));
+
+    // 75 energy a turn - 3 steps in 4 turns
+    simulation.run(vec![Command::Wait; 4]);
+    assert_eq!(simulation.entities[1].get_pos(), (15, 15));
+    assert_eq!(simulation.game.turn, 5);
+}
+
+#[test]
+fn actions_cost_the_player_energy() {
+    let mut simulation = Simulation::new(5);
+    arena(&mut simulation, 10, 10, 30, 20);
+    simulation.entities[PLAYER].set_pos(10, 15);
+    simulation.entities.push(orc(18, 15));
+    simulation.entities.push(potion(10, 15));
+    simulation.entities.push(potion(10, 15));
+    assert!(is_ready(simulation.player()));
+
+    // picking up is half an action, the turn passes and the half saved is kept for later
+    assert_eq!(simulation.step(Command::PickUp), PlayerAction::TookTurn);
+    assert_eq!(simulation.player().energy, ENERGY_THRESHOLD + PICK_UP_COST);
+    assert_eq!(simulation.entities[1].get_pos(), (17, 15));
+    assert_eq!(simulation.game.turn, 2);
+
+    // the second pick up uses the saved half - the orc has no time to move
+    assert_eq!(simulation.step(Command::PickUp), PlayerAction::TookTurn);
+    assert_eq!(simulation.player().energy, ENERGY_THRESHOLD);
+    assert_eq!(simulation.entities[1].get_pos(), (17, 15));
+    assert_eq!(simulation.game.turn, 2);
+    assert_eq!(simulation.game.inventory.len(), 2);
+
+    // nothing that did not take a turn costs anything
+    simulation.step(Command::Climb);
+    assert_eq!(simulation.game.turn, 2);
+    assert!(is_ready(simulation.player()));
+}
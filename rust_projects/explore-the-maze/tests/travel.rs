@@ -0,0 +1,140 @@
+mod common;
+use common::*;
+
+use explore_the_maze::models::engine::*;
+use explore_the_maze::models::entity::*;
+use explore_the_maze::models::headless::*;
+
+fn last_message(simulation: &Simulation) -> &str {
+    &simulation.game.messages.messages.last().unwrap().text
+}
+
+fn explored(simulation: &Simulation) -> usize {
+    simulation.game.maze.iter().flatten().filter(|tile| tile.is_explored).count()
+}
+
+#[test]
+fn explore_walks_until_everything_is_seen() {
+    let mut simulation = Simulation::new(1);
+    // a long corridor, only its start is seen at first
+    arena(&mut simulation, 5, 10, 60, 10);
+    assert!(!simulation.game.maze[60][10].is_explored);
+
+    assert_eq!(simulation.step(Command::Explore), PlayerAction::TookTurn);
+    assert!(simulation.game.maze[60][10].is_explored);
+    assert_eq!(last_message(&simulation), "There is nothing left to explore.");
+    assert!(simulation.turns > 0);
+
+    // the player only walked as far as he had to, to see the end
+    let (x, _) = simulation.player().get_pos();
+    assert!(x < 60 && x as u32 == 5 + simulation.turns);
+
+    // nothing more to do
+    let turns = simulation.turns;
+    assert_eq!(simulation.step(Command::Explore), PlayerAction::DidntTakeTurn);
+    assert_eq!(simulation.turns, turns);
+}
+
+#[test]
+fn explore_stops_when_a_monster_comes_into_view() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 5, 10, 60, 10);
+    simulation.entities.push(orc(50, 10));
+
+    simulation.step(Command::Explore);
+    assert_eq!(last_message(&simulation), "You spot the orc and stop.");
+    assert!(simulation.game.fov.is_in_fov(50, 10));
+
+    // the orc is still far away, the player did not walk up to it
+    let (x, _) = simulation.player().get_pos();
+    assert!(x < 45);
+
+    // and will not walk on with it in view
+    let turns = simulation.turns;
+    assert_eq!(simulation.step(Command::Explore), PlayerAction::DidntTakeTurn);
+    assert_eq!(last_message(&simulation), "Not with the orc in view!");
+    assert_eq!(simulation.turns, turns);
+}
+
+#[test]
+fn explore_stops_on_a_found_item() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 5, 10, 60, 10);
+    simulation.entities.push(potion(30, 10));
+
+    simulation.step(Command::Explore);
+    assert_eq!(simulation.player().get_pos(), (30, 10));
+    assert_eq!(last_message(&simulation), "You find a healing potion.");
+
+    // picking it up and exploring goes on from there
+    simulation.step(Command::PickUp);
+    simulation.step(Command::Explore);
+    assert_eq!(last_message(&simulation), "There is nothing left to explore.");
+}
+
+#[test]
+fn travel_walks_to_an_explored_tile_only() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 10, 10, 20, 14);
+    let explored_before = explored(&simulation);
+
+    assert_eq!(simulation.step(Command::Travel(18, 13)), PlayerAction::TookTurn);
+    assert_eq!(simulation.player().get_pos(), (18, 13));
+    assert_eq!(simulation.turns, 8 + 3);
+
+    // already there
+    assert_eq!(simulation.step(Command::Travel(18, 13)), PlayerAction::DidntTakeTurn);
+
+    // a wall or an unseen tile
+    assert_eq!(simulation.step(Command::Travel(30, 30)), PlayerAction::DidntTakeTurn);
+    assert_eq!(last_message(&simulation), "You cannot find a way there.");
+    assert_eq!(simulation.player().get_pos(), (18, 13));
+    assert!(explored(&simulation) >= explored_before);
+}
+
+#[test]
+fn travel_goes_around_monsters() {
+    let mut simulation = Simulation::new(1);
+    arena(&mut simulation, 10, 10, 20, 12);
+
+    // a sleeping orc without an ai - it is not a danger, just in the way
+    let mut orc = orc(15, 10);
+    orc.ai = None;
+    simulation.entities.push(orc);
+
+    simulation.step(Command::Travel(20, 10));
+    assert_eq!(simulation.player().get_pos(), (20, 10));
+    assert_eq!(hp(&simulation.entities[1]), 10);
+    assert_eq!(simulation.turns, 10 + 2);
+}
+
+#[test]
+fn explore_and_travel_parse() {
+    assert_eq!("explore".parse(), Ok(Command::Explore));
+    assert_eq!("travel 3 4".parse(), Ok(Command::Travel(3, 4)));
+    assert!("travel 3".parse::<Command>().is_err());
+}
+
+#[test]
+fn explore_a_whole_generated_floor() {
+    for seed in 0..10 {
+        let mut simulation = Simulation::new(seed);
+
+        // no monsters to stop the player
+        simulation.entities.retain(|entity| entity.ai.is_none());
+
+        // every item found stops the walk
+        for _ in 0..100 {
+            if simulation.step(Command::Explore) == PlayerAction::DidntTakeTurn {
+                break;
+            }
+        }
+
+        assert_eq!(last_message(&simulation), "There is nothing left to explore.", "seed {}", seed);
+        for column in &simulation.game.maze {
+            for tile in column {
+                assert!(tile.is_explored || tile.blocked);
+            }
+        }
+    }
+}